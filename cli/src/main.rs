@@ -81,6 +81,9 @@ fn main() {
         (@arg output_node: --("output-node") +takes_value
             "Override output nodes name (auto-detects otherwise).")
 
+        (@arg allow_unknown_ops: --("allow-unknown-ops")
+            "Load ONNX ops without a builder as placeholders instead of failing")
+
         (@arg skip_analyse: --("skip-analyse") "Skip analyse after model build")
         (@arg optimize: -O --optimize "Optimize after model load")
         (@arg pulse: --pulse +takes_value "Translate to pulse network")
@@ -280,7 +283,10 @@ impl Parameters {
             });
         let (graph, mut tract_model) = if format == "onnx" {
             let graph = tract_onnx::model::model_proto_for_path(&name)?;
-            let tract = tract_onnx::for_path(&name)?;
+            let options = tract_onnx::model::LoadOptions {
+                allow_unknown_ops: matches.is_present("allow_unknown_ops"),
+            };
+            let tract = tract_onnx::model::model_for_proto(&graph, &options)?;
             (SomeGraphDef::Onnx(graph), tract)
         } else {
             let graph = tract_tensorflow::model::graphdef_for_path(&name)?;
@@ -1,3 +1,4 @@
+use super::rules::SharedTensorProxy;
 use super::*;
 use crate::model::TVec;
 
@@ -19,8 +20,8 @@ pub fn infer_forward_concrete(
 
     // If we know the value of all the inputs, we can deduce everything.
     if let Some(stateless) = op.as_stateless() {
-        let output_value = stateless.eval(input_values)?.pop().unwrap();
-        return Ok(Some(tvec![output_value.into()]));
+        let output_values = stateless.eval(input_values)?;
+        return Ok(Some(output_values.into_iter().map(|v| v.into()).collect()));
     }
 
     Ok(None)
@@ -32,61 +33,11 @@ pub fn infer_shape_broadcasting(shapes: &[&ShapeFact]) -> TractResult<Option<Sha
         debug!("Can't infer shape for broadcasting operators when some inputs have an open shape.");
         return Ok(None);
     }
-
-    let bound = shapes
-        .iter()
-        .map(|s| s.rank().concretize().unwrap())
-        .max()
-        .unwrap() as usize;
-
-    let mut output_shape: TVec<DimFact> = tvec![];
-
-    for i in 0..bound {
-        let mut previous = None;
-        let mut unknown = 0;
-
-        for shape in shapes.iter() {
-            let rank = shape.rank().concretize().unwrap() as usize;
-            let shape: TVec<DimFact> = shape.dims().collect();
-            if i >= rank {
-                continue;
-            }
-
-            match shape[rank - i - 1] {
-                GenericFact::Any => unknown += 1,
-                GenericFact::Only(d) if d.is_one() => (),
-                GenericFact::Only(d) => {
-                    if previous.is_some() && previous != Some(d) {
-                        bail!(
-                            "Invalid shape (broadcasting): {:?} is not compatible with {:?}.",
-                            d,
-                            previous
-                        )
-                    } else {
-                        previous = Some(d)
-                    }
-                }
-            };
-        }
-
-        if unknown > 1 {
-            debug!("Can't infer shape (broadcasting): there are multiple unknown values at same index.");
-            return Ok(None);
-        } else if unknown == 1 && previous != None {
-            debug!("Can't infer shape (broadcasting): there are both unknown and known values at same index.");
-            return Ok(None);
-        } else if unknown == 1 && previous == None {
-            output_shape.push(GenericFact::Any);
-        } else if let Some(previous) = previous {
-            output_shape.push(GenericFact::Only(previous));
-        } else {
-            output_shape.push(GenericFact::Only(1.into()));
-        }
+    let mut output_shape = ShapeFact::closed(tvec![]);
+    for shape in shapes {
+        output_shape = output_shape.broadcast(shape)?;
     }
-
-    output_shape.reverse();
-
-    Ok(Some(ShapeFact::closed(output_shape)))
+    Ok(Some(output_shape))
 }
 
 /// Infers basic facts in the case of unary or binary operators.
@@ -126,7 +77,7 @@ pub fn most_specific_shape<'a, I: IntoIterator<Item = &'a ShapeFact>>(
     let mut best = None;
 
     for shape in iter {
-        if let Some(rank) = shape.rank().concretize() {
+        if let Some(rank) = shape.rank() {
             if prev_rank.is_some() && rank != prev_rank.unwrap() {
                 bail!("Rank mismatch between different shapes.");
             } else {
@@ -144,3 +95,44 @@ pub fn most_specific_shape<'a, I: IntoIterator<Item = &'a ShapeFact>>(
 
     Ok(best)
 }
+
+/// Encodes numpy-style broadcasting between the shapes of two inputs `a`
+/// and `b`, and the shape of the output `c`.
+///
+/// The output rank is the largest input rank, and the output shape is the
+/// `ShapeFact::broadcast` of the input shapes. Rules are expressed on whole
+/// shapes so that symbolic dimensions get bound.
+pub fn rules_for_binary_broadcast<'r, 'p: 'r>(
+    s: &mut Solver<'r>,
+    a: &'p SharedTensorProxy,
+    b: &'p SharedTensorProxy,
+    c: &'p SharedTensorProxy,
+) -> InferenceResult {
+    s.given_2(&a.rank, &b.rank, move |s, a_rank: i32, b_rank: i32| {
+        let rank = a_rank.max(b_rank) as usize;
+        let (a_rank, b_rank) = (a_rank as usize, b_rank as usize);
+        s.equals(&c.rank, rank as i32)?;
+        s.with(&a.shape, move |s, a_shape| {
+            s.with(&b.shape, move |s, b_shape| {
+                let a_shape = ShapeFact::closed((0..a_rank).map(|ix| a_shape.dim(ix)).collect());
+                let b_shape = ShapeFact::closed((0..b_rank).map(|ix| b_shape.dim(ix)).collect());
+                s.equals(&c.shape, a_shape.broadcast(&b_shape)?)
+            })
+        })?;
+        // the leading axes only the input of highest rank has are the output's
+        s.with(&c.shape, move |s, c_shape| {
+            let longest = if a_rank >= b_rank { a } else { b };
+            let exclusive = rank - a_rank.min(b_rank);
+            let dims = (0..rank)
+                .map(|ix| {
+                    if ix < exclusive {
+                        c_shape.dim(ix)
+                    } else {
+                        GenericFact::Any
+                    }
+                })
+                .collect();
+            s.equals(&longest.shape, ShapeFact::closed(dims))
+        })
+    })
+}
@@ -0,0 +1,328 @@
+use std::borrow::BorrowMut;
+use std::path::Path;
+
+use serde_json::Value;
+
+use super::Analyser;
+use crate::model::*;
+use crate::ops::prelude::*;
+
+/// Version of the schema produced by `Analyser::to_json`, to be bumped on
+/// any incompatible change.
+pub const JSON_SCHEMA_VERSION: u32 = 1;
+
+/// Renders a dimension as a number, a symbol name, or a `{"sym", "mul",
+/// "add"}` object for affine expressions of a symbol.
+fn dim_to_json(dim: &DimFact) -> TractResult<Value> {
+    Ok(match dim {
+        GenericFact::Only(d) => {
+            if let Some(d) = d.as_const() {
+                json!(d)
+            } else if let Some(s) = d.as_sym() {
+                json!(s.to_string())
+            } else if let Some((s, mul, add)) = d.as_affine() {
+                json!({ "sym": s.to_string(), "mul": mul, "add": add })
+            } else {
+                bail!("Can not serialize dimension {:?}", d)
+            }
+        }
+        GenericFact::Any => Value::Null,
+    })
+}
+
+fn fact_to_json(fact: &TensorFact) -> TractResult<Value> {
+    let value = match fact.value {
+        ValueFact::Only(ref t) => serde_json::to_value(t).map_err(|e| format!("{}", e))?,
+        _ => Value::Null,
+    };
+    Ok(json!({
+        "datum_type": fact.datum_type.concretize().map(|dt| format!("{:?}", dt)),
+        "shape": fact
+            .shape
+            .dims()
+            .map(|d| dim_to_json(&d))
+            .collect::<TractResult<Vec<_>>>()?,
+        "open": fact.shape.is_open(),
+        "value": value,
+    }))
+}
+
+fn dim_from_json(value: &Value) -> TractResult<DimFact> {
+    match value {
+        Value::Null => Ok(GenericFact::Any),
+        Value::Number(n) => match n.as_i64() {
+            Some(d) => Ok(d.to_dim().into()),
+            None => bail!("Invalid dimension {}", n),
+        },
+        Value::String(s) => Ok(TDim::sym(sym_from_json(s)?).into()),
+        Value::Object(o) => {
+            let sym = o
+                .get("sym")
+                .and_then(|s| s.as_str())
+                .ok_or_else(|| format!("Invalid dimension {}", value))?;
+            let coef = |key: &str| {
+                o.get(key)
+                    .and_then(|c| c.as_i64())
+                    .ok_or_else(|| format!("Invalid dimension {}", value))
+            };
+            let dim = TDim::sym(sym_from_json(sym)?) * coef("mul")? as i32 + coef("add")? as i32;
+            Ok(dim.into())
+        }
+        _ => bail!("Unsupported dimension {}", value),
+    }
+}
+
+fn sym_from_json(s: &str) -> TractResult<char> {
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Ok(c),
+        _ => bail!("Invalid dimension symbol {:?}", s),
+    }
+}
+
+/// Parses back a fact dumped by `fact_to_json`. Values are not restored, as
+/// constants get recomputed by the analysis anyway.
+fn fact_from_json(value: &Value) -> TractResult<TensorFact> {
+    let mut fact = TensorFact::default();
+    if let Some(dt) = value["datum_type"].as_str() {
+        fact = fact.with_datum_type(dt.parse()?);
+    }
+    let dims = value["shape"]
+        .as_array()
+        .ok_or_else(|| format!("Invalid shape in fact {}", value))?
+        .iter()
+        .map(dim_from_json)
+        .collect::<TractResult<TVec<_>>>()?;
+    let shape = if value["open"].as_bool().unwrap_or(false) {
+        ShapeFact::open(dims)
+    } else {
+        ShapeFact::closed(dims)
+    };
+    Ok(fact.with_shape(shape))
+}
+
+impl<M: BorrowMut<Model>> Analyser<M> {
+    /// Saves the analysed graph to `path`, in the format of `to_json`.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> TractResult<()> {
+        std::fs::write(path, self.to_json()?)?;
+        Ok(())
+    }
+
+    /// Restores the facts saved by `save` into the model, sparing a full
+    /// analysis when reloading the same model.
+    ///
+    /// Ops can not be serialized, so the model must be rebuilt beforehand,
+    /// and match the saved nodes by name, op and inputs. The restored facts
+    /// are then checked by an analysis pass on a copy of the model, which
+    /// fails if they are stale and leaves the model untouched.
+    ///
+    /// Returns the edges which were restored.
+    pub fn load_facts<P: AsRef<Path>>(&mut self, path: P) -> TractResult<Vec<OutletId>> {
+        let doc: Value =
+            serde_json::from_str(&std::fs::read_to_string(path)?).map_err(|e| format!("{}", e))?;
+        if doc["version"] != json!(JSON_SCHEMA_VERSION) {
+            bail!(
+                "Unsupported schema version {}, expected {}",
+                doc["version"],
+                JSON_SCHEMA_VERSION
+            )
+        }
+        let saved_nodes = doc["nodes"]
+            .as_array()
+            .ok_or("Saved analysis has no nodes")?;
+        {
+            let model = self.model.borrow();
+            if saved_nodes.len() != model.nodes().len() {
+                bail!(
+                    "Saved analysis has {} nodes, model has {}",
+                    saved_nodes.len(),
+                    model.nodes().len()
+                )
+            }
+            for (node, saved) in model.nodes().iter().zip(saved_nodes.iter()) {
+                if saved["name"] != json!(node.name)
+                    || saved["op"] != json!(node.op().name())
+                    || saved["inputs"] != json!(node.inputs)
+                {
+                    bail!(
+                        "Saved node {} does not match model node {}",
+                        saved,
+                        node.name
+                    )
+                }
+            }
+        }
+        let mut candidate = self.model.borrow().clone();
+        let mut restored = vec![];
+        for edge in doc["edges"]
+            .as_array()
+            .ok_or("Saved analysis has no edges")?
+        {
+            let node = edge["from"]["node"].as_u64();
+            let slot = edge["from"]["slot"].as_u64();
+            let outlet = match (node, slot) {
+                (Some(node), Some(slot)) => OutletId::new(node as usize, slot as usize),
+                _ => bail!("Invalid edge {}", edge),
+            };
+            let fact = fact_from_json(&edge["fact"])?;
+            candidate.set_fact(outlet, fact)?;
+            restored.push(outlet);
+        }
+        Analyser::with_valid_plan(&mut candidate, self.plan.clone())?
+            .analyse()
+            .map_err(|e| format!("Saved facts do not match the model: {}", e))?;
+        *self.model.borrow_mut() = candidate;
+        self.saturated.clear();
+        Ok(restored)
+    }
+
+    /// Dumps the analysed graph to json, for external tooling.
+    ///
+    /// The output holds the nodes, the edges with their inferred facts (unknown
+    /// dimensions rendered as `null`), and the evaluation order of the nodes.
+    /// Symbolic dimensions that are not affine in a single symbol can not be
+    /// rendered, and make this fail.
+    pub fn to_json(&self) -> TractResult<String> {
+        let model = self.model.borrow();
+        let nodes = model
+            .nodes()
+            .iter()
+            .map(|n| {
+                json!({
+                    "id": n.id,
+                    "name": n.name,
+                    "op": n.op().name(),
+                    "inputs": n.inputs,
+                })
+            })
+            .collect::<Vec<_>>();
+        let mut edges = vec![];
+        for node in model.nodes() {
+            for (slot, outlet) in node.outputs.iter().enumerate() {
+                edges.push(json!({
+                    "from": OutletId::new(node.id, slot),
+                    "to": outlet.successors,
+                    "fact": fact_to_json(&outlet.fact)?,
+                }));
+            }
+        }
+        let doc = json!({
+            "version": JSON_SCHEMA_VERSION,
+            "nodes": nodes,
+            "edges": edges,
+            "plan": model.eval_order()?,
+        });
+        Ok(serde_json::to_string(&doc).map_err(|e| format!("{}", e))?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::dsl::ModelDsl;
+
+    fn model() -> Model {
+        let mut model = Model::default();
+        model
+            .add_source_fact(
+                "a",
+                TensorFact::dt_shape(DatumType::F32, shapefact!(S, _, 3)),
+            )
+            .unwrap();
+        model
+            .chain("abs", Box::new(crate::ops::math::Abs::default()))
+            .unwrap();
+        model
+    }
+
+    #[test]
+    fn dump_facts() {
+        let mut model = model();
+        let mut analyser = Analyser::new(&mut model).unwrap();
+        analyser.analyse().unwrap();
+        let json: Value = serde_json::from_str(&analyser.to_json().unwrap()).unwrap();
+        assert_eq!(json["version"], json!(JSON_SCHEMA_VERSION));
+        assert_eq!(json["nodes"][1]["op"], json!("Abs"));
+        assert_eq!(json["plan"], json!([0, 1]));
+        assert_eq!(
+            json["edges"][1]["fact"],
+            json!({
+                "datum_type": "F32",
+                "shape": ["S", null, 3],
+                "open": false,
+                "value": null,
+            })
+        );
+    }
+
+    #[test]
+    fn save_and_load_facts() {
+        let path = std::env::temp_dir().join(format!("tract-facts-{}.json", std::process::id()));
+        let mut model = model();
+        Analyser::new(&mut model).unwrap().analyse().unwrap();
+        Analyser::new(&mut model).unwrap().save(&path).unwrap();
+
+        let mut reloaded = Model::default();
+        reloaded.add_source("a").unwrap();
+        reloaded
+            .chain("abs", Box::new(crate::ops::math::Abs::default()))
+            .unwrap();
+        let restored = Analyser::new(&mut reloaded)
+            .unwrap()
+            .load_facts(&path)
+            .unwrap();
+        assert_eq!(restored, vec![OutletId::new(0, 0), OutletId::new(1, 0)]);
+        for outlet in restored {
+            assert_eq!(reloaded.fact(outlet).unwrap(), model.fact(outlet).unwrap());
+        }
+
+        let mut other = Model::default();
+        other.add_source("a").unwrap();
+        other
+            .chain("neg", Box::new(crate::ops::math::Neg::default()))
+            .unwrap();
+        assert!(Analyser::new(&mut other)
+            .unwrap()
+            .load_facts(&path)
+            .is_err());
+
+        let mut stale = Model::default();
+        stale
+            .add_source_fact("a", TensorFact::dt(DatumType::I32))
+            .unwrap();
+        stale
+            .chain("abs", Box::new(crate::ops::math::Abs::default()))
+            .unwrap();
+        assert!(Analyser::new(&mut stale)
+            .unwrap()
+            .load_facts(&path)
+            .is_err());
+        assert_eq!(
+            stale.fact(OutletId::new(0, 0)).unwrap(),
+            &TensorFact::dt(DatumType::I32)
+        );
+        assert_eq!(
+            stale.fact(OutletId::new(1, 0)).unwrap(),
+            &TensorFact::default()
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn symbolic_dims_round_trip() {
+        let dims: TVec<DimFact> = tvec!(
+            TDim::sym('N').into(),
+            (TDim::sym('N') * 2 + 1).into(),
+            GenericFact::Any,
+            4.to_dim().into()
+        );
+        for dim in dims {
+            let json = dim_to_json(&dim).unwrap();
+            assert_eq!(dim_from_json(&json).unwrap(), dim);
+        }
+        let fact = TensorFact::dt_shape(DatumType::F32, vec![TDim::sym('N'), 3.to_dim()]);
+        assert_eq!(fact_from_json(&fact_to_json(&fact).unwrap()).unwrap(), fact);
+        let ratio: DimFact = (TDim::sym('N') / 2).into();
+        assert!(dim_to_json(&ratio).is_err());
+    }
+}
@@ -46,7 +46,7 @@ macro_rules! valuefact {
     };
     ($arg:expr) => {{
         let fact: $crate::analyser::types::ValueFact =
-            $crate::analyser::types::GenericFact::Only($arg);
+            $crate::analyser::types::ValueFact::Only($arg);
         fact
     }};
 }
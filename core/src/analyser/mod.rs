@@ -1,5 +1,12 @@
 use std::borrow::BorrowMut;
+#[cfg(feature = "parallel")]
+use std::collections::BTreeMap;
 use std::collections::BTreeSet;
+#[cfg(feature = "profile")]
+use std::time::Duration;
+use std::time::Instant;
+
+use bit_set::BitSet;
 
 use crate::model::*;
 use crate::ops::prelude::*;
@@ -13,31 +20,362 @@ pub mod helpers;
 #[macro_use]
 pub mod rules;
 
+#[cfg(feature = "serialize")]
+mod json;
+#[cfg(feature = "serialize")]
+pub use self::json::JSON_SCHEMA_VERSION;
+
+/// Number of consecutive rounds without any information gained after which a
+/// still changing analysis is considered to be oscillating. A round is as
+/// many passes as there are nodes in the model.
+const MAX_STALE_ROUNDS: usize = 3;
+
+/// Counters accumulated by an analyser over all its runs.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct AnalyserStats {
+    /// Number of node analyses performed.
+    pub total_passes: usize,
+    /// Number of edge facts narrowed.
+    pub total_edge_updates: usize,
+    /// Number of nodes visited, including the saturated ones that were
+    /// skipped.
+    pub total_steps: usize,
+}
+
 /// A graph analyser, along with its current state.
 pub struct Analyser<M: BorrowMut<Model>> {
     model: M,
+    /// Number of passes made by the current run, a pass being the analysis
+    /// of a single node.
+    pub current_pass: usize,
+    /// Number of steps of the current run skipped because the node was
+    /// saturated, i.e. all its input and output facts were fully determined.
+    pub skipped_steps: usize,
+    stats: AnalyserStats,
+    saturated: BitSet,
+    history: HashMap<OutletId, Vec<(usize, TensorFact)>>,
+    nodes_by_name: HashMap<String, usize>,
+    plan: Vec<usize>,
+    value_tolerance: f32,
+    #[cfg(feature = "profile")]
+    op_timings: HashMap<String, Duration>,
 }
 
 impl<M: BorrowMut<Model>> Analyser<M> {
     pub fn new(model: M) -> TractResult<Analyser<M>> {
-        Ok(Analyser { model })
+        if model.borrow().outputs()?.is_empty() {
+            let sinks = detect_outputs(model.borrow())?;
+            if sinks.is_empty() {
+                bail!("Model has no output, and no sink node to use instead (is it cyclic?)")
+            }
+            let names: Vec<&str> = sinks
+                .iter()
+                .map(|&n| &*model.borrow().node(n).name)
+                .collect();
+            bail!("Model has no output. Candidate sinks: {}", names.join(", "))
+        }
+        let plan = model.borrow().eval_order()?;
+        Self::with_valid_plan(model, plan)
+    }
+
+    /// Builds an analyser covering only the nodes needed to compute
+    /// `targets`, for models whose outputs are not set.
+    pub fn for_targets(model: M, targets: &[usize]) -> TractResult<Analyser<M>> {
+        let plan = {
+            let model = model.borrow();
+            if let Some(&target) = targets.iter().find(|&&t| t >= model.nodes().len()) {
+                bail!("Target node {} does not exist", target)
+            }
+            let inputs: Vec<usize> = model.inputs()?.iter().map(|i| i.node).collect();
+            eval_order_for_nodes(model.nodes(), &inputs, targets)?
+        };
+        Self::with_valid_plan(model, plan)
+    }
+
+    /// Builds an analyser reusing an evaluation order computed beforehand,
+    /// for instance by a `SimplePlan` of the same model.
+    ///
+    /// The plan must be a topological order of the nodes, and reach all the
+    /// outputs of the model.
+    pub fn with_plan(model: M, plan: Vec<usize>) -> TractResult<Analyser<M>> {
+        {
+            let model = model.borrow();
+            let mut done = BitSet::with_capacity(model.nodes().len());
+            for &node in &plan {
+                if node >= model.nodes().len() {
+                    bail!("Plan refers to node {}, which does not exist", node)
+                }
+                if let Some(input) = model
+                    .node(node)
+                    .inputs
+                    .iter()
+                    .find(|i| !done.contains(i.node))
+                {
+                    bail!(
+                        "Plan is not a topological order: node {} comes before its input {}",
+                        node,
+                        input.node
+                    )
+                }
+                done.insert(node);
+            }
+            if let Some(output) = model.outputs()?.iter().find(|o| !done.contains(o.node)) {
+                bail!("Plan does not reach output node {}", output.node)
+            }
+        }
+        Self::with_valid_plan(model, plan)
+    }
+
+    fn with_valid_plan(model: M, plan: Vec<usize>) -> TractResult<Analyser<M>> {
+        let nodes_by_name = model
+            .borrow()
+            .nodes()
+            .iter()
+            .map(|n| (n.name.clone(), n.id))
+            .collect();
+        Ok(Analyser {
+            model,
+            current_pass: 0,
+            skipped_steps: 0,
+            stats: AnalyserStats::default(),
+            saturated: BitSet::new(),
+            history: HashMap::new(),
+            nodes_by_name,
+            plan,
+            value_tolerance: 0.0,
+            #[cfg(feature = "profile")]
+            op_timings: HashMap::new(),
+        })
+    }
+
+    /// Lets concrete float values inferred for the same edge unify when they
+    /// differ by up to `tolerance`, instead of requiring exact equality.
+    pub fn with_value_tolerance(self, tolerance: f32) -> Analyser<M> {
+        Analyser {
+            value_tolerance: tolerance,
+            ..self
+        }
+    }
+
+    /// Counters accumulated since the analyser was built.
+    pub fn stats(&self) -> AnalyserStats {
+        self.stats
+    }
+
+    /// Recomputes the evaluation order from the model.
+    pub fn reset_plan(&mut self) -> TractResult<()> {
+        self.plan = self.model.borrow().eval_order()?;
+        Ok(())
     }
 
     /// Runs the entire analysis at once.
     pub fn analyse(&mut self) -> TractResult<()> {
-        let mut nodes_to_visit: BTreeSet<usize> =
-            self.model.borrow().eval_order()?.iter().cloned().collect();
+        self.run_with_timeout(usize::max_value(), None)?;
+        Ok(())
+    }
+
+    /// Propagates pulsed facts through the whole graph, starting from the
+    /// sources streaming on `stream_axis` by pulses of `pulse`.
+    ///
+    /// Returns the ops of each node pulsified chain, in evaluation order.
+    /// The last op of a chain carries the pulsed facts of the node outputs,
+    /// and their `delay`.
+    pub fn pulsify_all(
+        &self,
+        stream_axis: usize,
+        pulse: usize,
+    ) -> TractResult<Vec<crate::pulse::PulsifiedOp>> {
+        use crate::ops::source::Source;
+        use crate::pulse::{PulsedTensorFact, PulsifiedOp};
+        let model = self.model.borrow();
+        let mut facts: HashMap<OutletId, PulsedTensorFact> = HashMap::new();
+        let mut pulsified = vec![];
+        for &n in &self.plan {
+            let node = model.node(n);
+            let chain = if node.op_as::<Source>().is_some() {
+                let fact = PulsedTensorFact::from_tensor_fact_pulse(&node.outputs[0].fact, pulse)
+                    .map_err(|e| format!("Pulsifying source {}: {}", node.name, e))?;
+                if fact.axis != stream_axis {
+                    bail!(
+                        "Source {} streams along axis {}, expected {}",
+                        node.name,
+                        fact.axis,
+                        stream_axis
+                    )
+                }
+                vec![PulsifiedOp::new(node.op.clone(), tvec!(fact))]
+            } else {
+                let inputs = node
+                    .inputs
+                    .iter()
+                    .map(|i| {
+                        facts
+                            .get(i)
+                            .ok_or_else(|| format!("No pulsed fact for input {:?}", i).into())
+                    })
+                    .collect::<TractResult<_>>()?;
+                node.op()
+                    .pulsify(inputs)
+                    .map_err(|e| format!("Pulsifying {}: {}", node.name, e))?
+            };
+            let last = chain
+                .last()
+                .ok_or_else(|| format!("Pulsifying {} produced no op", node.name))?;
+            for (ix, fact) in last.outputs.iter().enumerate() {
+                fact.validate()?;
+                facts.insert(OutletId::new(n, ix), fact.clone());
+            }
+            pulsified.extend(chain);
+        }
+        Ok(pulsified)
+    }
+
+    /// Runs the analysis until it reaches a fixpoint, or stops after
+    /// `max_passes` passes or once `deadline` is over.
+    ///
+    /// Returns Ok(true) if the analysis converged, Ok(false) if it was
+    /// interrupted. Facts inferred so far are kept in both cases.
+    pub fn run_with_timeout(
+        &mut self,
+        max_passes: usize,
+        deadline: Option<Instant>,
+    ) -> TractResult<bool> {
+        let nodes_to_visit = self.plan.iter().cloned().collect();
+        self.run_nodes(nodes_to_visit, max_passes, deadline)
+    }
+
+    /// Narrows the fact of an edge with a hint, and returns the edges that
+    /// were changed.
+    ///
+    /// A hint contradicting the fact already inferred for the edge is an
+    /// error naming the node, the hint and the inferred fact.
+    pub fn hint(&mut self, outlet: OutletId, fact: &TensorFact) -> TractResult<Vec<OutletId>> {
+        let (old_fact, unified) = self.unify_hint(outlet, fact)?;
+        self.model.borrow_mut().hinted.insert(outlet.node);
+        if unified == old_fact {
+            return Ok(vec![]);
+        }
+        self.model.borrow_mut().set_fact(outlet, unified)?;
+        Ok(vec![outlet])
+    }
+
+    /// Hints the first output of several nodes, designated by name.
+    ///
+    /// The hints are applied all together or not at all: if one of them
+    /// conflicts with the facts of the model, no edge is modified.
+    pub fn with_input_facts(mut self, facts: &HashMap<String, TensorFact>) -> TractResult<Self> {
+        let mut names: Vec<&String> = facts.keys().collect();
+        names.sort();
+        let mut unified = vec![];
+        for name in names {
+            let outlet = OutletId::new(self.node_id_by_name(name)?, 0);
+            unified.push((outlet, self.unify_hint(outlet, &facts[name])?.1));
+        }
+        for (outlet, fact) in unified {
+            self.model.borrow_mut().hinted.insert(outlet.node);
+            self.model.borrow_mut().set_fact(outlet, fact)?;
+        }
+        Ok(self)
+    }
+
+    /// Returns the current fact of an edge and its unification with a hint.
+    fn unify_hint(
+        &self,
+        outlet: OutletId,
+        fact: &TensorFact,
+    ) -> TractResult<(TensorFact, TensorFact)> {
+        let old_fact = self.model.borrow().fact(outlet)?.clone();
+        let unified = old_fact.unify(fact).map_err(|e| {
+            let node = &self.model.borrow().node(outlet.node).name;
+            let node = if outlet.slot == 0 {
+                node.to_string()
+            } else {
+                format!("{}#{}", node, outlet.slot)
+            };
+            format!(
+                "hint for node {} conflicts with inferred fact {:?} (hint: {:?}): {}",
+                node, old_fact, fact, e
+            )
+        })?;
+        Ok((old_fact, unified))
+    }
+
+    /// Narrows the fact of the first output of the node called `name`.
+    pub fn hint_by_name(&mut self, name: &str, fact: &TensorFact) -> TractResult<Vec<OutletId>> {
+        let id = self.node_id_by_name(name)?;
+        self.hint(OutletId::new(id, 0), fact)
+    }
+
+    fn node_id_by_name(&self, name: &str) -> TractResult<usize> {
+        match self.nodes_by_name.get(name) {
+            Some(&id) => Ok(id),
+            None => {
+                let mut names: Vec<&String> = self.nodes_by_name.keys().collect();
+                names.sort_by_key(|n| (edit_distance(name, n), n.to_string()));
+                let close: Vec<&str> = names.iter().take(3).map(|n| n.as_str()).collect();
+                bail!("No node named {:?}. Closest names: {:?}", name, close)
+            }
+        }
+    }
+
+    /// Runs the analysis starting from a few changed edges only.
+    ///
+    /// Only the nodes around `seed_edges`, and the ones their changes
+    /// propagate to, are analysed again.
+    pub fn run_from(&mut self, seed_edges: &[OutletId]) -> TractResult<()> {
+        let mut nodes_to_visit = BTreeSet::new();
+        for edge in seed_edges {
+            nodes_to_visit.insert(edge.node);
+            for succ in &self.model.borrow().node(edge.node).outputs[edge.slot].successors {
+                nodes_to_visit.insert(succ.node);
+            }
+        }
+        self.run_nodes(nodes_to_visit, usize::max_value(), None)?;
+        Ok(())
+    }
+
+    fn run_nodes(
+        &mut self,
+        mut nodes_to_visit: BTreeSet<usize>,
+        max_passes: usize,
+        deadline: Option<Instant>,
+    ) -> TractResult<bool> {
+        self.current_pass = 0;
+        self.skipped_steps = 0;
+        let round = self.model.borrow().nodes().len().max(1);
+        let mut score = self.information_score();
+        let mut stale_rounds = 0;
+        let mut round_edges: Vec<OutletId> = vec![];
         loop {
             trace!("Remaining nodes {}", nodes_to_visit.len());
             let node = match nodes_to_visit.iter().next() {
-                None => return Ok(()),
+                None => return Ok(true),
                 Some(n) => *n,
             };
+            if self.current_pass >= max_passes
+                || deadline.map(|d| Instant::now() >= d).unwrap_or(false)
+            {
+                debug!("Analysis interrupted after {} passes", self.current_pass);
+                return Ok(false);
+            }
+            if self.is_saturated(node)? {
+                trace!("Skipping saturated node {}", node);
+                self.skipped_steps += 1;
+                self.stats.total_steps += 1;
+                nodes_to_visit.remove(&node);
+                continue;
+            }
+            self.current_pass += 1;
+            self.stats.total_passes += 1;
+            self.stats.total_steps += 1;
             let changed_edges = self
                 .analyse_one(node)
                 .map_err(|e| format!("Analysing node {:?}, {:?}", node, e))?;
             for (edge, _fact) in changed_edges {
                 trace!("Changed edge: {:?}", edge);
+                if !round_edges.contains(&edge) {
+                    round_edges.push(edge);
+                }
                 for dst in self.model.borrow().nodes()[edge.node].outputs[edge.slot]
                     .successors
                     .iter()
@@ -53,197 +391,1167 @@ impl<M: BorrowMut<Model>> Analyser<M> {
                 }
             }
             nodes_to_visit.remove(&node);
+            if self.current_pass % round == 0 {
+                let new_score = self.information_score();
+                if new_score > score {
+                    score = new_score;
+                    stale_rounds = 0;
+                } else if !round_edges.is_empty() {
+                    stale_rounds += 1;
+                    if stale_rounds >= MAX_STALE_ROUNDS {
+                        bail!(
+                            "Analysis does not converge: edges {:?} keep changing without gaining information",
+                            round_edges
+                        )
+                    }
+                }
+                round_edges.clear();
+            }
         }
     }
 
+    /// Measures how much is known about the facts of the whole graph.
+    ///
+    /// Unifying facts can never make it decrease, so a run where edges keep
+    /// changing while the score stays put is going round in circles.
+    fn information_score(&self) -> usize {
+        self.model
+            .borrow()
+            .nodes()
+            .iter()
+            .flat_map(|n| n.outputs.iter())
+            .map(|o| fact_information(&o.fact))
+            .sum()
+    }
+
+    /// Checks whether all the facts around a node are fully determined, in
+    /// which case analysing it can not bring anything new.
+    fn is_saturated(&mut self, node: usize) -> TractResult<bool> {
+        if self.saturated.contains(node) {
+            return Ok(true);
+        }
+        let (inputs, outputs) = self.model.borrow().facts(node)?;
+        let saturated = inputs
+            .iter()
+            .chain(outputs.iter())
+            .all(|f| f.datum_type.is_concrete() && f.shape.is_concrete() && f.value.is_concrete());
+        if saturated {
+            self.saturated.insert(node);
+        }
+        Ok(saturated)
+    }
+
     /// Tries to run a single step of the analysis, and returns whether
     /// there was any additional information gained during the step.
     pub fn analyse_one(&mut self, node: usize) -> TractResult<Vec<(OutletId, TensorFact)>> {
-        let mut changed_edges = vec![];
+        #[cfg(feature = "profile")]
+        let start = Instant::now();
+        let changed_edges = infer_node(self.model.borrow(), node, self.value_tolerance)?;
+        #[cfg(feature = "profile")]
         {
-            let node = &self.model.borrow().nodes()[node];
-            debug!(
-                "Starting step for #{} {} ({})",
-                node.id,
+            let op_name = self.model.borrow().node(node).op().name().into_owned();
+            *self
+                .op_timings
+                .entry(op_name)
+                .or_insert_with(Duration::default) += start.elapsed();
+        }
+        self.apply(node, changed_edges)
+    }
+
+    /// Time spent inferring facts, summed by operator name, since the
+    /// analyser was built. Parallel analyses are not timed.
+    #[cfg(feature = "profile")]
+    pub fn op_timings(&self) -> HashMap<String, Duration> {
+        self.op_timings.clone()
+    }
+
+    /// Runs the entire analysis, inferring all the nodes of a topological
+    /// layer concurrently.
+    ///
+    /// Facts inferred by the nodes of a layer are merged with `unify`, so the
+    /// result does not depend on the order in which the nodes are processed.
+    #[cfg(feature = "parallel")]
+    pub fn analyse_parallel(&mut self) -> TractResult<()> {
+        use rayon::prelude::*;
+        self.current_pass = 0;
+        self.skipped_steps = 0;
+        let layers = self.layers()?;
+        loop {
+            let mut changed = false;
+            for layer in &layers {
+                let inferred = {
+                    let model = self.model.borrow();
+                    let tolerance = self.value_tolerance;
+                    layer
+                        .par_iter()
+                        .map(|&node| Ok((node, infer_node(model, node, tolerance)?)))
+                        .collect::<TractResult<Vec<_>>>()?
+                };
+                self.current_pass += layer.len();
+                self.stats.total_passes += layer.len();
+                self.stats.total_steps += layer.len();
+                let mut merged: HashMap<OutletId, (usize, TensorFact)> = HashMap::new();
+                for (node, edges) in inferred {
+                    for (outlet, fact) in edges {
+                        let merged_fact = match merged.remove(&outlet) {
+                            Some((source, other)) => (
+                                source.min(node),
+                                other
+                                    .unify_with_tolerance(&fact, self.value_tolerance)
+                                    .map_err(|e| {
+                                        format!("While merging facts of {:?}: {}", outlet, e)
+                                    })?,
+                            ),
+                            None => (node, fact),
+                        };
+                        merged.insert(outlet, merged_fact);
+                    }
+                }
+                let mut by_source: BTreeMap<usize, Vec<(OutletId, TensorFact)>> = BTreeMap::new();
+                for (outlet, (source, fact)) in merged {
+                    by_source
+                        .entry(source)
+                        .or_insert_with(Vec::new)
+                        .push((outlet, fact));
+                }
+                for (source, edges) in by_source {
+                    // facts may have been refined by the previous sources of
+                    // this layer, through symbol bindings
+                    let mut refined = vec![];
+                    for (outlet, fact) in edges {
+                        let old_fact = self.model.borrow().fact(outlet)?;
+                        let unified = old_fact.unify_with_tolerance(&fact, self.value_tolerance)?;
+                        if &unified != old_fact {
+                            refined.push((outlet, unified));
+                        }
+                    }
+                    changed |= !self.apply(source, refined)?.is_empty();
+                }
+            }
+            if !changed {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Groups the nodes by topological depth, so that the nodes of a layer
+    /// only depend on the nodes of the previous ones.
+    #[cfg(feature = "parallel")]
+    fn layers(&self) -> TractResult<Vec<Vec<usize>>> {
+        let model = self.model.borrow();
+        let mut depths = vec![0; model.nodes().len()];
+        let mut layers: Vec<Vec<usize>> = vec![];
+        for &node in &self.plan {
+            let depth = model
+                .node(node)
+                .inputs
+                .iter()
+                .map(|i| depths[i.node] + 1)
+                .max()
+                .unwrap_or(0);
+            depths[node] = depth;
+            if layers.len() <= depth {
+                layers.resize(depth + 1, vec![]);
+            }
+            layers[depth].push(node);
+        }
+        Ok(layers)
+    }
+
+    /// Sets the changed facts inferred by a node, and propagates the symbols
+    /// they bind to the whole graph.
+    fn apply(
+        &mut self,
+        node: usize,
+        mut changed_edges: Vec<(OutletId, TensorFact)>,
+    ) -> TractResult<Vec<(OutletId, TensorFact)>> {
+        let mut bindings = vec![];
+        for (outlet, fact) in &changed_edges {
+            let old_fact = self.model.borrow().fact(*outlet)?;
+            bindings.extend(old_fact.shape.symbol_bindings(&fact.shape));
+        }
+        for (outlet, fact) in &changed_edges {
+            self.narrow(*outlet, fact.clone(), node)?;
+        }
+        for (symbol, value) in bindings {
+            debug!(" Binding symbol {} to {:?}", symbol, value);
+            for (outlet, fact) in self.bind_symbol(symbol, value)? {
+                self.narrow(outlet, fact.clone(), node)?;
+                changed_edges.retain(|(o, _)| *o != outlet);
+                changed_edges.push((outlet, fact));
+            }
+        }
+        Ok(changed_edges)
+    }
+
+    /// Resets the values of the edges whose consumers are all saturated,
+    /// keeping their datum type and shape, and returns the number of edges
+    /// stripped.
+    ///
+    /// Once every node reading a constant is fully determined, the analysis
+    /// does not need its value anymore, so this reclaims the memory held by
+    /// large constants. Model outputs are left alone. Optimisations folding
+    /// constant inputs rely on these values, so this is meant for analyses
+    /// whose model will not be decluttered afterwards.
+    pub fn drop_resolved_values(&mut self) -> TractResult<usize> {
+        let mut candidates = vec![];
+        {
+            let model = self.model.borrow();
+            let outputs = model.outputs()?;
+            for node in model.nodes() {
+                for (ix, outlet) in node.outputs.iter().enumerate() {
+                    let id = OutletId::new(node.id, ix);
+                    if outlet.fact.value.is_concrete()
+                        && !outlet.successors.is_empty()
+                        && !outputs.contains(&id)
+                    {
+                        let consumers: Vec<usize> =
+                            outlet.successors.iter().map(|s| s.node).collect();
+                        candidates.push((id, consumers));
+                    }
+                }
+            }
+        }
+        // check saturation before stripping anything, as stripping makes the
+        // consumers look undetermined
+        let mut resolved = vec![];
+        for (id, consumers) in candidates {
+            let mut done = true;
+            for consumer in consumers {
+                done = done && self.is_saturated(consumer)?;
+            }
+            if done {
+                resolved.push(id);
+            }
+        }
+        for &id in &resolved {
+            let fact = self.model.borrow().fact(id)?.without_value();
+            self.model.borrow_mut().set_fact(id, fact)?;
+            if let Some(history) = self.history.get_mut(&id) {
+                for (_, fact) in history.iter_mut() {
+                    *fact = fact.without_value();
+                }
+            }
+        }
+        Ok(resolved.len())
+    }
+
+    /// Returns the successive facts of an edge, along with the node whose
+    /// inference narrowed it each time.
+    pub fn explain_edge(&self, outlet: OutletId) -> Vec<(usize, TensorFact)> {
+        self.history.get(&outlet).cloned().unwrap_or_else(Vec::new)
+    }
+
+    /// Lists the edges whose datum type or shape is not fully determined,
+    /// in plan order. Along with `explain_edge`, this helps deciding where
+    /// hints are needed.
+    pub fn unresolved_edges(&self) -> Vec<(OutletId, &TensorFact)> {
+        let model = self.model.borrow();
+        self.plan
+            .iter()
+            .flat_map(|&n| {
+                model
+                    .node(n)
+                    .outputs
+                    .iter()
+                    .enumerate()
+                    .map(move |(ix, outlet)| (OutletId::new(n, ix), &outlet.fact))
+            })
+            .filter(|(_, fact)| !fact.datum_type.is_concrete() || !fact.shape.is_fully_determined())
+            .collect()
+    }
+
+    /// Returns the fact on the model output `ix`.
+    pub fn outputs_fact(&self, ix: usize) -> TractResult<&TensorFact> {
+        let model = self.model.borrow();
+        let outlet = *model
+            .outputs()?
+            .get(ix)
+            .ok_or_else(|| format!("Model has no output {}", ix))?;
+        model.fact(outlet)
+    }
+
+    /// Returns the fact on the model output.
+    pub fn output_fact(&self) -> TractResult<&TensorFact> {
+        self.outputs_fact(0)
+    }
+
+    /// Panics unless model output `ix` has a closed shape matching `dims`,
+    /// where `None` stands for a dimension that is not known as an integer.
+    pub fn assert_outputs_shape(&self, ix: usize, dims: &[Option<usize>]) {
+        let fact = self.outputs_fact(ix).unwrap();
+        assert!(
+            !fact.shape.is_open(),
+            "Output {} has an open shape: {:?}",
+            ix,
+            fact.shape
+        );
+        let found: Vec<Option<usize>> = fact
+            .shape
+            .dims()
+            .map(|d| {
+                d.concretize()
+                    .and_then(|d| d.to_integer().ok())
+                    .map(|d| d as usize)
+            })
+            .collect();
+        assert_eq!(found, dims, "Unexpected shape for output {}", ix);
+    }
+
+    /// Panics unless the model output has a closed shape matching `dims`.
+    pub fn assert_output_shape(&self, dims: &[Option<usize>]) {
+        self.assert_outputs_shape(0, dims)
+    }
+
+    /// Renders the plan, one step per line in evaluation order, each node
+    /// followed by the facts currently known for its input and output edges.
+    pub fn dump_plan(&self) -> String {
+        use std::fmt::Write;
+        let model = self.model.borrow();
+        let mut dump = String::new();
+        for (step, &n) in self.plan.iter().enumerate() {
+            let node = model.node(n);
+            writeln!(
+                dump,
+                "{:>4}: #{} {:?} ({})",
+                step,
+                n,
                 node.name,
-                node.op.name(),
-            );
+                node.op.name()
+            )
+            .unwrap();
+            for (ix, input) in node.inputs.iter().enumerate() {
+                let fact = &model.node(input.node).outputs[input.slot].fact;
+                writeln!(
+                    dump,
+                    "        input  {} <- {}/{}: {:?}",
+                    ix, input.node, input.slot, fact
+                )
+                .unwrap();
+            }
+            for (ix, output) in node.outputs.iter().enumerate() {
+                writeln!(dump, "        output {}: {:?}", ix, output.fact).unwrap();
+            }
+        }
+        dump
+    }
+
+    /// Sets the fact of an edge, keeping track of where it comes from.
+    fn narrow(&mut self, outlet: OutletId, fact: TensorFact, source: usize) -> TractResult<()> {
+        let pass = self.current_pass;
+        self.stats.total_edge_updates += 1;
+        let model = self.model.borrow_mut();
+        model.set_fact(outlet, fact.clone())?;
+        let outlet_fact = &mut model.node_mut(outlet.node).outputs[outlet.slot];
+        outlet_fact.source_node = Some(source);
+        outlet_fact.source_pass = pass;
+        self.history
+            .entry(outlet)
+            .or_insert_with(Vec::new)
+            .push((source, fact));
+        Ok(())
+    }
+
+    /// Computes the facts changed by replacing a symbolic dimension by its
+    /// value in the whole graph.
+    fn bind_symbol(&self, symbol: char, value: TDim) -> TractResult<Vec<(OutletId, TensorFact)>> {
+        let mut changed = vec![];
+        for node in self.model.borrow().nodes() {
+            for (ix, outlet) in node.outputs.iter().enumerate() {
+                let shape = outlet.fact.shape.substitute(symbol, value);
+                if shape != outlet.fact.shape {
+                    let fact = TensorFact {
+                        shape,
+                        ..outlet.fact.clone()
+                    };
+                    changed.push((OutletId::new(node.id, ix), fact));
+                }
+            }
+        }
+        Ok(changed)
+    }
+}
 
-            let (inputs, outputs) = self.model.borrow().facts(node.id)?;
+/// Runs the inference of a single node, and returns the facts it refines,
+/// without changing the model.
+fn infer_node(
+    model: &Model,
+    node: usize,
+    tolerance: f32,
+) -> TractResult<Vec<(OutletId, TensorFact)>> {
+    let mut changed_edges = vec![];
+    let node = &model.nodes()[node];
+    debug!(
+        "Starting step for #{} {} ({})",
+        node.id,
+        node.name,
+        node.op.name(),
+    );
 
-            let inferred = node.op.infer(inputs, outputs).map_err(|e| {
+    let (inputs, outputs) = model.facts(node.id)?;
+
+    let inferred = node.op.infer(inputs, outputs).map_err(|e| {
+        format!(
+            "While inferring forward for #{} {}: {}",
+            node.id, node.name, e
+        )
+    })?;
+
+    for (ix, &outlet) in node.inputs.iter().enumerate() {
+        let inferred_fact = &inferred.0[ix];
+        let old_fact = model.fact(outlet)?;
+        let unified = old_fact
+            .unify_with_tolerance(inferred_fact, tolerance)
+            .map_err(|e| {
                 format!(
-                    "While inferring forward for #{} {}: {}",
+                    "While unifying inputs of node #{} {}: {}",
                     node.id, node.name, e
                 )
             })?;
 
-            for (ix, &outlet) in node.inputs.iter().enumerate() {
-                let inferred_fact = &inferred.0[ix];
-                let old_fact = self.model.borrow().fact(outlet)?;
-                let unified = inferred_fact.unify(&old_fact).map_err(|e| {
-                    format!(
-                        "While unifying inputs of node #{} {}: {}",
-                        node.id, node.name, e
-                    )
-                })?;
-
-                if &unified != old_fact {
-                    debug!(" Refined {} input #{} to {:?}", node.name, ix, unified);
-                    changed_edges.push((outlet, unified));
-                }
-            }
+        if &unified != old_fact {
+            debug!(" Refined {} input #{} to {:?}", node.name, ix, unified);
+            changed_edges.push((outlet, unified));
+        }
+    }
 
-            for (ix, inferred_fact) in inferred.1.iter().enumerate() {
-                let old_fact = self.model.borrow().fact(OutletId::new(node.id, ix))?;
-                let unified = old_fact.unify(inferred_fact)?;
+    for (ix, inferred_fact) in inferred.1.iter().enumerate() {
+        let old_fact = model.fact(OutletId::new(node.id, ix))?;
+        let unified = old_fact.unify_with_tolerance(inferred_fact, tolerance)?;
 
-                if &unified != old_fact {
-                    debug!(" Refined {} input #{} to {:?}", node.name, ix, unified);
-                    changed_edges.push((OutletId::new(node.id, ix), unified));
-                }
+        if &unified != old_fact {
+            debug!(" Refined {} input #{} to {:?}", node.name, ix, unified);
+            if let ValueFact::Only(value) = &unified.value {
+                trace!("  Values: {}", value.dump_values(16));
             }
+            changed_edges.push((OutletId::new(node.id, ix), unified));
         }
-        for (outlet, fact) in &changed_edges {
-            self.model.borrow_mut().set_fact(*outlet, fact.clone())?;
+    }
+    Ok(changed_edges)
+}
+
+/// Counts the known bits of a fact: datum type, rank, dimensions (a constant
+/// one weighting more than a symbolic one) and value elements.
+fn fact_information(fact: &TensorFact) -> usize {
+    let mut score = fact.datum_type.is_concrete() as usize;
+    score += fact.shape.rank().is_some() as usize;
+    for dim in fact.shape.dims() {
+        if let GenericFact::Only(d) = dim {
+            score += 1 + d.as_const().is_some() as usize;
         }
-        Ok(changed_edges)
     }
+    score += match &fact.value {
+        ValueFact::Only(t) => 1 + t.shape().iter().product::<usize>(),
+        ValueFact::Partial(a) => a.iter().filter(|d| d.is_concrete()).count(),
+        ValueFact::Any => 0,
+    };
+    score
 }
 
-#[cfg(tests)]
+/// Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let current = row[j + 1];
+            row[j + 1] = if ca == cb {
+                previous
+            } else {
+                1 + previous.min(row[j]).min(current)
+            };
+            previous = current;
+        }
+    }
+    row[b.len()]
+}
+
+/// Detects the sink nodes of the graph, i.e. the nodes whose outputs are not
+/// consumed by any other node.
+pub fn detect_outputs(model: &Model) -> TractResult<Vec<usize>> {
+    Ok(model
+        .successors()
+        .iter()
+        .enumerate()
+        .filter(|(_, succ)| succ.is_empty())
+        .map(|(id, _)| id)
+        .collect())
+}
+
+/// Detects the first sink node of the graph.
+pub fn detect_output(model: &Model) -> TractResult<Option<usize>> {
+    Ok(detect_outputs(model)?.into_iter().next())
+}
+
+#[cfg(test)]
 mod tests {
+    use super::*;
+    use crate::model::dsl::ModelDsl;
+    use crate::ops::array::Split;
+    use crate::ops::math::{Abs, Add, Exp, Neg};
+    use ndarray::prelude::*;
+
+    /// A source `a` of the given fact, feeding a node `abs`.
+    fn abs_chain(fact: TensorFact) -> Model {
+        let mut model = Model::default();
+        model.add_source_fact("a", fact).unwrap();
+        model.chain("abs", Box::new(Abs::default())).unwrap();
+        model
+    }
+
+    fn dim(d: i32) -> DimFact {
+        GenericFact::Only(d.to_dim())
+    }
+
+    fn zeros(len: usize) -> ValueFact {
+        ValueFact::Only(ArrayD::<f32>::zeros(IxDyn(&[len])).into())
+    }
+
     #[test]
     fn unify_same_datum_type() {
-        let dt = TypeFact::Only(DatumType::DT_FLOAT);
-        assert_eq!(unify_datum_type(&dt, &dt).unwrap(), dt);
+        let dt = TypeFact::Only(DatumType::F32);
+        assert_eq!(dt.unify(&dt).unwrap(), dt);
     }
 
     #[test]
     fn unify_different_datum_types_only() {
-        let dt1 = TypeFact::Only(DatumType::DT_FLOAT);
-        let dt2 = TypeFact::Only(DatumType::DT_DOUBLE);
-        assert!(unify_datum_type(&dt1, &dt2).is_err());
+        let dt1 = TypeFact::Only(DatumType::F32);
+        let dt2 = TypeFact::Only(DatumType::F64);
+        assert!(dt1.unify(&dt2).is_err());
     }
 
     #[test]
     fn unify_different_datum_types_any_left() {
-        let dt = TypeFact::Only(DatumType::DT_FLOAT);
-        assert_eq!(unify_datum_type(&TypeFact::Any, &dt).unwrap(), dt);
+        let dt = TypeFact::Only(DatumType::F32);
+        assert_eq!(TypeFact::Any.unify(&dt).unwrap(), dt);
     }
 
     #[test]
     fn unify_different_datum_types_any_right() {
-        let dt = TypeFact::Only(DatumType::DT_FLOAT);
-        assert_eq!(unify_datum_type(&dt, &TypeFact::Any).unwrap(), dt);
+        let dt = TypeFact::Only(DatumType::F32);
+        assert_eq!(dt.unify(&TypeFact::Any).unwrap(), dt);
     }
 
     #[test]
     fn unify_same_shape_1() {
-        let s = ShapeFact::closed(vec![]);
-        assert_eq!(unify_shape(&s, &s).unwrap(), s);
+        let s = ShapeFact::closed(tvec![]);
+        assert_eq!(s.unify(&s).unwrap(), s);
     }
 
     #[test]
     fn unify_same_shape_2() {
-        use super::DimFact::*;
-        let s = ShapeFact::closed(vec![Any]);
-        assert_eq!(unify_shape(&s, &s).unwrap(), s);
+        let s = ShapeFact::closed(tvec![GenericFact::Any]);
+        assert_eq!(s.unify(&s).unwrap(), s);
     }
 
     #[test]
     fn unify_same_shape_3() {
-        use super::DimFact::*;
-        let s = ShapeFact::closed(vec![Only(1), Only(2)]);
-        assert_eq!(unify_shape(&s, &s).unwrap(), s);
+        let s = ShapeFact::closed(tvec![dim(1), dim(2)]);
+        assert_eq!(s.unify(&s).unwrap(), s);
     }
 
     #[test]
     fn unify_different_shapes_1() {
-        use super::DimFact::*;
-        let s1 = ShapeFact::closed(vec![Only(1), Only(2)]);
-        let s2 = ShapeFact::closed(vec![Only(1)]);
-        assert!(unify_shape(&s1, &s2).is_err());
+        let s1 = ShapeFact::closed(tvec![dim(1), dim(2)]);
+        let s2 = ShapeFact::closed(tvec![dim(1)]);
+        assert!(s1.unify(&s2).is_err());
     }
 
     #[test]
     fn unify_different_shapes_2() {
-        use super::DimFact::*;
-        let s1 = ShapeFact::closed(vec![Only(1), Only(2)]);
-        let s2 = ShapeFact::closed(vec![Any]);
-        assert!(unify_shape(&s1, &s2).is_err());
+        let s1 = ShapeFact::closed(tvec![dim(1), dim(2)]);
+        let s2 = ShapeFact::closed(tvec![GenericFact::Any]);
+        assert!(s1.unify(&s2).is_err());
     }
 
     #[test]
     fn unify_different_shapes_3() {
-        use super::DimFact::*;
-        let s1 = ShapeFact::open(vec![Only(1), Only(2)]);
-        let s2 = ShapeFact::closed(vec![Any]);
-        assert!(unify_shape(&s1, &s2).is_err());
+        let s1 = ShapeFact::open(tvec![dim(1), dim(2)]);
+        let s2 = ShapeFact::closed(tvec![GenericFact::Any]);
+        assert!(s1.unify(&s2).is_err());
     }
 
     #[test]
     fn unify_different_shapes_4() {
-        use super::DimFact::*;
-        let s1 = ShapeFact::closed(vec![Any]);
-        let s2 = ShapeFact::closed(vec![Any]);
-        let sr = ShapeFact::closed(vec![Any]);
-        assert_eq!(unify_shape(&s1, &s2).unwrap(), sr);
+        let s1 = ShapeFact::closed(tvec![GenericFact::Any]);
+        let s2 = ShapeFact::closed(tvec![GenericFact::Any]);
+        let sr = ShapeFact::closed(tvec![GenericFact::Any]);
+        assert_eq!(s1.unify(&s2).unwrap(), sr);
     }
 
     #[test]
     fn unify_different_shapes_5() {
-        use super::DimFact::*;
-        let s1 = ShapeFact::closed(vec![Any]);
-        let s2 = ShapeFact::closed(vec![Only(1)]);
-        let sr = ShapeFact::closed(vec![Only(1)]);
-        assert_eq!(unify_shape(&s1, &s2).unwrap(), sr);
+        let s1 = ShapeFact::closed(tvec![GenericFact::Any]);
+        let s2 = ShapeFact::closed(tvec![dim(1)]);
+        let sr = ShapeFact::closed(tvec![dim(1)]);
+        assert_eq!(s1.unify(&s2).unwrap(), sr);
     }
 
     #[test]
     fn unify_different_shapes_6() {
-        use super::DimFact::*;
-        let s1 = ShapeFact::open(vec![]);
-        let s2 = ShapeFact::closed(vec![Only(1)]);
-        let sr = ShapeFact::closed(vec![Only(1)]);
-        assert_eq!(unify_shape(&s1, &s2).unwrap(), sr);
+        let s1 = ShapeFact::open(tvec![]);
+        let s2 = ShapeFact::closed(tvec![dim(1)]);
+        let sr = ShapeFact::closed(tvec![dim(1)]);
+        assert_eq!(s1.unify(&s2).unwrap(), sr);
     }
 
     #[test]
     fn unify_different_shapes_7() {
-        use super::DimFact::*;
-        let s1 = ShapeFact::open(vec![Any, Only(2)]);
-        let s2 = ShapeFact::closed(vec![Only(1), Any, Any]);
-        let sr = ShapeFact::closed(vec![Only(1), Only(2), Any]);
-        assert_eq!(unify_shape(&s1, &s2).unwrap(), sr);
+        let s1 = ShapeFact::open(tvec![GenericFact::Any, dim(2)]);
+        let s2 = ShapeFact::closed(tvec![dim(1), GenericFact::Any, GenericFact::Any]);
+        let sr = ShapeFact::closed(tvec![dim(1), dim(2), GenericFact::Any]);
+        assert_eq!(s1.unify(&s2).unwrap(), sr);
     }
 
     #[test]
     fn unify_same_value() {
-        use ndarray::prelude::*;
-        let dt = ValueFact::Only(Tensor::F32(ArrayD::zeros(IxDyn(&[1]))));
-        assert_eq!(unify_value(&dt, &dt).unwrap(), dt);
+        let dt = zeros(1);
+        assert_eq!(dt.unify(&dt).unwrap(), dt);
     }
 
     #[test]
     fn unify_different_values_only() {
-        use ndarray::prelude::*;
-        let dt1 = ValueFact::Only(Tensor::F32(ArrayD::zeros(IxDyn(&[1]))));
-        let dt2 = ValueFact::Only(Tensor::F32(ArrayD::zeros(IxDyn(&[2]))));
-        assert!(unify_value(&dt1, &dt2).is_err());
+        assert!(zeros(1).unify(&zeros(2)).is_err());
     }
 
     #[test]
     fn unify_different_values_any_left() {
-        use ndarray::prelude::*;
-        let dt = ValueFact::Only(Tensor::F32(ArrayD::zeros(IxDyn(&[1]))));
-        assert_eq!(unify_value(&ValueFact::Any, &dt).unwrap(), dt);
+        let dt = zeros(1);
+        assert_eq!(ValueFact::Any.unify(&dt).unwrap(), dt);
     }
 
     #[test]
     fn unify_different_values_any_right() {
-        use ndarray::prelude::*;
-        let dt = ValueFact::Only(Tensor::F32(ArrayD::zeros(IxDyn(&[1]))));
-        assert_eq!(unify_value(&dt, &ValueFact::Any).unwrap(), dt);
+        let dt = zeros(1);
+        assert_eq!(dt.unify(&ValueFact::Any).unwrap(), dt);
+    }
+
+    #[test]
+    fn analyser_hints_by_name() {
+        let mut model = Model::default();
+        model
+            .add_source_fact("input_0", TensorFact::default())
+            .unwrap();
+        model.chain("abs", Box::new(Abs::default())).unwrap();
+        let mut analyser = Analyser::new(&mut model).unwrap();
+        let fact = TensorFact::dt_shape(DatumType::F32, vec![3]);
+        let err = analyser.hint_by_name("input0", &fact).unwrap_err();
+        assert!(format!("{}", err).contains("\"input_0\""));
+        let changed = analyser.hint_by_name("input_0", &fact).unwrap();
+        analyser.run_from(&changed).unwrap();
+        assert_eq!(model.output_fact().unwrap(), &fact);
+    }
+
+    #[test]
+    fn analyser_skips_saturated_nodes() {
+        let mut model = Model::default();
+        model.add_const("a", arr1(&[-1.0f32, 2.0]).into()).unwrap();
+        model.chain("abs", Box::new(Abs::default())).unwrap();
+        model
+            .add_source_fact("b", TensorFact::dt_shape(DatumType::F32, vec![2]))
+            .unwrap();
+        model.chain("neg", Box::new(Neg::default())).unwrap();
+        let mut analyser = Analyser::new(&mut model).unwrap();
+        analyser.analyse().unwrap();
+        assert_eq!(analyser.skipped_steps, 0);
+        analyser.analyse().unwrap();
+        assert_eq!(analyser.skipped_steps, 2);
+        assert_eq!(analyser.current_pass, 2);
+        assert_eq!(
+            model.outputs_fact(0).unwrap(),
+            &TensorFact::from(arr1(&[1.0f32, 2.0]))
+        );
+        assert_eq!(
+            model.outputs_fact(1).unwrap(),
+            &TensorFact::dt_shape(DatumType::F32, vec![2])
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "profile")]
+    fn analyser_times_ops() {
+        let mut model = abs_chain(TensorFact::dt_shape(DatumType::F32, vec![2]));
+        model.chain("neg", Box::new(Neg::default())).unwrap();
+        let mut analyser = Analyser::new(&mut model).unwrap();
+        analyser.analyse().unwrap();
+        let timings = analyser.op_timings();
+        let mut names: Vec<&str> = timings.keys().map(|k| &**k).collect();
+        names.sort();
+        assert_eq!(names, vec!["Abs", "Neg", "Source"]);
+    }
+
+    #[test]
+    fn analyser_stats_on_constrained_chain() {
+        let mut model = Model::default();
+        model
+            .add_source_fact("a", TensorFact::dt_shape(DatumType::F32, vec![2]))
+            .unwrap();
+        for i in 0..4 {
+            model
+                .chain(format!("abs-{}", i), Box::new(Abs::default()))
+                .unwrap();
+        }
+        let mut analyser = Analyser::new(&mut model).unwrap();
+        analyser.analyse().unwrap();
+        let stats = analyser.stats();
+        assert!(stats.total_passes <= 5, "{:?}", stats);
+        assert_eq!(stats.total_edge_updates, 4);
+        assert_eq!(stats.total_steps, 5);
+        analyser.analyse().unwrap();
+        assert_eq!(
+            analyser.stats(),
+            AnalyserStats {
+                total_passes: 2 * stats.total_passes,
+                total_edge_updates: 4,
+                total_steps: 10,
+            }
+        );
+    }
+
+    #[test]
+    fn analyser_bails_on_oscillating_inference() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        static RENAMES: AtomicUsize = AtomicUsize::new(0);
+
+        /// Renames the dimension of its input and output to a fresh symbol at
+        /// every step, so the analysis never stabilizes.
+        #[derive(Debug, Clone)]
+        struct Oscillating;
+
+        impl Op for Oscillating {
+            fn name(&self) -> Cow<str> {
+                "Oscillating".into()
+            }
+        }
+
+        impl StatelessOp for Oscillating {
+            fn eval(&self, inputs: TVec<SharedTensor>) -> TractResult<TVec<SharedTensor>> {
+                Ok(inputs)
+            }
+        }
+
+        impl InferenceOp for Oscillating {
+            fn infer_facts(
+                &self,
+                _inputs: TVec<&TensorFact>,
+                _outputs: TVec<&TensorFact>,
+            ) -> TractResult<(TVec<TensorFact>, TVec<TensorFact>)> {
+                let n = RENAMES.fetch_add(1, Ordering::SeqCst) as u32;
+                let sym = ::std::char::from_u32(0xFFFF - n).unwrap();
+                let fact = TensorFact::shape(vec![TDim::sym(sym)]);
+                Ok((tvec!(fact.clone()), tvec!(fact)))
+            }
+        }
+
+        let mut model = Model::default();
+        model.add_source("a").unwrap();
+        model.chain("osc1", Box::new(Oscillating)).unwrap();
+        model.chain("osc2", Box::new(Oscillating)).unwrap();
+        let mut analyser = Analyser::new(&mut model).unwrap();
+        let err = analyser.run_with_timeout(10_000, None).unwrap_err();
+        assert!(format!("{}", err).contains("does not converge"));
+        assert!(analyser.current_pass < 100);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_analysis_matches_sequential() {
+        let mut model = Model::default();
+        let a = model
+            .add_source_fact(
+                "a",
+                TensorFact::dt_shape(DatumType::F32, vec![TDim::sym('N'), 3.to_dim()]),
+            )
+            .unwrap();
+        let abs = model.chain("abs", Box::new(Abs::default())).unwrap();
+        let neg = model
+            .tap_and_chain(OutletId::new(a, 0), "neg", Box::new(Neg::default()))
+            .unwrap();
+        let add = model
+            .add_node("add".to_string(), Box::new(Add::default()))
+            .unwrap();
+        model
+            .add_edge(OutletId::new(abs, 0), InletId::new(add, 0))
+            .unwrap();
+        model
+            .add_edge(OutletId::new(neg, 0), InletId::new(add, 1))
+            .unwrap();
+        model
+            .set_fact(OutletId::new(add, 0), TensorFact::shape(vec![2, 3]))
+            .unwrap();
+
+        let mut sequential = model.clone();
+        Analyser::new(&mut sequential).unwrap().analyse().unwrap();
+        let mut parallel = model.clone();
+        Analyser::new(&mut parallel)
+            .unwrap()
+            .analyse_parallel()
+            .unwrap();
+        for node in 0..model.nodes().len() {
+            assert_eq!(
+                sequential.fact(OutletId::new(node, 0)).unwrap(),
+                parallel.fact(OutletId::new(node, 0)).unwrap()
+            );
+        }
+        assert_eq!(
+            parallel.fact(OutletId::new(add, 0)).unwrap(),
+            &TensorFact::dt_shape(DatumType::F32, vec![2, 3])
+        );
+    }
+
+    #[test]
+    fn analyser_reuses_plan() {
+        let mut model = abs_chain(TensorFact::dt_shape(DatumType::F32, vec![2]));
+        model.chain("neg", Box::new(Neg::default())).unwrap();
+        assert!(Analyser::with_plan(&mut model, vec![1, 0, 2]).is_err());
+        assert!(Analyser::with_plan(&mut model, vec![0, 1]).is_err());
+        assert!(Analyser::with_plan(&mut model, vec![0, 1, 3]).is_err());
+        let order = crate::plan::SimplePlan::new(&model).unwrap().order;
+        let mut analyser = Analyser::with_plan(&mut model, order).unwrap();
+        analyser.analyse().unwrap();
+        assert_eq!(analyser.current_pass, 3);
+        analyser.reset_plan().unwrap();
+        assert_eq!(
+            model.output_fact().unwrap(),
+            &TensorFact::dt_shape(DatumType::F32, vec![2])
+        );
+    }
+
+    #[test]
+    fn analyser_without_outputs_lists_sinks() {
+        let mut model = abs_chain(TensorFact::dt_shape(DatumType::F32, vec![2]));
+        let abs = model.node_by_name("abs").unwrap().id;
+        model.set_outputs_outlets(&[]).unwrap();
+        let err = Analyser::new(&mut model).err().unwrap().to_string();
+        assert!(err.contains("Candidate sinks: abs"), "{}", err);
+        Analyser::for_targets(&mut model, &[abs])
+            .unwrap()
+            .analyse()
+            .unwrap();
+        assert_eq!(
+            model.fact(OutletId::new(abs, 0)).unwrap(),
+            &TensorFact::dt_shape(DatumType::F32, vec![2])
+        );
+    }
+
+    #[test]
+    fn analyser_value_tolerance() {
+        let mut model = Model::default();
+        let k = model.add_const("k", Tensor::from(1.0f32).into()).unwrap();
+        let abs = model.chain("abs", Box::new(Abs::default())).unwrap();
+        model
+            .set_fact(OutletId::new(abs, 0), Tensor::from(1.0000001f32).into())
+            .unwrap();
+        let mut analyser = Analyser::new(&mut model).unwrap();
+        analyser.analyse_one(k).unwrap();
+        assert!(analyser.analyse_one(abs).is_err());
+        let changed = Analyser::new(&mut model)
+            .unwrap()
+            .with_value_tolerance(1e-5)
+            .analyse_one(abs)
+            .unwrap();
+        assert!(changed.is_empty());
+    }
+
+    #[test]
+    fn analyser_stops_after_max_passes() {
+        let mut model = abs_chain(TensorFact::dt_shape(DatumType::F32, vec![2]));
+        let mut analyser = Analyser::new(&mut model).unwrap();
+        assert!(!analyser.run_with_timeout(1, None).unwrap());
+        assert_eq!(analyser.current_pass, 1);
+        assert!(analyser.run_with_timeout(100, None).unwrap());
+        assert_eq!(
+            model.output_fact().unwrap(),
+            &TensorFact::dt_shape(DatumType::F32, vec![2])
+        );
+    }
+
+    #[test]
+    fn analyser_runs_from_hint() {
+        let mut model = abs_chain(TensorFact::default());
+        model.chain("exp", Box::new(Exp::default())).unwrap();
+        model.analyse().unwrap();
+        let mut full = model.clone();
+        let fact = TensorFact::dt_shape(DatumType::F32, vec![2]);
+
+        let mut analyser = Analyser::new(&mut model).unwrap();
+        let changed = analyser.hint(OutletId::new(0, 0), &fact).unwrap();
+        assert_eq!(changed, vec![OutletId::new(0, 0)]);
+        analyser.run_from(&changed).unwrap();
+        assert!(analyser
+            .hint(OutletId::new(0, 0), &fact)
+            .unwrap()
+            .is_empty());
+
+        full.set_input_fact(0, fact.clone()).unwrap();
+        full.analyse().unwrap();
+        for node in 0..3 {
+            assert_eq!(
+                model.fact(OutletId::new(node, 0)).unwrap(),
+                full.fact(OutletId::new(node, 0)).unwrap()
+            );
+        }
+    }
+
+    fn three_inputs_sum(c: TensorFact) -> Model {
+        let mut model = Model::default();
+        let a = model.add_source("a").unwrap();
+        let b = model.add_source("b").unwrap();
+        let c = model.add_source_fact("c", c).unwrap();
+        let ab = model
+            .add_node("ab".to_string(), Box::new(Add::default()))
+            .unwrap();
+        let abc = model
+            .add_node("abc".to_string(), Box::new(Add::default()))
+            .unwrap();
+        for &(from, to, slot) in &[(a, ab, 0), (b, ab, 1), (ab, abc, 0), (c, abc, 1)] {
+            model
+                .add_edge(OutletId::new(from, 0), InletId::new(to, slot))
+                .unwrap();
+        }
+        model.set_outputs_outlets(&[OutletId::new(abc, 0)]).unwrap();
+        model
+    }
+
+    #[test]
+    fn analyser_applies_input_facts() {
+        let mut model = three_inputs_sum(TensorFact::default());
+        let fact = TensorFact::dt_shape(DatumType::F32, vec![2]);
+        let facts: HashMap<String, TensorFact> = ["a", "b", "c"]
+            .iter()
+            .map(|n| (n.to_string(), fact.clone()))
+            .collect();
+        Analyser::new(&mut model)
+            .unwrap()
+            .with_input_facts(&facts)
+            .unwrap()
+            .analyse()
+            .unwrap();
+        for node in 0..3 {
+            assert_eq!(model.fact(OutletId::new(node, 0)).unwrap(), &fact);
+        }
+        assert_eq!(model.output_fact().unwrap(), &fact);
+    }
+
+    #[test]
+    fn analyser_rolls_back_conflicting_input_facts() {
+        let mut model = three_inputs_sum(TensorFact::shape(vec![4]));
+        let before = model.clone();
+        let fact = TensorFact::dt_shape(DatumType::F32, vec![2]);
+        let facts: HashMap<String, TensorFact> = ["a", "b", "c"]
+            .iter()
+            .map(|n| (n.to_string(), fact.clone()))
+            .collect();
+        assert!(Analyser::new(&mut model)
+            .unwrap()
+            .with_input_facts(&facts)
+            .is_err());
+        for node in 0..5 {
+            assert_eq!(
+                model.fact(OutletId::new(node, 0)).unwrap(),
+                before.fact(OutletId::new(node, 0)).unwrap()
+            );
+        }
+        assert!(model.hinted.is_empty());
+    }
+
+    #[test]
+    fn analyser_rejects_conflicting_hint() {
+        let mut model = abs_chain(TensorFact::dt_shape(DatumType::F32, vec![1, 4]));
+        let mut analyser = Analyser::new(&mut model).unwrap();
+        analyser.analyse().unwrap();
+        let hint = TensorFact::shape(vec![1, 3]);
+        let err = analyser.hint_by_name("abs", &hint).unwrap_err();
+        let message = format!("{}", err);
+        assert!(
+            message.starts_with("hint for node abs conflicts with inferred fact"),
+            "{}",
+            message
+        );
+        assert!(message.contains(&format!("{:?}", hint)));
+        assert!(message.contains(&format!(
+            "{:?}",
+            TensorFact::dt_shape(DatumType::F32, vec![1, 4])
+        )));
+    }
+
+    #[test]
+    fn analyser_drops_resolved_values() {
+        let mut model = Model::default();
+        model
+            .add_const("c", Tensor::from(arr1(&[1.0f32, -2.0, 3.0])).into())
+            .unwrap();
+        model.chain("abs", Box::new(Abs::default())).unwrap();
+        {
+            let mut analyser = Analyser::new(&mut model).unwrap();
+            analyser.analyse().unwrap();
+            assert_eq!(analyser.drop_resolved_values().unwrap(), 1);
+        }
+        let c = model.fact(OutletId::new(0, 0)).unwrap();
+        assert_eq!(c.value, ValueFact::Any);
+        assert_eq!(c.datum_type, DatumType::F32.into());
+        assert_eq!(c.shape, ShapeFact::from(vec![3]));
+        let abs = model.fact(OutletId::new(1, 0)).unwrap();
+        assert!(abs.value.is_concrete());
+    }
+
+    #[test]
+    fn analyser_dumps_plan_in_order() {
+        let mut model = abs_chain(TensorFact::dt_shape(DatumType::F32, vec![1, 4]));
+        model.chain("exp", Box::new(Exp::default())).unwrap();
+        let mut analyser = Analyser::new(&mut model).unwrap();
+        analyser.analyse().unwrap();
+        let dump = analyser.dump_plan();
+        let positions: Vec<usize> = ["\"a\"", "\"abs\"", "\"exp\""]
+            .iter()
+            .map(|name| dump.find(name).unwrap())
+            .collect();
+        assert!(positions[0] < positions[1] && positions[1] < positions[2]);
+        assert!(dump.contains("input  0 <- 1/0: Tensor, F32"));
+    }
+
+    #[test]
+    fn analyser_exposes_output_facts() {
+        let mut model = Model::default();
+        model
+            .add_source_fact("a", TensorFact::dt_shape(DatumType::F32, shapefact!(6, S)))
+            .unwrap();
+        let split = model
+            .chain("split", Box::new(Split::new(0, 3, None)))
+            .unwrap();
+        model
+            .set_outputs_outlets(&[OutletId::new(split, 0), OutletId::new(split, 2)])
+            .unwrap();
+        let mut analyser = Analyser::new(&mut model).unwrap();
+        analyser.analyse().unwrap();
+        assert_eq!(
+            analyser.output_fact().unwrap().datum_type,
+            DatumType::F32.into()
+        );
+        analyser.assert_output_shape(&[Some(2), None]);
+        analyser.assert_outputs_shape(1, &[Some(2), None]);
+        assert!(analyser.outputs_fact(2).is_err());
+    }
+
+    #[test]
+    fn analyser_lists_unresolved_edges() {
+        let mut model = abs_chain(TensorFact::dt_shape(DatumType::F32, shapefact![_, 3]));
+        let a = model.node_by_name("a").unwrap().id;
+        let abs = model.node_by_name("abs").unwrap().id;
+        let b = model
+            .add_source_fact("b", TensorFact::dt_shape(DatumType::F32, vec![2, 3]))
+            .unwrap();
+        let neg = model.chain("neg", Box::new(Neg::default())).unwrap();
+        model
+            .set_outputs_outlets(&[OutletId::new(abs, 0), OutletId::new(neg, 0)])
+            .unwrap();
+        let mut analyser = Analyser::new(&mut model).unwrap();
+        analyser.analyse().unwrap();
+        let unresolved: Vec<OutletId> = analyser
+            .unresolved_edges()
+            .into_iter()
+            .map(|(outlet, _)| outlet)
+            .collect();
+        assert_eq!(unresolved, vec![OutletId::new(a, 0), OutletId::new(abs, 0)]);
+        assert!(!unresolved.contains(&OutletId::new(b, 0)));
+    }
+
+    #[test]
+    fn analyser_explains_edges() {
+        let mut model = abs_chain(TensorFact::dt_shape(DatumType::F32, vec![2]));
+        let abs = model.node_by_name("abs").unwrap().id;
+        let mut analyser = Analyser::new(&mut model).unwrap();
+        analyser.analyse().unwrap();
+        let history = analyser.explain_edge(OutletId::new(abs, 0));
+        assert_eq!(
+            history,
+            vec![(abs, TensorFact::dt_shape(DatumType::F32, vec![2]))]
+        );
+        assert!(analyser.explain_edge(OutletId::new(0, 0)).is_empty());
+        assert_eq!(model.node(abs).outputs[0].source_node, Some(abs));
+    }
+
+    #[test]
+    fn all_sinks_are_outputs() {
+        let mut model = Model::default();
+        let source = model
+            .add_source_fact("a", TensorFact::dt_shape(DatumType::F32, vec![2]))
+            .unwrap();
+        let abs = model.chain("abs", Box::new(Abs::default())).unwrap();
+        let neg = model
+            .add_node("neg".to_string(), Box::new(Neg::default()))
+            .unwrap();
+        model
+            .add_edge(OutletId::new(source, 0), InletId::new(neg, 0))
+            .unwrap();
+        assert_eq!(detect_outputs(&model).unwrap(), vec![abs, neg]);
+        assert_eq!(detect_output(&model).unwrap(), Some(abs));
+        let model = model.into_optimized().unwrap();
+        assert_eq!(model.outputs().unwrap().len(), 2);
+        assert_eq!(detect_outputs(&model).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn symbol_is_bound_across_graph() {
+        let mut model = Model::default();
+        let n = TDim::sym('N');
+        model
+            .add_source_fact("a", TensorFact::shape(vec![n, 3.to_dim()]))
+            .unwrap();
+        let abs = model.chain("abs", Box::new(Abs::default())).unwrap();
+        let other = model
+            .add_source_fact("b", TensorFact::shape(vec![n]))
+            .unwrap();
+        model
+            .set_fact(OutletId::new(abs, 0), TensorFact::shape(vec![2, 3]))
+            .unwrap();
+        model.analyse().unwrap();
+        assert_eq!(
+            model.fact(OutletId::new(0, 0)).unwrap(),
+            &TensorFact::shape(vec![2, 3])
+        );
+        assert_eq!(
+            model.fact(OutletId::new(other, 0)).unwrap(),
+            &TensorFact::shape(vec![2])
+        );
+    }
+
+    #[test]
+    fn affine_dimension_is_solved_through_downsample() {
+        use crate::ops::nn::{DataFormat, MaxPool, PaddingSpec};
+        let mut model = Model::default();
+        let n = TDim::sym('N');
+        let source = model
+            .add_source_fact(
+                "a",
+                TensorFact::dt_shape(DatumType::F32, vec![1.to_dim(), 1.to_dim(), n * 2]),
+            )
+            .unwrap();
+        let pool = model
+            .chain(
+                "pool",
+                Box::new(MaxPool::new(
+                    DataFormat::NCHW,
+                    tvec!(2),
+                    PaddingSpec::Valid,
+                    Some(tvec!(2)),
+                    None,
+                )),
+            )
+            .unwrap();
+        let abs = model
+            .tap_and_chain(OutletId::new(source, 0), "abs", Box::new(Abs::default()))
+            .unwrap();
+        model
+            .set_fact(OutletId::new(abs, 0), TensorFact::shape(vec![1, 1, 8]))
+            .unwrap();
+        model.analyse().unwrap();
+        assert_eq!(
+            model.fact(OutletId::new(pool, 0)).unwrap(),
+            &TensorFact::dt_shape(DatumType::F32, vec![1, 1, 4])
+        );
     }
 }
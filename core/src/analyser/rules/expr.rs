@@ -565,6 +565,12 @@ impl<'a> IntoExp<ValueFact> for &'a ValueProxy {
     }
 }
 
+impl IntoExp<ValueFact> for ValueFact {
+    fn bex(self) -> Exp<ValueFact> {
+        ConstantExp(self).bex()
+    }
+}
+
 impl IntoExp<ValueFact> for SharedTensor {
     fn bex(self) -> Exp<ValueFact> {
         ConstantExp(self.into()).bex()
@@ -59,3 +59,39 @@ impl<O: InferenceRulesOp> crate::ops::InferenceOp for O {
         solver.infer_facts((inputs, outputs))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ops::InferenceOp;
+
+    #[derive(Debug)]
+    struct SameShape;
+
+    impl InferenceRulesOp for SameShape {
+        fn rules<'r, 'p: 'r, 's: 'r>(
+            &'s self,
+            s: &mut Solver<'r>,
+            inputs: &'p SharedTensorsProxy,
+            outputs: &'p SharedTensorsProxy,
+        ) -> InferenceResult {
+            s.equals(&inputs.len, 1)?;
+            s.equals(&outputs.len, 1)?;
+            s.equals(&inputs[0].shape, &outputs[0].shape)
+        }
+    }
+
+    #[test]
+    fn rules_infer_forward() {
+        let input = TensorFact::shape(shapefact![1, 3]);
+        let outputs = SameShape.infer_forward(tvec![&input], 1).unwrap();
+        assert_eq!(outputs, tvec![TensorFact::shape(shapefact![1, 3])]);
+    }
+
+    #[test]
+    fn rules_infer_backward() {
+        let output = TensorFact::shape(shapefact![2, _]);
+        let inputs = SameShape.infer_backward(1, tvec![&output]).unwrap();
+        assert_eq!(inputs, tvec![TensorFact::shape(shapefact![2, _])]);
+    }
+}
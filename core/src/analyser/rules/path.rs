@@ -171,7 +171,11 @@ fn get_tensorfact_path(fact: &TensorFact, path: &[isize]) -> TractResult<Wrapped
         [0] => Ok(fact.datum_type.clone().wrap()),
 
         // Get the rank of the TensorFact.
-        [1] => Ok(fact.shape.rank().wrap()),
+        [1] => Ok(match fact.shape.rank() {
+            Some(rank) => GenericFact::Only(rank as i32),
+            None => GenericFact::Any,
+        }
+        .wrap()),
 
         slice if slice[0] == 2 => get_shape_path(&fact.shape, &slice[1..]),
         slice if slice[0] == 3 => get_value_path(&fact.value, &slice[1..]),
@@ -299,7 +303,7 @@ fn get_shape_path(shape: &ShapeFact, path: &[isize]) -> TractResult<Wrapped> {
 fn get_value_path(value: &ValueFact, path: &[isize]) -> TractResult<Wrapped> {
     trace!("get_value_path path:{:?} value:{:?}", path, value);
     // Return the whole tensor.
-    if path == &[-1] || path == &[] {
+    if path == &[-1] || path.is_empty() {
         return Ok(value.clone().wrap());
     }
 
@@ -7,6 +7,7 @@ use crate::ops::prelude::*;
 
 use self::super::expr::{Exp, IntoExp, Output, TExp};
 use self::super::path::{get_path, set_path, Path};
+use self::super::proxies::SharedTensorProxy;
 
 /// A structure that holds the current sets of TensorFacts.
 ///
@@ -409,6 +410,22 @@ impl<'rules> Solver<'rules> {
         Ok(())
     }
 
+    /// Ensures that two tensors are the same, i.e. have the same datum type,
+    /// rank, shape and value.
+    ///
+    /// This is meant for pass-through ops, and is equivalent to equating
+    /// each of these fields.
+    pub fn equals_fact(
+        &mut self,
+        left: &'rules SharedTensorProxy,
+        right: &'rules SharedTensorProxy,
+    ) -> InferenceResult {
+        self.equals(&left.datum_type, &right.datum_type)?;
+        self.equals(&left.rank, &right.rank)?;
+        self.equals(&left.shape, &right.shape)?;
+        self.equals(&left.value, &right.value)
+    }
+
     /// Ensures that the sum of several expressions equals zero.
     ///
     /// For instance, one could write:
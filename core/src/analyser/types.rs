@@ -1,8 +1,9 @@
-use crate::TractResult;
+use crate::{TractResult, UnifyError};
 use std::fmt;
 use std::iter::FromIterator;
 use std::ops::{Add, Div, Mul, Neg, Sub};
 
+use ndarray::ArrayD;
 use num_traits::Zero;
 
 use crate::ops::prelude::*;
@@ -54,6 +55,12 @@ impl TensorFact {
         TensorFact::default()
     }
 
+    /// Constructs a fact fully determined by `t`: its datum type, its
+    /// closed shape and its value.
+    pub fn from_tensor(t: &Tensor) -> TensorFact {
+        TensorFact::from(t.clone())
+    }
+
     pub fn dt(dt: DatumType) -> TensorFact {
         TensorFact::default().with_datum_type(dt)
     }
@@ -95,6 +102,89 @@ impl TensorFact {
     pub fn stream_info(&self) -> TractResult<Option<StreamInfo>> {
         self.shape.stream_info()
     }
+
+    /// Returns a copy of this fact without its value, keeping the datum type
+    /// and shape.
+    pub fn without_value(&self) -> TensorFact {
+        TensorFact {
+            datum_type: self.datum_type.clone(),
+            shape: self.shape.clone(),
+            value: ValueFact::Any,
+        }
+    }
+
+    /// Parses a fact from a spec like `f32,1,3,?,?`: a datum type followed
+    /// by the dimensions, `?` standing for an unknown one and `S` for the
+    /// streaming one. The shape is open if the spec ends with `..`.
+    pub fn from_spec(spec: &str) -> TractResult<TensorFact> {
+        let mut tokens = spec.split(',').map(|t| t.trim());
+        let dt: DatumType = tokens.next().unwrap().parse()?;
+        let mut dims: TVec<DimFact> = tvec!();
+        let mut open = false;
+        for token in tokens {
+            if open {
+                bail!("Invalid fact spec {:?}: `..` must come last", spec)
+            }
+            match token {
+                ".." => open = true,
+                "?" => dims.push(GenericFact::Any),
+                "S" => dims.push(TDim::s().into()),
+                _ => {
+                    let dim = token.parse::<usize>().map_err(|_| {
+                        format!("Invalid dimension {:?} in fact spec {:?}", token, spec)
+                    })?;
+                    dims.push(dim.to_dim().into())
+                }
+            }
+        }
+        let shape = if open {
+            ShapeFact::open(dims)
+        } else {
+            ShapeFact::closed(dims)
+        };
+        Ok(TensorFact::dt(dt).with_shape(shape))
+    }
+
+    /// Renders the datum type and shape of the fact in the format parsed by
+    /// `from_spec`.
+    pub fn to_spec(&self) -> String {
+        let mut tokens = vec![self
+            .datum_type
+            .concretize()
+            .map(|dt| format!("{:?}", dt).to_lowercase())
+            .unwrap_or_else(|| "?".to_string())];
+        for dim in self.shape.dims() {
+            tokens.push(match dim {
+                GenericFact::Only(d) if d.is_stream() => "S".to_string(),
+                GenericFact::Only(d) => format!("{:?}", d),
+                GenericFact::Any => "?".to_string(),
+            });
+        }
+        if self.shape.is_open() {
+            tokens.push("..".to_string());
+        }
+        tokens.join(",")
+    }
+
+    /// Unifies the facts like `unify`, but lets concrete float values
+    /// differing by up to `tolerance` unify.
+    pub fn unify_with_tolerance(&self, other: &Self, tolerance: f32) -> TractResult<Self> {
+        let datum_type = self.datum_type.unify(&other.datum_type).map_err(|_| {
+            UnifyError::DatatypeConflict(
+                format!("{:?}", self.datum_type),
+                format!("{:?}", other.datum_type),
+            )
+        })?;
+        let tensor = TensorFact {
+            datum_type,
+            shape: self.shape.unify(&other.shape)?,
+            value: self.value.unify_with_tolerance(&other.value, tolerance)?,
+        };
+
+        trace!("Unifying {:?} with {:?} into {:?}.", self, other, tensor);
+
+        Ok(tensor)
+    }
 }
 
 impl Fact for TensorFact {
@@ -107,15 +197,7 @@ impl Fact for TensorFact {
 
     /// Tries to unify the fact with another fact of the same type.
     fn unify(&self, other: &Self) -> TractResult<Self> {
-        let tensor = TensorFact {
-            datum_type: self.datum_type.unify(&other.datum_type)?,
-            shape: self.shape.unify(&other.shape)?,
-            value: self.value.unify(&other.value)?,
-        };
-
-        trace!("Unifying {:?} with {:?} into {:?}.", self, other, tensor);
-
-        Ok(tensor)
+        self.unify_with_tolerance(other, 0.0)
     }
 }
 
@@ -125,7 +207,7 @@ impl<V: Into<SharedTensor>> From<V> for TensorFact {
         TensorFact {
             datum_type: GenericFact::Only(v.datum_type()),
             shape: ShapeFact::from(v.shape()),
-            value: GenericFact::Only(v),
+            value: ValueFact::Only(v),
         }
     }
 }
@@ -216,47 +298,13 @@ pub type TypeFact = GenericFact<DatumType>;
 #[derive(Clone, PartialEq)]
 pub struct ShapeFact {
     open: bool,
-    dims: TVec<GenericFact<i32>>,
-    stream: Option<StreamInfo>,
+    dims: TVec<DimFact>,
 }
 
 impl ShapeFact {
     /// Constructs an open shape fact.
     pub fn open(dims: TVec<DimFact>) -> ShapeFact {
-        if let Some((ix, &d)) = dims
-            .iter()
-            .enumerate()
-            .find(|(_ix, d)| d.concretize().map(|d| d.is_stream()).unwrap_or(false))
-        {
-            let stream = Some(StreamInfo {
-                axis: ix,
-                len: d.concretize().unwrap(),
-            });
-            ShapeFact {
-                open: true,
-                dims: dims
-                    .iter()
-                    .map(|d| match d {
-                        GenericFact::Only(d) if d.is_stream() => GenericFact::Only(-1),
-                        GenericFact::Only(d) => GenericFact::Only(d.to_integer().unwrap()),
-                        GenericFact::Any => GenericFact::Any,
-                    })
-                    .collect(),
-                stream,
-            }
-        } else {
-            ShapeFact {
-                open: true,
-                dims: dims
-                    .iter()
-                    .map(|d| match d {
-                        GenericFact::Only(d) => GenericFact::Only(d.to_integer().unwrap()),
-                        GenericFact::Any => GenericFact::Any,
-                    })
-                    .collect(),
-                stream: None,
-            }
-        }
+        ShapeFact { open: true, dims }
     }
 
     pub fn is_open(&self) -> bool {
@@ -265,31 +313,32 @@ impl ShapeFact {
 
     /// Constructs a closed shape fact.
     pub fn closed(dims: TVec<DimFact>) -> ShapeFact {
-        ShapeFact {
-            open: false,
-            ..Self::open(dims)
-        }
+        ShapeFact { open: false, dims }
     }
 
-    pub fn rank(&self) -> IntFact {
+    /// Returns the rank of the shape, or `None` if the shape is open.
+    pub fn rank(&self) -> Option<usize> {
         if self.open {
-            GenericFact::Any
+            None
         } else {
-            GenericFact::Only(self.dims.len() as i32)
+            Some(self.dims.len())
         }
-        .into()
+    }
+
+    /// Returns the fact about the dimension at `axis`.
+    ///
+    /// Axes past the known dimensions of an open shape are unknown.
+    pub fn dim(&self, axis: usize) -> DimFact {
+        self.dims.get(axis).cloned().unwrap_or(GenericFact::Any)
+    }
+
+    /// Returns whether the shape is closed and all its dimensions are known.
+    pub fn is_fully_determined(&self) -> bool {
+        !self.open && self.dims.iter().all(|d| d.is_concrete())
     }
 
     pub fn dims(&self) -> impl Iterator<Item = DimFact> {
-        let stream = self.stream.clone();
-        self.dims.clone().into_iter().map(move |d| match d {
-            GenericFact::Only(-1) => {
-                assert!(stream.is_some());
-                GenericFact::Only(stream.unwrap().len)
-            }
-            GenericFact::Only(d) => GenericFact::Only(d.to_dim()),
-            GenericFact::Any => GenericFact::Any,
-        })
+        self.dims.clone().into_iter()
     }
 
     pub fn stream_info(&self) -> TractResult<Option<StreamInfo>> {
@@ -308,39 +357,174 @@ impl ShapeFact {
     }
 
     pub fn as_concrete_finite(&self) -> TractResult<Option<TVec<usize>>> {
-        if !self.is_concrete() || self.stream_info()?.is_some() {
+        if !self.is_concrete() {
             return Ok(None);
         }
-        Ok(Some(
-            self.dims
+        Ok(self
+            .dims
+            .iter()
+            .map(|d| {
+                d.concretize()
+                    .and_then(|d| d.as_const())
+                    .map(|d| d as usize)
+            })
+            .collect())
+    }
+
+    /// Lists the symbols that `refined` binds in this shape.
+    ///
+    /// A dimension that is a single symbol (other than the streaming one) in
+    /// this shape and something else in `refined` yields a binding from the
+    /// symbol to the refined dimension. An affine dimension, like `2*N+1`,
+    /// refined to a constant yields the value of its symbol.
+    pub fn symbol_bindings(&self, refined: &ShapeFact) -> Vec<(char, TDim)> {
+        self.dims
+            .iter()
+            .zip(refined.dims.iter())
+            .filter_map(|(old, new)| match (old, new) {
+                (GenericFact::Only(old), GenericFact::Only(new)) if old != new => {
+                    if let Some(s) = old.as_sym() {
+                        return Some((s, *new)).filter(|&(s, _)| s != 'S');
+                    }
+                    match new.as_const().map(|c| old.solve(c)) {
+                        Some(Ok(Some((s, v)))) if s != 'S' => Some((s, v.to_dim())),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Replaces every occurence of the symbol `s` by `value`.
+    pub fn substitute(&self, s: char, value: TDim) -> ShapeFact {
+        ShapeFact {
+            open: self.open,
+            dims: self
+                .dims
                 .iter()
-                .map(|i| i.concretize().unwrap() as usize)
+                .map(|d| match d {
+                    GenericFact::Only(d) => GenericFact::Only(d.substitute(s, value)),
+                    GenericFact::Any => GenericFact::Any,
+                })
                 .collect(),
-        ))
+        }
+    }
+
+    /// Computes the shape resulting from the numpy broadcasting of two
+    /// shapes.
+    ///
+    /// Dimensions are aligned from the trailing one. Aligned dimensions must
+    /// be equal unless one of them is 1, and the unaligned leading dimensions
+    /// come from the longest shape. A symbolic dimension facing a different
+    /// one is an error, as the broadcast would otherwise silently assume the
+    /// symbol is either 1 or equal to it. The result is open if any of the
+    /// shapes is.
+    pub fn broadcast(&self, other: &ShapeFact) -> TractResult<ShapeFact> {
+        if self.open || other.open {
+            return Ok(ShapeFact::default());
+        }
+        use itertools::EitherOrBoth::{Both, Left, Right};
+        use itertools::Itertools;
+        let is_one = |d: &DimFact| match d {
+            GenericFact::Only(d) => d.is_one(),
+            GenericFact::Any => false,
+        };
+        let mut dims = self
+            .dims
+            .iter()
+            .rev()
+            .zip_longest(other.dims.iter().rev())
+            .map(|r| match r {
+                Both(a, b) if is_one(a) => Ok(b.clone()),
+                Both(a, b) if is_one(b) => Ok(a.clone()),
+                Both(GenericFact::Any, GenericFact::Any) => Ok(GenericFact::Any),
+                Both(GenericFact::Any, d) | Both(d, GenericFact::Any) => Ok(d.clone()),
+                Both(GenericFact::Only(a), GenericFact::Only(b)) => {
+                    if a == b {
+                        Ok(GenericFact::Only(a.clone()))
+                    } else if let (Some(a), Some(b)) = (a.as_const(), b.as_const()) {
+                        bail!(
+                            "Invalid shape (broadcasting): {} is not compatible with {}.",
+                            a,
+                            b
+                        )
+                    } else {
+                        bail!(
+                            "Invalid shape (broadcasting): can not broadcast symbolic {:?} with {:?}.",
+                            a,
+                            b
+                        )
+                    }
+                }
+                Left(d) | Right(d) => Ok(d.clone()),
+            })
+            .collect::<TractResult<TVec<DimFact>>>()?;
+        dims.reverse();
+        Ok(ShapeFact::closed(dims))
     }
 }
 
+/// Unifies two dimension facts.
+///
+/// On top of the regular unification, a dimension that is a single symbol
+/// (other than the streaming one) is refined by any other dimension that does
+/// not depend on it. Between two distinct symbols, the smallest one wins.
+/// An affine dimension over a symbol, like `2*N+1`, unifies with a constant
+/// if some integer value of the symbol gives that constant.
+fn unify_dim(a: &DimFact, b: &DimFact) -> TractResult<DimFact> {
+    if let (GenericFact::Only(x), GenericFact::Only(y)) = (a, b) {
+        let solvable = |d: &TDim, other: &TDim| -> TractResult<bool> {
+            match (d.as_affine(), other.as_const()) {
+                (Some((s, _, _)), Some(c)) if s != 'S' => Ok(d.solve(c)?.is_some()),
+                _ => Ok(false),
+            }
+        };
+        if solvable(x, y)? {
+            return Ok(b.clone());
+        }
+        if solvable(y, x)? {
+            return Ok(a.clone());
+        }
+        let bindable = |d: &TDim, other: &TDim| {
+            d.as_sym()
+                .map(|s| s != 'S' && !other.has_sym(s))
+                .unwrap_or(false)
+        };
+        match (bindable(x, y), bindable(y, x)) {
+            (true, true) => {
+                return Ok(GenericFact::Only(if x.as_sym() < y.as_sym() {
+                    *x
+                } else {
+                    *y
+                }))
+            }
+            (true, false) => return Ok(b.clone()),
+            (false, true) => return Ok(a.clone()),
+            (false, false) => (),
+        }
+    }
+    a.unify(b)
+}
+
 impl Fact for ShapeFact {
     type Concrete = TVec<TDim>;
 
     /// Tries to transform the fact into a `Vec<usize>`, or returns `None`.
     fn concretize(self: &ShapeFact) -> Option<TVec<TDim>> {
-        if self.open {
-            debug!("Impossible to concretize an open shape.");
+        if !self.is_fully_determined() {
+            debug!("Impossible to concretize an open shape or a shape with unknown dimensions.");
             return None;
         }
-
-        let dims: TVec<_> = self.dims().filter_map(|d| d.concretize()).collect();
-
-        if dims.len() < self.dims.len() {
-            debug!("Impossible to concretize a shape with unknown dimensions.");
-            None
-        } else {
-            Some(dims)
-        }
+        self.dims().map(|d| d.concretize()).collect()
     }
 
     /// Tries to unify the fact with another fact of the same type.
+    ///
+    /// Dimensions are unified pairwise on the common prefix, symbols being
+    /// kept or bound as in `unify_dim`. The dimensions of the longest shape
+    /// past that prefix are kept as long as the other shape is open. Two
+    /// open shapes unify into an open shape.
     fn unify(&self, other: &Self) -> TractResult<Self> {
         let (x, y) = (self, other);
 
@@ -352,21 +536,28 @@ impl Fact for ShapeFact {
 
         let dimensions: TVec<_> = xi
             .zip_longest(yi)
-            .map(|r| match r {
-                Both(a, b) => a.unify(&b),
-                Left(d) if y.open => Ok(d),
-                Right(d) if x.open => Ok(d),
-
-                Left(_) | Right(_) => bail!(
-                    "Impossible to unify closed shapes of different rank (found {:?} and {:?}).",
-                    x,
-                    y
-                ),
+            .enumerate()
+            .map(|(axis, r)| match r {
+                Both(a, b) => unify_dim(&a, &b).map_err(|_| {
+                    UnifyError::DimConflict {
+                        shapes: (format!("{:?}", x), format!("{:?}", y)),
+                        axis,
+                        a: format!("{:?}", a),
+                        b: format!("{:?}", b),
+                    }
+                    .into()
+                }),
+                Left(d) if y.rank().is_none() => Ok(d),
+                Right(d) if x.rank().is_none() => Ok(d),
+                Left(_) | Right(_) => Err(UnifyError::ShapeRankConflict(
+                    format!("{:?}", x),
+                    format!("{:?}", y),
+                )
+                .into()),
             })
-            .collect::<TractResult<_>>()
-            .map_err(|e| format!("Unifying shapes {:?} and {:?}, {}", x, y, e))?;
+            .collect::<TractResult<_>>()?;
 
-        if x.open && y.open {
+        if x.rank().is_none() && y.rank().is_none() {
             Ok(ShapeFact::open(dimensions))
         } else {
             Ok(ShapeFact::closed(dimensions))
@@ -463,7 +654,127 @@ impl fmt::Debug for ShapeFact {
 pub type DimFact = GenericFact<TDim>;
 
 /// Partial information about a value.
-pub type ValueFact = GenericFact<SharedTensor>;
+///
+/// On top of the fully known (`Only`) and unknown (`Any`) cases, integer-like
+/// values can be known element by element (`Partial`), unknown elements being
+/// `Any`. This is typically what comes out of a `Shape` op on a tensor with
+/// some unknown dimensions.
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Clone, PartialEq)]
+pub enum ValueFact {
+    Only(SharedTensor),
+    #[cfg_attr(feature = "serialize", serde(skip_serializing))]
+    Partial(ArrayD<DimFact>),
+    Any,
+}
+
+impl ValueFact {
+    fn conflict(&self, other: &ValueFact) -> UnifyError {
+        UnifyError::ValueConflict(format!("{:?}", self), format!("{:?}", other))
+    }
+
+    /// Unifies the facts like `unify`, but lets concrete float values
+    /// differing by up to `tolerance` unify, keeping `self`.
+    pub fn unify_with_tolerance(&self, other: &Self, tolerance: f32) -> TractResult<Self> {
+        if tolerance > 0.0 {
+            if let (ValueFact::Only(a), ValueFact::Only(b)) = (self, other) {
+                if a.as_tensor().approx_eq(b.as_tensor(), tolerance) {
+                    return Ok(self.clone());
+                }
+            }
+        }
+        self.unify(other)
+    }
+
+    /// Returns the element by element view of the value, if anything is
+    /// known about it.
+    ///
+    /// Only TDim and signed integer values can be viewed as dimensions.
+    pub fn partial(&self) -> TractResult<Option<ArrayD<DimFact>>> {
+        match self {
+            ValueFact::Any => Ok(None),
+            ValueFact::Partial(p) => Ok(Some(p.clone())),
+            ValueFact::Only(t) => {
+                let dims = match t.datum_type() {
+                    DatumType::TDim => t.to_array_view::<TDim>()?.to_owned(),
+                    DatumType::I8 | DatumType::I16 | DatumType::I32 | DatumType::I64 => t
+                        .cast_to::<i64>()?
+                        .to_array_view::<i64>()?
+                        .mapv(|d| d.to_dim()),
+                    dt => bail!("Can not view a {:?} value as dimensions", dt),
+                };
+                Ok(Some(dims.mapv(GenericFact::Only)))
+            }
+        }
+    }
+}
+
+impl Fact for ValueFact {
+    type Concrete = SharedTensor;
+
+    /// Tries to transform the fact into a concrete value.
+    fn concretize(&self) -> Option<SharedTensor> {
+        match self {
+            ValueFact::Only(t) => Some(t.clone()),
+            _ => None,
+        }
+    }
+
+    /// Tries to unify the fact with another fact of the same type.
+    fn unify(&self, other: &Self) -> TractResult<Self> {
+        let fact = match (self, other) {
+            (_, ValueFact::Any) => self.clone(),
+            (ValueFact::Any, _) => other.clone(),
+            _ if self == other => self.clone(),
+            (ValueFact::Only(_), ValueFact::Only(_)) => return Err(self.conflict(other).into()),
+            _ => {
+                let (a, b) = (self.partial()?.unwrap(), other.partial()?.unwrap());
+                if a.shape() != b.shape() {
+                    return Err(self.conflict(other).into());
+                }
+                let mut unified = a.clone();
+                for ((u, a), b) in unified.iter_mut().zip(a.iter()).zip(b.iter()) {
+                    *u = unify_dim(a, b).map_err(|_| self.conflict(other))?;
+                }
+                match (self, other) {
+                    (ValueFact::Only(t), _) | (_, ValueFact::Only(t)) => {
+                        if unified != self.partial()?.unwrap()
+                            && unified != other.partial()?.unwrap()
+                        {
+                            return Err(self.conflict(other).into());
+                        }
+                        ValueFact::Only(t.clone())
+                    }
+                    _ => ValueFact::Partial(unified),
+                }
+            }
+        };
+
+        Ok(fact)
+    }
+}
+
+impl Default for ValueFact {
+    fn default() -> ValueFact {
+        ValueFact::Any
+    }
+}
+
+impl From<SharedTensor> for ValueFact {
+    fn from(t: SharedTensor) -> ValueFact {
+        ValueFact::Only(t)
+    }
+}
+
+impl fmt::Debug for ValueFact {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ValueFact::Any => write!(formatter, "?"),
+            ValueFact::Only(t) => write!(formatter, "{:?}", t),
+            ValueFact::Partial(p) => write!(formatter, "Partial {:?}", p),
+        }
+    }
+}
 
 pub type IntFact = GenericFact<i32>;
 
@@ -605,3 +916,367 @@ where
     }
 }
 */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn open_shape_has_no_rank() {
+        let shape = ShapeFact::open(tvec![1.to_dim().into(), 2.to_dim().into()]);
+        assert_eq!(shape.rank(), None);
+        assert_eq!(shape.dim(1), GenericFact::Only(2.to_dim()));
+        assert_eq!(shape.dim(2), GenericFact::Any);
+        assert!(!shape.is_fully_determined());
+    }
+
+    #[test]
+    fn broadcast_shapes() {
+        // None stands for an unknown dimension
+        let shape = |dims: &[Option<usize>]| {
+            ShapeFact::closed(
+                dims.iter()
+                    .map(|d| match d {
+                        Some(d) => GenericFact::Only(d.to_dim()),
+                        None => GenericFact::Any,
+                    })
+                    .collect(),
+            )
+        };
+        let a = shape(&[Some(1), Some(3)]);
+        let b = shape(&[Some(4), Some(1)]);
+        assert_eq!(a.broadcast(&b).unwrap(), shape(&[Some(4), Some(3)]));
+        assert_eq!(
+            shape(&[Some(2), Some(1), Some(3)])
+                .broadcast(&shape(&[None, Some(1)]))
+                .unwrap(),
+            shape(&[Some(2), None, Some(3)])
+        );
+        assert_eq!(
+            shape(&[None, Some(3)])
+                .broadcast(&shape(&[Some(5), Some(3)]))
+                .unwrap(),
+            shape(&[Some(5), Some(3)])
+        );
+        assert!(shape(&[Some(2), Some(3)])
+            .broadcast(&shape(&[Some(4), Some(3)]))
+            .is_err());
+        let n = ShapeFact::closed(tvec![TDim::sym('N').into()]);
+        let m = ShapeFact::closed(tvec![TDim::sym('M').into()]);
+        assert_eq!(n.broadcast(&n).unwrap(), n);
+        assert_eq!(n.broadcast(&shape(&[Some(1)])).unwrap(), n);
+        assert!(n.broadcast(&m).is_err());
+        assert!(n.broadcast(&shape(&[Some(3)])).is_err());
+        assert!(ShapeFact::open(tvec![1.to_dim().into()])
+            .broadcast(&b)
+            .unwrap()
+            .is_open());
+    }
+
+    #[test]
+    fn unify_solves_affine_dimensions() {
+        let n = TDim::sym('N');
+        let dim = |d: TDim| -> DimFact { GenericFact::Only(d) };
+        let shape = |d: TDim| ShapeFact::closed(tvec!(dim(d)));
+        assert_eq!(
+            unify_dim(&dim(n * 2 + 1), &dim(9.to_dim())).unwrap(),
+            dim(9.to_dim())
+        );
+        assert!(unify_dim(&dim(n * 2), &dim(7.to_dim())).is_err());
+        assert_eq!(
+            shape(n * 2 + 1).symbol_bindings(&shape(9.to_dim())),
+            vec![('N', 4.to_dim())]
+        );
+        // the stream dimension is never bound
+        assert!(unify_dim(&dim(TDim::s() * 2), &dim(8.to_dim())).is_err());
+    }
+
+    #[test]
+    fn unify_open_shapes_keeps_symbols_and_tails() {
+        let a = ShapeFact::open(tvec![TDim::sym('N').into(), GenericFact::Any]);
+        let b = ShapeFact::open(tvec![
+            GenericFact::Any,
+            5.to_dim().into(),
+            7.to_dim().into()
+        ]);
+        let unified = a.unify(&b).unwrap();
+        assert_eq!(
+            unified,
+            ShapeFact::open(tvec![
+                TDim::sym('N').into(),
+                5.to_dim().into(),
+                7.to_dim().into()
+            ])
+        );
+        assert_eq!(b.unify(&a).unwrap(), unified);
+
+        let c = ShapeFact::open(tvec![3.to_dim().into()]);
+        let d = ShapeFact::open(tvec![4.to_dim().into(), GenericFact::Any]);
+        assert!(c.unify(&d).is_err());
+    }
+
+    #[test]
+    fn closed_shape_with_known_dims_is_fully_determined() {
+        let shape: ShapeFact = [1usize, 2, 3].iter().cloned().collect();
+        assert_eq!(shape.rank(), Some(3));
+        assert_eq!(shape.dim(2), GenericFact::Only(3.to_dim()));
+        assert!(shape.is_fully_determined());
+        let partial = ShapeFact::closed(tvec![
+            1.to_dim().into(),
+            GenericFact::Any,
+            3.to_dim().into()
+        ]);
+        assert!(!partial.is_fully_determined());
+    }
+
+    #[test]
+    fn unify_errors_tell_conflicts_apart() {
+        use crate::TractErrorKind;
+        let kind = |a: TensorFact, b: TensorFact| match a.unify(&b).unwrap_err().kind() {
+            TractErrorKind::Unify(e) => e.clone(),
+            e => panic!("unexpected error {:?}", e),
+        };
+        match kind(
+            TensorFact::dt(DatumType::F32),
+            TensorFact::dt(DatumType::I32),
+        ) {
+            UnifyError::DatatypeConflict(..) => (),
+            e => panic!("unexpected error {:?}", e),
+        }
+        match kind(TensorFact::shape(vec![2]), TensorFact::shape(vec![2, 3])) {
+            UnifyError::ShapeRankConflict(..) => (),
+            e => panic!("unexpected error {:?}", e),
+        }
+        assert_eq!(
+            kind(TensorFact::shape(vec![2, 3]), TensorFact::shape(vec![2, 4])),
+            UnifyError::DimConflict {
+                shapes: ("[2,3]".to_string(), "[2,4]".to_string()),
+                axis: 1,
+                a: "3".to_string(),
+                b: "4".to_string()
+            }
+        );
+        let err = TensorFact::shape(vec![2, 3])
+            .unify(&TensorFact::shape(vec![2, 4]))
+            .unwrap_err();
+        assert_eq!(
+            format!("{}", err),
+            "Unifying shapes [2,3] and [2,4], Impossible to unify 3 with 4 (axis 1)."
+        );
+    }
+
+    #[test]
+    fn unify_float_values_with_tolerance() {
+        let a: ValueFact = SharedTensor::from(Tensor::from(1.0000001f32)).into();
+        let b: ValueFact = SharedTensor::from(Tensor::from(1.0f32)).into();
+        assert!(a.unify(&b).is_err());
+        assert!(a.unify_with_tolerance(&b, 0.0).is_err());
+        assert_eq!(a.unify_with_tolerance(&b, 1e-5).unwrap(), a);
+        let a: ValueFact = SharedTensor::from(Tensor::from(1i32)).into();
+        let b: ValueFact = SharedTensor::from(Tensor::from(2i32)).into();
+        assert!(a.unify_with_tolerance(&b, 10.0).is_err());
+    }
+
+    #[test]
+    fn partial_values_are_integers() {
+        let big: ValueFact = SharedTensor::from(Tensor::from(ndarray::arr1(&[1i64 << 40]))).into();
+        assert_eq!(
+            big.partial().unwrap().unwrap(),
+            ndarray::arr1(&[GenericFact::Only((1i64 << 40).to_dim())]).into_dyn()
+        );
+        let float: ValueFact = SharedTensor::from(Tensor::from(ndarray::arr1(&[2.5f32]))).into();
+        assert!(float.partial().is_err());
+        let partial = ValueFact::Partial(ndarray::arr1(&[GenericFact::Any]).into_dyn());
+        assert!(float.unify(&partial).is_err());
+    }
+
+    #[test]
+    fn fact_from_spec() {
+        let fact = TensorFact::from_spec("f32,1,3,?,?").unwrap();
+        assert_eq!(
+            fact,
+            TensorFact::dt_shape(
+                DatumType::F32,
+                ShapeFact::closed(tvec![
+                    1.to_dim().into(),
+                    3.to_dim().into(),
+                    GenericFact::Any,
+                    GenericFact::Any
+                ])
+            )
+        );
+        let open = TensorFact::from_spec("i64,S,..").unwrap();
+        assert_eq!(open.shape, ShapeFact::open(tvec![TDim::s().into()]));
+        assert_eq!(TensorFact::from_spec("bool").unwrap().shape.rank(), Some(0));
+    }
+
+    #[test]
+    fn fact_spec_round_trip() {
+        for spec in &["f32,1,3,?,?", "i64,S,..", "tdim", "u8,2,.."] {
+            assert_eq!(&TensorFact::from_spec(spec).unwrap().to_spec(), spec);
+        }
+    }
+
+    #[test]
+    fn fact_spec_errors() {
+        assert!(TensorFact::from_spec("float,1,3").is_err());
+        assert!(TensorFact::from_spec("f32,1,x").is_err());
+        assert!(TensorFact::from_spec("f32,..,1").is_err());
+    }
+
+    #[test]
+    fn fact_from_tensor() {
+        let t = Tensor::from(::ndarray::arr2(&[[1i64, 2], [3, 4]]));
+        let fact = TensorFact::from_tensor(&t);
+        assert_eq!(fact.datum_type, TypeFact::Only(DatumType::I64));
+        assert_eq!(fact.shape, ShapeFact::from(&[2usize, 2][..]));
+        assert!(!fact.shape.is_open());
+        assert_eq!(fact.value.concretize(), Some(t.into()));
+    }
+
+    mod laws {
+        use super::*;
+        use ndarray::arr1;
+        use proptest::prelude::*;
+        use proptest::*;
+
+        pub fn strat_type_fact() -> BoxedStrategy<TypeFact> {
+            prop_oneof![
+                Just(GenericFact::Any),
+                prop::sample::select(vec![DatumType::F32, DatumType::I32, DatumType::I64])
+                    .prop_map(GenericFact::Only),
+            ]
+            .boxed()
+        }
+
+        pub fn strat_dim_fact() -> BoxedStrategy<DimFact> {
+            let n = TDim::sym('N');
+            let m = TDim::sym('M');
+            prop_oneof![
+                Just(GenericFact::Any),
+                (1i32..4).prop_map(|d| GenericFact::Only(d.to_dim())),
+                prop::sample::select(vec![n, m, n * 2 + 1, m + 1, TDim::s()])
+                    .prop_map(GenericFact::Only),
+            ]
+            .boxed()
+        }
+
+        pub fn strat_shape_fact() -> BoxedStrategy<ShapeFact> {
+            (any::<bool>(), prop::collection::vec(strat_dim_fact(), 0..3))
+                .prop_map(|(open, dims)| {
+                    if open {
+                        ShapeFact::open(dims.into_iter().collect())
+                    } else {
+                        ShapeFact::closed(dims.into_iter().collect())
+                    }
+                })
+                .boxed()
+        }
+
+        pub fn strat_value_fact() -> BoxedStrategy<ValueFact> {
+            prop_oneof![
+                Just(ValueFact::Any),
+                prop::collection::vec(1i32..4, 2)
+                    .prop_map(|v| ValueFact::Only(Tensor::from(arr1(&v)).into())),
+                prop::collection::vec(strat_dim_fact(), 2)
+                    .prop_map(|v| ValueFact::Partial(arr1(&v).into_dyn())),
+            ]
+            .boxed()
+        }
+
+        pub fn strat_tensor_fact() -> BoxedStrategy<TensorFact> {
+            (strat_type_fact(), strat_shape_fact(), strat_value_fact())
+                .prop_map(|(datum_type, shape, value)| TensorFact {
+                    datum_type,
+                    shape,
+                    value,
+                })
+                .boxed()
+        }
+
+        fn commutes<F: Fact + fmt::Debug>(a: &F, b: &F) -> Result<(), TestCaseError> {
+            match (a.unify(b), b.unify(a)) {
+                (Ok(ab), Ok(ba)) => prop_assert_eq!(ab, ba),
+                (Err(_), Err(_)) => (),
+                (ab, ba) => prop_assert!(false, "{:?} vs {:?}", ab, ba),
+            }
+            Ok(())
+        }
+
+        fn associates<F: Fact + fmt::Debug>(a: &F, b: &F, c: &F) -> Result<(), TestCaseError> {
+            let left = a.unify(b).and_then(|ab| ab.unify(c));
+            let right = b.unify(c).and_then(|bc| a.unify(&bc));
+            if let (Ok(left), Ok(right)) = (left, right) {
+                prop_assert_eq!(left, right);
+            }
+            Ok(())
+        }
+
+        proptest! {
+            #[test]
+            fn type_facts_commute(a in strat_type_fact(), b in strat_type_fact()) {
+                commutes(&a, &b)?
+            }
+
+            #[test]
+            fn type_facts_associate(
+                a in strat_type_fact(),
+                b in strat_type_fact(),
+                c in strat_type_fact()
+            ) {
+                associates(&a, &b, &c)?
+            }
+
+            #[test]
+            fn dim_facts_commute(a in strat_dim_fact(), b in strat_dim_fact()) {
+                match (unify_dim(&a, &b), unify_dim(&b, &a)) {
+                    (Ok(ab), Ok(ba)) => prop_assert_eq!(ab, ba),
+                    (Err(_), Err(_)) => (),
+                    (ab, ba) => prop_assert!(false, "{:?} vs {:?}", ab, ba),
+                }
+            }
+
+            #[test]
+            fn shape_facts_commute(a in strat_shape_fact(), b in strat_shape_fact()) {
+                commutes(&a, &b)?
+            }
+
+            #[test]
+            fn shape_facts_associate(
+                a in strat_shape_fact(),
+                b in strat_shape_fact(),
+                c in strat_shape_fact()
+            ) {
+                associates(&a, &b, &c)?
+            }
+
+            #[test]
+            fn value_facts_commute(a in strat_value_fact(), b in strat_value_fact()) {
+                commutes(&a, &b)?
+            }
+
+            #[test]
+            fn value_facts_associate(
+                a in strat_value_fact(),
+                b in strat_value_fact(),
+                c in strat_value_fact()
+            ) {
+                associates(&a, &b, &c)?
+            }
+
+            #[test]
+            fn tensor_facts_commute(a in strat_tensor_fact(), b in strat_tensor_fact()) {
+                commutes(&a, &b)?
+            }
+
+            #[test]
+            fn tensor_facts_associate(
+                a in strat_tensor_fact(),
+                b in strat_tensor_fact(),
+                c in strat_tensor_fact()
+            ) {
+                associates(&a, &b, &c)?
+            }
+        }
+    }
+}
@@ -29,6 +29,13 @@ pub enum DatumType {
 }
 
 impl DatumType {
+    pub fn is_float(&self) -> bool {
+        match self {
+            DatumType::F16 | DatumType::F32 | DatumType::F64 => true,
+            _ => false,
+        }
+    }
+
     pub fn super_types(&self) -> &'static [DatumType] {
         match self {
             DatumType::Bool => &[DatumType::Bool],
@@ -94,6 +101,7 @@ impl DatumType {
         return None;
     }
 
+    /// Size of one element of this type, in bytes.
     pub fn size_of(&self) -> usize {
         match self {
             DatumType::Bool => std::mem::size_of::<bool>(),
@@ -120,6 +128,29 @@ impl DatumType {
     }
 }
 
+impl std::str::FromStr for DatumType {
+    type Err = crate::TractError;
+
+    /// Parses a datum type name, like `f32` or `tdim`, case insensitively.
+    fn from_str(s: &str) -> TractResult<DatumType> {
+        Ok(match s.to_lowercase().as_str() {
+            "bool" => DatumType::Bool,
+            "u8" => DatumType::U8,
+            "u16" => DatumType::U16,
+            "i8" => DatumType::I8,
+            "i16" => DatumType::I16,
+            "i32" => DatumType::I32,
+            "i64" => DatumType::I64,
+            "f16" => DatumType::F16,
+            "f32" => DatumType::F32,
+            "f64" => DatumType::F64,
+            "tdim" => DatumType::TDim,
+            "string" => DatumType::String,
+            _ => bail!("Unknown datum type {:?}", s),
+        })
+    }
+}
+
 pub trait Datum:
     Copy + Clone + Send + Sync + fmt::Debug + fmt::Display + Default + 'static + PartialEq
 {
@@ -127,6 +158,11 @@ pub trait Datum:
     fn datum_type() -> DatumType;
 
     fn packed_mat_mul(m: usize, k: usize, n: usize) -> Option<Box<tract_linalg::MatMul<Self>>>;
+
+    /// Renders a single element, for dumps and traces.
+    fn format_elem(&self) -> String {
+        format!("{}", self)
+    }
 }
 
 pub(crate) trait TryInto<D: Datum> {
@@ -195,6 +231,28 @@ try_into!(i8, f32);
 try_into!(i16, f32);
 try_into!(i32, f32);
 try_into!(i64, f32);
+try_into!(u8, f32);
+try_into!(u16, f32);
+
+try_into!(u8, i32);
+try_into!(u16, i32);
+try_into!(u8, i64);
+try_into!(u16, i64);
+
+// float to integer `as` conversions truncate toward zero and saturate out of
+// range values, NaN becoming 0.
+try_into!(f32, i8);
+try_into!(f32, i16);
+try_into!(f32, i32);
+try_into!(f32, i64);
+try_into!(f32, u8);
+try_into!(f32, u16);
+try_into!(f64, i8);
+try_into!(f64, i16);
+try_into!(f64, i32);
+try_into!(f64, i64);
+try_into!(f64, u8);
+try_into!(f64, u16);
 
 impl TryInto<TDim> for i32 {
     fn try_into(self) -> TractResult<TDim> {
@@ -271,6 +329,13 @@ mod tests {
     use crate::datum::*;
     use crate::dim::ToDim;
 
+    #[test]
+    fn size_of() {
+        assert_eq!(DatumType::F32.size_of(), 4);
+        assert_eq!(DatumType::F64.size_of(), 8);
+        assert_eq!(DatumType::I8.size_of(), 1);
+    }
+
     #[test]
     fn test_array_to_tensor_to_array() {
         let array = arr1(&[12i32, 42]);
@@ -292,4 +357,41 @@ mod tests {
         let t_i32: Tensor = arr1(&[0i32, 0]).into();
         t_i32.cast_to::<TDim>().unwrap();
     }
+
+    #[test]
+    fn test_dump_values() {
+        let t: Tensor = arr1(&[1i32, 2, 3, 4, 5]).into();
+        assert_eq!(t.dump_values(3), "[1, 2, 3, ...]");
+        assert_eq!(t.dump_values(5), "[1, 2, 3, 4, 5]");
+        let t: Tensor = arr2(&[[true, false]]).into();
+        assert_eq!(t.dump_values(8), "[true, false]");
+    }
+
+    #[test]
+    fn test_cast_f16_roundtrip() {
+        let t_f32: Tensor = arr1(&[0.5f32, -2.0, 1024.0]).into();
+        let t_f16 = t_f32.cast_to::<f16>().unwrap();
+        assert_eq!(t_f16.datum_type(), DatumType::F16);
+        assert_eq!(t_f16.to_f32().unwrap().into_owned(), t_f32);
+        let t_f64 = t_f16.cast_to::<f64>().unwrap();
+        assert_eq!(
+            t_f64.cast_to::<f16>().unwrap().into_owned(),
+            t_f16.into_owned()
+        );
+    }
+
+    #[test]
+    fn test_unify_f16_values() {
+        use crate::analyser::types::{Fact, ValueFact};
+        use crate::SharedTensor;
+        let t: Tensor = arr1(&[1.5f32, 3.0]).into();
+        let a = ValueFact::from(SharedTensor::from(t.cast_to::<f16>().unwrap().into_owned()));
+        let b = a.clone();
+        assert_eq!(a.unify(&b).unwrap(), a);
+        let other: Tensor = arr1(&[1.5f32, 4.0]).into();
+        let c = ValueFact::from(SharedTensor::from(
+            other.cast_to::<f16>().unwrap().into_owned(),
+        ));
+        assert!(a.unify(&c).is_err());
+    }
 }
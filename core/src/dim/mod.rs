@@ -8,7 +8,7 @@ use num_traits::Zero;
 mod stack;
 mod tree;
 
-use self::stack::Stack;
+use self::stack::{Stack, StackOp};
 use crate::TractResult;
 
 pub trait DimLike:
@@ -85,6 +85,63 @@ impl TDim {
         Self::s()
     }
 
+    /// A symbolic dimension, only known at runtime.
+    ///
+    /// `S` is reserved for the streaming dimension.
+    pub fn sym(s: char) -> TDim {
+        TDim(Stack::sym(s))
+    }
+
+    /// Returns the symbol if the dimension is a single symbol.
+    pub fn as_sym(&self) -> Option<char> {
+        match self.0.as_ops() {
+            [StackOp::Sym(s)] => Some(*s),
+            _ => None,
+        }
+    }
+
+    pub fn has_sym(&self, s: char) -> bool {
+        self.0.has_sym(s)
+    }
+
+    /// Replaces every occurence of the symbol `s` by `value`.
+    pub fn substitute(&self, s: char, value: TDim) -> TDim {
+        TDim(self.0.substitute(s, &value.0))
+    }
+
+    /// Decomposes the dimension as `a * s + b`, for its only symbol `s`.
+    ///
+    /// Returns None for constants, and for expressions that are not affine,
+    /// like the ones involving a division.
+    pub fn as_affine(&self) -> Option<(char, i32, i32)> {
+        match self.0.to_tree().as_affine() {
+            Some((Some(s), a, b)) if a != 0 => Some((s, a, b)),
+            _ => None,
+        }
+    }
+
+    /// Finds the value of the symbol making this affine dimension equal to
+    /// `value`.
+    ///
+    /// Returns None if the dimension is not affine, and an error if no
+    /// integer value of the symbol works.
+    pub fn solve(&self, value: i32) -> TractResult<Option<(char, i32)>> {
+        match self.as_affine() {
+            Some((s, a, b)) => {
+                if (value - b) % a != 0 {
+                    bail!(
+                        "No integer value of {} makes {:?} equal to {}",
+                        s,
+                        self,
+                        value
+                    )
+                }
+                Ok(Some((s, (value - b) / a)))
+            }
+            None => Ok(None),
+        }
+    }
+
     pub fn as_const(&self) -> Option<i32> {
         self.to_integer().ok()
     }
@@ -94,7 +151,7 @@ impl TDim {
     }
 
     pub fn is_stream(&self) -> bool {
-        self.as_const().is_none()
+        self.has_sym('S')
     }
 
     pub fn to_integer(&self) -> TractResult<i32> {
@@ -272,3 +329,27 @@ impl<'a> From<&'a usize> for TDim {
         TDim((*it as i32).into())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn affine() {
+        let n = TDim::sym('N');
+        assert_eq!(n.as_affine(), Some(('N', 1, 0)));
+        assert_eq!((n * 2 + 3).as_affine(), Some(('N', 2, 3)));
+        assert_eq!(((n + 1) * 2).as_affine(), Some(('N', 2, 2)));
+        assert_eq!((n / 2).as_affine(), None);
+        assert_eq!((n * TDim::sym('M')).as_affine(), None);
+        assert_eq!(3.to_dim().as_affine(), None);
+    }
+
+    #[test]
+    fn solve() {
+        let n = TDim::sym('N');
+        assert_eq!((n * 2 + 1).solve(9).unwrap(), Some(('N', 4)));
+        assert!((n * 2).solve(7).is_err());
+        assert_eq!((n / 2).solve(4).unwrap(), None);
+    }
+}
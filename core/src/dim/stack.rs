@@ -159,6 +159,25 @@ impl Stack {
     pub fn to_tree(&self) -> ExpNode {
         ExpNode::from_ops(self)
     }
+
+    pub fn has_sym(&self, s: char) -> bool {
+        self.as_ops().contains(&StackOp::Sym(s))
+    }
+
+    pub fn substitute(&self, s: char, value: &Stack) -> Stack {
+        if !self.has_sym(s) {
+            return *self;
+        }
+        let mut e = Stack::empty();
+        for op in self.as_ops() {
+            if *op == StackOp::Sym(s) {
+                e.push_all(value.as_ops());
+            } else {
+                e.push(*op);
+            }
+        }
+        e.to_tree().reduce().to_stack()
+    }
 }
 
 impl From<i32> for Stack {
@@ -320,6 +339,13 @@ mod tests {
         assert_eq!(e.eval(&hashmap! {}).unwrap(), -2);
     }
 
+    #[test]
+    fn substitute_sym() {
+        let e = Stack::sym('N') * 3 + 1;
+        let e = e.substitute('N', &Stack::from(2));
+        assert_eq!(e.eval(&hashmap! {}).unwrap(), 7);
+    }
+
     #[test]
     fn simple_error_cases() {
         let e = Stack::empty();
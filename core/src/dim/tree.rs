@@ -134,6 +134,33 @@ impl ExpNode {
         }
     }
 
+    /// Decomposes a reduced expression as `a * s + b`, `s` being its only
+    /// symbol if any. Returns None if the expression is not affine.
+    pub fn as_affine(&self) -> Option<(Option<char>, i32, i32)> {
+        use self::ExpNode::*;
+        match self {
+            Val(v) => Some((None, 0, *v)),
+            Sym(s) => Some((Some(*s), 1, 0)),
+            Add(terms) => terms.iter().try_fold((None, 0, 0), |(s, a, b), term| {
+                let (ts, ta, tb) = term.as_affine()?;
+                match (s, ts) {
+                    (Some(s), Some(ts)) if s != ts => None,
+                    _ => Some((s.or(ts), a + ta, b + tb)),
+                }
+            }),
+            Mul(k, factors) => {
+                factors
+                    .iter()
+                    .try_fold((None, 0, *k), |(s, a, b), f| match (s, f.as_affine()?) {
+                        (Some(_), (Some(_), _, _)) => None,
+                        (Some(s), (None, _, fb)) => Some((Some(s), a * fb, b * fb)),
+                        (None, (fs, fa, fb)) => Some((fs, b * fa, b * fb)),
+                    })
+            }
+            _ => None,
+        }
+    }
+
     pub fn reduce(self) -> ExpNode {
         macro_rules! b( ($e:expr) => { Box::new($e) } );
         use self::ExpNode::*;
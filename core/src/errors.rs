@@ -13,5 +13,52 @@ error_chain! {
     }
     errors {
         TFString {}
+        Unify(e: UnifyError) {
+            description("facts can not be unified")
+            display("{}", e)
+        }
+    }
+}
+
+/// The ways unifying two facts can fail.
+///
+/// The facts are kept in their `Debug` rendering. Dimension conflicts also
+/// carry the shapes being unified.
+#[derive(Debug, Clone, PartialEq)]
+pub enum UnifyError {
+    DatatypeConflict(String, String),
+    ShapeRankConflict(String, String),
+    DimConflict {
+        shapes: (String, String),
+        axis: usize,
+        a: String,
+        b: String,
+    },
+    ValueConflict(String, String),
+}
+
+impl ::std::fmt::Display for UnifyError {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        match self {
+            UnifyError::DatatypeConflict(a, b) | UnifyError::ValueConflict(a, b) => {
+                write!(f, "Impossible to unify {} with {}.", a, b)
+            }
+            UnifyError::ShapeRankConflict(a, b) => write!(
+                f,
+                "Unifying shapes {} and {}, Impossible to unify closed shapes of different rank (found {} and {}).",
+                a, b, a, b
+            ),
+            UnifyError::DimConflict { shapes, axis, a, b } => write!(
+                f,
+                "Unifying shapes {} and {}, Impossible to unify {} with {} (axis {}).",
+                shapes.0, shapes.1, a, b, axis
+            ),
+        }
+    }
+}
+
+impl From<UnifyError> for TractError {
+    fn from(e: UnifyError) -> TractError {
+        TractErrorKind::Unify(e).into()
     }
 }
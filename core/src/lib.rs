@@ -80,6 +80,8 @@ extern crate matrixmultiply;
 extern crate objekt;
 #[cfg(test)]
 extern crate proptest;
+#[cfg(feature = "parallel")]
+extern crate rayon;
 #[cfg(feature = "serialize")]
 extern crate serde;
 #[cfg(test)]
@@ -88,6 +90,9 @@ extern crate smallvec;
 #[cfg(feature = "serialize")]
 #[macro_use]
 extern crate serde_derive;
+#[cfg(feature = "serialize")]
+#[macro_use]
+extern crate serde_json;
 
 extern crate tract_linalg;
 
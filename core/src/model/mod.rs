@@ -1,10 +1,10 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::str;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 
 pub mod dsl;
 mod order;
-pub use self::order::eval_order;
+pub use self::order::{eval_order, eval_order_for_nodes, memory_aware_eval_order};
 pub use crate::analyser::types::TensorFact;
 use crate::context::Context;
 
@@ -45,6 +45,10 @@ impl Node {
 pub struct OutletFact {
     pub fact: TensorFact,
     pub successors: Vec<InletId>,
+    /// Node whose inference last narrowed the fact, if any.
+    pub source_node: Option<usize>,
+    /// Analyser pass during which the fact was last narrowed.
+    pub source_pass: usize,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
@@ -83,6 +87,11 @@ pub struct Model {
     nodes_by_name: HashMap<String, usize>,
     pub(crate) inputs: Vec<OutletId>,
     pub(crate) outputs: Vec<OutletId>,
+    /// Nodes whose facts were hinted, so that pruning them can be reported.
+    pub(crate) hinted: HashSet<usize>,
+    /// Successors of each node, computed on demand and reset whenever the
+    /// graph is mutated.
+    successors: OnceLock<Vec<Vec<usize>>>,
 }
 
 impl Default for Model {
@@ -93,6 +102,8 @@ impl Default for Model {
             nodes_by_name: HashMap::new(),
             inputs: vec![],
             outputs: vec![],
+            hinted: HashSet::new(),
+            successors: OnceLock::new(),
         }
     }
 }
@@ -103,6 +114,7 @@ impl Model {
     }
 
     pub fn add_node(&mut self, name: String, op: Box<ops::Op>) -> TractResult<usize> {
+        self.successors.take();
         let id = self.nodes.len();
         self.nodes_by_name.insert(name.clone(), id);
         let is_input = op.name() == "Source";
@@ -112,7 +124,9 @@ impl Model {
             name,
             op,
             inputs: vec![],
-            outputs: tvec!(OutletFact::default()),
+            outputs: (0..noutputs.max(1))
+                .map(|_| OutletFact::default())
+                .collect(),
         };
         if is_input {
             self.inputs.push(OutletId::new(id, 0));
@@ -125,6 +139,7 @@ impl Model {
     }
 
     pub fn clear_inputs(&mut self, node: usize) -> TractResult<()> {
+        self.successors.take();
         for ix in 0..self.nodes[node].inputs.len() {
             let previous = self.nodes[node].inputs[ix];
             self.nodes[previous.node].outputs[previous.slot]
@@ -136,6 +151,7 @@ impl Model {
     }
 
     pub fn add_edge(&mut self, outlet: OutletId, inlet: InletId) -> TractResult<()> {
+        self.successors.take();
         if let Some(previous) = self.nodes[inlet.node].inputs.get(inlet.slot).cloned() {
             self.nodes[previous.node].outputs[previous.slot]
                 .successors
@@ -173,6 +189,7 @@ impl Model {
             })
             .collect::<TractResult<_>>()?;
         self.inputs = ids;
+        self.successors.take();
         for &i in &self.inputs {
             self.nodes[i.node].inputs.clear();
             self.nodes[i.node].op = Box::new(Source::default());
@@ -288,6 +305,63 @@ impl Model {
         eval_order(&self)
     }
 
+    /// Extracts the nodes needed to compute `outputs` into a new model.
+    pub fn subgraph(&self, outputs: &[usize]) -> TractResult<Model> {
+        self.subgraph_from(self.inputs()?, outputs)
+    }
+
+    /// Extracts the nodes needed to compute `outputs` from `inputs` into a
+    /// new model.
+    ///
+    /// Each of the `inputs` becomes a Source placeholder of the new model,
+    /// carrying the fact of the outlet. Node ids are remapped, names are
+    /// kept, except for the placeholders of outlets other than the first one
+    /// of a node, named like `node#slot`.
+    pub fn subgraph_from(&self, inputs: &[OutletId], outputs: &[usize]) -> TractResult<Model> {
+        use crate::ops::source::Source;
+        let input_nodes: Vec<usize> = inputs.iter().map(|i| i.node).collect();
+        let mut model = Model::default().with_context(self.ctx.clone());
+        let mut mapping: HashMap<OutletId, OutletId> = HashMap::new();
+        for old_id in eval_order_for_nodes(&self.nodes, &input_nodes, outputs)? {
+            let node = &self.nodes[old_id];
+            if input_nodes.contains(&old_id) {
+                for input in inputs.iter().filter(|i| i.node == old_id) {
+                    let name = if input.slot == 0 {
+                        node.name.clone()
+                    } else {
+                        format!("{}#{}", node.name, input.slot)
+                    };
+                    let fact = self.fact(*input)?.clone();
+                    let id = model.add_node(name, Box::new(Source::new(fact.clone())))?;
+                    model.set_fact(OutletId::new(id, 0), fact)?;
+                    mapping.insert(*input, OutletId::new(id, 0));
+                }
+                continue;
+            }
+            let id = model.add_node(node.name.clone(), node.op.clone())?;
+            for (ix, input) in node.inputs.iter().enumerate() {
+                let outlet = mapping.get(input).ok_or_else(|| {
+                    format!("Subgraph of {:?} does not reach {:?}", outputs, input)
+                })?;
+                model.add_edge(*outlet, InletId::new(id, ix))?;
+            }
+            for (slot, outlet) in node.outputs.iter().enumerate() {
+                model.set_fact(OutletId::new(id, slot), outlet.fact.clone())?;
+                mapping.insert(OutletId::new(old_id, slot), OutletId::new(id, slot));
+            }
+        }
+        model.outputs = outputs
+            .iter()
+            .map(|&o| {
+                mapping
+                    .get(&OutletId::new(o, 0))
+                    .cloned()
+                    .ok_or_else(|| format!("Subgraph does not compute output node {}", o).into())
+            })
+            .collect::<TractResult<_>>()?;
+        Ok(model)
+    }
+
     pub fn node_by_name(&self, name: &str) -> TractResult<&Node> {
         let id: &usize = self
             .nodes_by_name
@@ -296,6 +370,54 @@ impl Model {
         Ok(&self.nodes[*id])
     }
 
+    pub fn node_by_name_mut(&mut self, name: &str) -> TractResult<&mut Node> {
+        let id: usize = *self
+            .nodes_by_name
+            .get(name)
+            .ok_or_else(|| format!("Node named {} not found", name))?;
+        Ok(self.node_mut(id))
+    }
+
+    /// Renames a node, keeping the lookup by name in sync.
+    pub fn rename_node(&mut self, id: usize, name: &str) -> TractResult<()> {
+        if id >= self.nodes.len() {
+            bail!("Node {} does not exist", id)
+        }
+        if let Some(&other) = self.nodes_by_name.get(name) {
+            if other == id {
+                return Ok(());
+            }
+            bail!("Can not rename node {}: {} is already taken", id, name)
+        }
+        let old = ::std::mem::replace(&mut self.nodes[id].name, name.to_string());
+        if self.nodes_by_name.get(&old) == Some(&id) {
+            self.nodes_by_name.remove(&old);
+        }
+        self.nodes_by_name.insert(name.to_string(), id);
+        Ok(())
+    }
+
+    /// Lists the nodes consuming the outputs of each node, by node id.
+    ///
+    /// The lists are computed on the first call, and kept until the graph
+    /// is changed.
+    pub fn successors(&self) -> &[Vec<usize>] {
+        self.successors.get_or_init(|| {
+            self.nodes
+                .iter()
+                .map(|node| {
+                    let mut succ: Vec<usize> = vec![];
+                    for inlet in node.outputs.iter().flat_map(|o| o.successors.iter()) {
+                        if !succ.contains(&inlet.node) {
+                            succ.push(inlet.node)
+                        }
+                    }
+                    succ
+                })
+                .collect()
+        })
+    }
+
     pub fn node_names(&self) -> Vec<&str> {
         self.nodes.iter().map(|s| &*s.name).collect()
     }
@@ -305,6 +427,7 @@ impl Model {
     }
 
     pub fn node_mut(&mut self, id: usize) -> &mut Node {
+        self.successors.take();
         &mut self.nodes[id]
     }
 
@@ -313,6 +436,7 @@ impl Model {
     }
 
     pub fn mut_nodes(&mut self) -> &mut [Node] {
+        self.successors.take();
         &mut *self.nodes
     }
 
@@ -394,4 +518,115 @@ mod test {
         fn is_sync<T: Sync>() {}
         is_sync::<Model>();
     }
+
+    #[test]
+    fn subgraph_of_chain() {
+        use crate::model::dsl::ModelDsl;
+        use crate::ops::math::Abs;
+        use crate::ops::prelude::*;
+        use ndarray::arr1;
+        let mut model = Model::default();
+        model
+            .add_source_fact("a", TensorFact::dt_shape(DatumType::F32, vec![2]))
+            .unwrap();
+        for name in &["b", "c", "d", "e"] {
+            model.chain(name, Box::new(Abs::default())).unwrap();
+        }
+        model.analyse().unwrap();
+
+        let sub = model.subgraph(&[2]).unwrap();
+        assert_eq!(sub.node_names(), vec!["a", "b", "c"]);
+
+        let b = model.node_by_name("b").unwrap().id;
+        let d = model.node_by_name("d").unwrap().id;
+        let sub = model.subgraph_from(&[OutletId::new(b, 0)], &[d]).unwrap();
+        assert_eq!(sub.node_names(), vec!["b", "c", "d"]);
+        assert_eq!(sub.node(0).op().name(), "Source");
+        assert_eq!(sub.inputs().unwrap(), &[OutletId::new(0, 0)]);
+        assert_eq!(sub.outputs().unwrap(), &[OutletId::new(2, 0)]);
+        assert_eq!(
+            sub.input_fact().unwrap(),
+            &TensorFact::dt_shape(DatumType::F32, vec![2])
+        );
+        assert_eq!(sub.node(1).inputs, vec![OutletId::new(0, 0)]);
+
+        let input = Tensor::from(arr1(&[-1.0f32, 2.0]));
+        let plan = crate::SimplePlan::new(&sub).unwrap();
+        let outputs = plan.run(tvec!(input)).unwrap();
+        assert_eq!(*outputs[0], Tensor::from(arr1(&[1.0f32, 2.0])));
+    }
+
+    #[test]
+    fn subgraph_from_rejects_unreached_output() {
+        use crate::model::dsl::ModelDsl;
+        use crate::ops::array::Split;
+        use crate::ops::prelude::*;
+        let mut model = Model::default();
+        model
+            .add_source_fact("a", TensorFact::dt_shape(DatumType::F32, vec![6]))
+            .unwrap();
+        let split = model
+            .chain("split", Box::new(Split::new(0, 3, None)))
+            .unwrap();
+        model.analyse().unwrap();
+        assert!(model
+            .subgraph_from(&[OutletId::new(split, 1)], &[split])
+            .is_err());
+    }
+
+    #[test]
+    fn rename_node_updates_lookup() {
+        let mut model = Model::default();
+        model.add_source("a").unwrap();
+        model
+            .chain("abs", Box::new(crate::ops::math::Abs::default()))
+            .unwrap();
+        model.rename_node(1, "magnitude").unwrap();
+        assert_eq!(model.node_by_name("magnitude").unwrap().id, 1);
+        assert_eq!(model.node(1).name, "magnitude");
+        assert!(model.node_by_name("abs").is_err());
+        assert!(model.rename_node(1, "a").is_err());
+        assert!(model.rename_node(2, "b").is_err());
+        model.node_by_name_mut("magnitude").unwrap().op =
+            Box::new(crate::ops::math::Neg::default());
+        assert_eq!(model.node(1).op().name(), "Neg");
+    }
+
+    #[test]
+    fn successors_follow_graph_changes() {
+        use crate::model::dsl::ModelDsl;
+        use crate::ops::math::{Abs, Add};
+        let mut model = Model::default();
+        let a = model.add_source("a").unwrap();
+        let abs = model.chain("abs", Box::new(Abs::default())).unwrap();
+        let neg = model
+            .tap_and_chain(
+                OutletId::new(a, 0),
+                "neg",
+                Box::new(crate::ops::math::Neg::default()),
+            )
+            .unwrap();
+        assert_eq!(model.successors(), &[vec![abs, neg], vec![], vec![]]);
+
+        let add = model
+            .add_node("add".to_string(), Box::new(Add::default()))
+            .unwrap();
+        model
+            .add_edge(OutletId::new(abs, 0), InletId::new(add, 0))
+            .unwrap();
+        model
+            .add_edge(OutletId::new(abs, 0), InletId::new(add, 1))
+            .unwrap();
+        assert_eq!(
+            model.successors(),
+            &[vec![abs, neg], vec![add], vec![], vec![]]
+        );
+
+        assert!(::std::ptr::eq(model.successors(), model.successors()));
+
+        model.clear_inputs(neg).unwrap();
+        assert!(model.successors.get().is_none());
+        assert_eq!(model.successors(), &[vec![abs], vec![add], vec![], vec![]]);
+        assert!(model.successors.get().is_some());
+    }
 }
@@ -20,8 +20,68 @@ pub fn eval_order_for_nodes(
     nodes: &[Node],
     inputs: &[usize],
     targets: &[usize],
+) -> TractResult<Vec<usize>> {
+    eval_order_for_nodes_with(nodes, inputs, targets, |_| 0)
+}
+
+/// Computes an evaluation order that tries to keep the peak memory low,
+/// using the operators `cost` estimates.
+///
+/// Among the inputs of a node, the branches that need the most memory
+/// compared to what they leave behind are evaluated first, so that their
+/// large intermediates are freed before the other branches start. Ops with
+/// no cost estimate leave the order of `eval_order` unchanged.
+pub fn memory_aware_eval_order(model: &super::Model) -> TractResult<Vec<usize>> {
+    let inputs = model
+        .inputs()?
+        .iter()
+        .map(|n| n.node)
+        .collect::<Vec<usize>>();
+    let targets = model
+        .outputs()?
+        .iter()
+        .map(|n| n.node)
+        .collect::<Vec<usize>>();
+    let mut out_bytes = vec![0u64; model.nodes().len()];
+    let mut peaks = vec![0u64; model.nodes().len()];
+    for node in eval_order_for_nodes(model.nodes(), &inputs, &targets)? {
+        let (input_facts, _) = model.facts(node)?;
+        out_bytes[node] = model.node(node).op().cost(input_facts).out_bytes;
+        let mut branches: Vec<(u64, u64)> = if inputs.contains(&node) {
+            vec![]
+        } else {
+            model
+                .node(node)
+                .inputs
+                .iter()
+                .map(|i| (peaks[i.node], out_bytes[i.node]))
+                .collect()
+        };
+        branches.sort_by_key(|&(peak, out)| ::std::cmp::Reverse(peak.saturating_sub(out)));
+        let mut alive = 0u64;
+        let mut peak = 0u64;
+        for (branch_peak, branch_out) in branches {
+            peak = peak.max(alive + branch_peak);
+            alive += branch_out;
+        }
+        peaks[node] = peak.max(alive + out_bytes[node]);
+    }
+    eval_order_for_nodes_with(model.nodes(), &inputs, &targets, |n| {
+        peaks[n].saturating_sub(out_bytes[n])
+    })
+}
+
+/// Depth-first evaluation order, evaluating the inputs of a node by
+/// decreasing `priority`, and in input order on ties.
+fn eval_order_for_nodes_with<F: Fn(usize) -> u64>(
+    nodes: &[Node],
+    inputs: &[usize],
+    targets: &[usize],
+    priority: F,
 ) -> TractResult<Vec<usize>> {
     let mut done = bit_set::BitSet::with_capacity(nodes.len());
+    // nodes whose inputs have been requested, but are not done yet
+    let mut pending = bit_set::BitSet::with_capacity(nodes.len());
     let mut needed: Vec<usize> = vec![];
     let mut order: Vec<usize> = vec![];
     for &t in targets {
@@ -36,10 +96,33 @@ pub fn eval_order_for_nodes(
             order.push(node);
             needed.pop();
             done.insert(node);
+            pending.remove(node);
         } else {
-            for input in nodes[node].inputs.iter().rev() {
-                if !done.contains(input.node) {
-                    needed.push(input.node);
+            if let Some(input) = nodes[node].inputs.iter().find(|i| pending.contains(i.node)) {
+                let from = match needed.iter().rposition(|&n| n == input.node) {
+                    Some(from) => from,
+                    None => bail!(
+                        "Inconsistent evaluation order: node {} is pending but not needed",
+                        input.node
+                    ),
+                };
+                let mut cycle: Vec<&str> = vec![];
+                for &n in &needed[from..] {
+                    if (pending.contains(n) || n == node) && !cycle.contains(&&*nodes[n].name) {
+                        cycle.push(&nodes[n].name);
+                    }
+                }
+                bail!(
+                    "Graph is not a DAG, found a cycle through: {}",
+                    cycle.join(", ")
+                )
+            }
+            pending.insert(node);
+            let mut node_inputs: Vec<usize> = nodes[node].inputs.iter().map(|i| i.node).collect();
+            node_inputs.sort_by_key(|&i| ::std::cmp::Reverse(priority(i)));
+            for &input in node_inputs.iter().rev() {
+                if !done.contains(input) {
+                    needed.push(input);
                 }
             }
         }
@@ -52,8 +135,61 @@ mod tests {
     use crate::model::dsl::ModelDsl;
     use crate::model::*;
     use crate::ops::math::Add;
+    use crate::ops::prelude::*;
     use crate::*;
 
+    #[derive(Debug, Clone, new)]
+    struct Sized(u64);
+
+    impl Op for Sized {
+        fn name(&self) -> Cow<str> {
+            "Sized".into()
+        }
+
+        fn cost(&self, _inputs: TVec<&TensorFact>) -> OpCost {
+            OpCost {
+                flops: 0,
+                out_bytes: self.0,
+            }
+        }
+    }
+
+    impl StatelessOp for Sized {
+        fn eval(&self, inputs: TVec<SharedTensor>) -> TractResult<TVec<SharedTensor>> {
+            Ok(inputs)
+        }
+    }
+
+    impl InferenceRulesOp for Sized {
+        fn rules<'r, 'p: 'r, 's: 'r>(
+            &'s self,
+            s: &mut Solver<'r>,
+            _inputs: &'p SharedTensorsProxy,
+            outputs: &'p SharedTensorsProxy,
+        ) -> InferenceResult {
+            s.equals(&outputs.len, 1)
+        }
+    }
+
+    fn peak_bytes(model: &Model, order: &[usize]) -> u64 {
+        let mut alive = vec![0u64; model.nodes().len()];
+        let mut peak = 0;
+        for (step, &node) in order.iter().enumerate() {
+            let (inputs, _) = model.facts(node).unwrap();
+            alive[node] = model.node(node).op().cost(inputs).out_bytes;
+            peak = peak.max(alive.iter().sum());
+            for &other in &order[..=step] {
+                let needed = order[step + 1..]
+                    .iter()
+                    .any(|&n| model.node(n).inputs.iter().any(|i| i.node == other));
+                if !needed {
+                    alive[other] = 0;
+                }
+            }
+        }
+        peak
+    }
+
     #[test]
     fn test_simple() {
         let mut model = Model::default();
@@ -76,4 +212,118 @@ mod tests {
             .unwrap();
         assert_eq!(model.eval_order().unwrap(), vec!(0, 1));
     }
+
+    /// x -> a, x -> b, (a, b) -> n, (a, n) -> t: `a` is a residual input of `t`.
+    fn residual_model() -> Model {
+        let mut model = Model::default();
+        let x = model.add_source("x").unwrap();
+        let a = model
+            .add_node("a".to_string(), Box::new(crate::ops::math::Neg::default()))
+            .unwrap();
+        let b = model
+            .add_node("b".to_string(), Box::new(crate::ops::math::Abs::default()))
+            .unwrap();
+        let n = model
+            .add_node("n".to_string(), Box::new(Add::default()))
+            .unwrap();
+        let t = model
+            .add_node("t".to_string(), Box::new(Add::default()))
+            .unwrap();
+        for &(from, to, slot) in &[
+            (x, a, 0),
+            (x, b, 0),
+            (a, n, 0),
+            (b, n, 1),
+            (a, t, 0),
+            (n, t, 1),
+        ] {
+            model
+                .add_edge(OutletId::new(from, 0), InletId::new(to, slot))
+                .unwrap();
+        }
+        model.set_outputs(&["t"]).unwrap();
+        model
+    }
+
+    #[test]
+    fn test_residual() {
+        let model = residual_model();
+        assert_eq!(model.eval_order().unwrap(), vec!(0, 1, 2, 3, 4));
+        let order = super::memory_aware_eval_order(&model).unwrap();
+        assert_eq!(order.len(), 5);
+        for (step, &node) in order.iter().enumerate() {
+            for input in &model.node(node).inputs {
+                assert!(order[..step].contains(&input.node));
+            }
+        }
+    }
+
+    #[test]
+    fn simple_plan_runs_fan_out() {
+        let model = residual_model();
+        let plan = crate::plan::SimplePlan::new(&model).unwrap();
+        let outputs = plan
+            .run(tvec!(Tensor::from(ndarray::arr1(&[1.0f32, -2.0]))))
+            .unwrap();
+        assert_eq!(*outputs[0], Tensor::from(ndarray::arr1(&[-1.0f32, 6.0])));
+    }
+
+    #[test]
+    fn memory_aware_order_frees_large_branch_first() {
+        let mut model = Model::default();
+        model.add_source("a").unwrap();
+        model.chain("q1", Box::new(Sized::new(50))).unwrap();
+        model.chain("q2", Box::new(Sized::new(40))).unwrap();
+        model
+            .add_node("p1".to_string(), Box::new(Sized::new(100)))
+            .unwrap();
+        model
+            .add_edge(OutletId::new(0, 0), InletId::new(3, 0))
+            .unwrap();
+        model.chain("p2", Box::new(Sized::new(1))).unwrap();
+        model
+            .add_node("add".to_string(), Box::new(Add::default()))
+            .unwrap();
+        model
+            .add_edge(OutletId::new(2, 0), InletId::new(5, 0))
+            .unwrap();
+        model
+            .add_edge(OutletId::new(4, 0), InletId::new(5, 1))
+            .unwrap();
+        model.set_outputs(&["add"]).unwrap();
+        let naive = model.eval_order().unwrap();
+        assert_eq!(naive, vec!(0, 1, 2, 3, 4, 5));
+        let order = crate::plan::SimplePlan::new(&model).unwrap().order;
+        assert_eq!(order, vec!(0, 3, 4, 1, 2, 5));
+        assert_eq!(peak_bytes(&model, &naive), 141);
+        assert_eq!(peak_bytes(&model, &order), 101);
+    }
+
+    #[test]
+    fn test_cycle() {
+        let mut model = Model::default();
+        model.add_source("a").unwrap();
+        model.chain("add", Box::new(Add::default())).unwrap();
+        model
+            .chain("neg", Box::new(crate::ops::math::Neg::default()))
+            .unwrap();
+        model
+            .add_edge(OutletId::new(2, 0), InletId::new(1, 1))
+            .unwrap();
+        model
+            .add_node(
+                "sink".to_string(),
+                Box::new(crate::ops::math::Abs::default()),
+            )
+            .unwrap();
+        model
+            .add_edge(OutletId::new(2, 0), InletId::new(3, 0))
+            .unwrap();
+        let err = model.eval_order().unwrap_err().to_string();
+        assert!(
+            err.contains("add, neg") || err.contains("neg, add"),
+            "{}",
+            err
+        );
+    }
 }
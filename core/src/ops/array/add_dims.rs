@@ -2,21 +2,34 @@ use crate::ops::prelude::*;
 
 #[derive(Debug, Clone, new)]
 pub struct AddDims {
-    pub axes: Vec<usize>,
+    pub axes: Vec<i64>,
 }
 
 impl AddDims {
-    fn compute_shape<D: DimLike>(&self, input: &[D]) -> TVec<D> {
+    /// Axes refer to the output, so negative ones are resolved against the
+    /// output rank.
+    fn resolved_axes(&self, input_rank: usize) -> TractResult<Vec<usize>> {
+        let rank = input_rank + self.axes.len();
+        let mut axes = self
+            .axes
+            .iter()
+            .map(|&axis| normalize_axis(axis, rank))
+            .collect::<TractResult<Vec<_>>>()?;
+        axes.sort();
+        Ok(axes)
+    }
+
+    fn compute_shape<D: DimLike>(&self, input: &[D]) -> TractResult<TVec<D>> {
         let mut shape: TVec<D> = input.iter().cloned().collect();
-        for &axis in &self.axes {
+        for axis in self.resolved_axes(input.len())? {
             shape.insert(axis, D::one())
         }
-        shape
+        Ok(shape)
     }
 
     /// Evaluates the operation given the input tensors.
     fn eval_t<T: Datum>(&self, input: SharedTensor) -> TractResult<TVec<SharedTensor>> {
-        let shape = self.compute_shape(input.shape());
+        let shape = self.compute_shape(input.shape())?;
         Ok(tvec![input.to_array::<T>()?.into_shape(&*shape)?.into()])
     }
 }
@@ -26,11 +39,19 @@ impl Op for AddDims {
         "AddDims".into()
     }
 
+    fn is_foldable(&self) -> bool {
+        true
+    }
+
     fn pulsify(&self, mut inputs: TVec<&PulsedTensorFact>) -> TractResult<Vec<PulsifiedOp>> {
         let input = args_1!(inputs);
         let mut fact = input.clone();
-        fact.shape = self.compute_shape(&input.shape);
-        fact.axis += self.axes.iter().filter(|&ax| *ax <= input.axis).count();
+        fact.shape = self.compute_shape(&input.shape)?;
+        for axis in self.resolved_axes(input.shape.len())? {
+            if axis <= fact.axis {
+                fact.axis += 1;
+            }
+        }
         Ok(vec![PulsifiedOp::new(Box::new(self.clone()), tvec!(fact))])
     }
 }
@@ -56,8 +77,21 @@ impl InferenceRulesOp for AddDims {
             (&inputs[0].rank).bex() + self.axes.len() as i32,
         )?;
         s.given(&inputs[0].shape, move |s, shape| {
-            let output_shape = self.compute_shape(&shape);
+            let output_shape = self.compute_shape(&shape)?;
             s.equals(&outputs[0].shape, output_shape)
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::Array3;
+
+    #[test]
+    fn add_dims_negative_axis() {
+        let input = Tensor::from(Array3::<f32>::zeros((2, 3, 4)));
+        let outputs = AddDims::new(vec![-1]).eval(tvec!(input.into())).unwrap();
+        assert_eq!(outputs[0].shape(), &[2, 3, 4, 1]);
+    }
+}
@@ -3,14 +3,6 @@ use crate::ops::prelude::*;
 #[derive(Debug, Clone, new, Default)]
 pub struct MultiBroadcastTo;
 
-impl MultiBroadcastTo {
-    fn eval_t<T: Datum>(input: &Tensor, shape: &[usize]) -> TractResult<TVec<SharedTensor>> {
-        let input = input.to_array_view::<T>()?;
-        let output = input.broadcast(&*shape).ok_or("incompatible shapes")?;
-        Ok(tvec![output.to_owned().into()])
-    }
-}
-
 impl Op for MultiBroadcastTo {
     fn name(&self) -> Cow<str> {
         "MultiBroadcastTo".into()
@@ -28,7 +20,7 @@ impl StatelessOp for MultiBroadcastTo {
             .collect();
         let dims = crate::broadcast::multi_broadcast(&[&*dims, &*input.shape()])
             .ok_or("incompatible shapes")?;
-        dispatch_datum!(Self::eval_t(input.datum_type())(input.as_tensor(), &*dims))
+        Ok(tvec![input.broadcast_to(&*dims)?.into()])
     }
 }
 
@@ -4,7 +4,7 @@ use crate::ops::prelude::*;
 
 #[derive(Debug, Clone, new)]
 pub struct Concat {
-    axis: usize,
+    axis: i64,
 }
 
 impl Concat {
@@ -12,7 +12,8 @@ impl Concat {
     fn eval_t<T: Datum>(&self, inputs: TVec<SharedTensor>) -> TractResult<TVec<SharedTensor>> {
         let mats: TractResult<Vec<ArrayViewD<T>>> =
             inputs.iter().map(|mat| mat.to_array_view()).collect();
-        let result = ::ndarray::stack(Axis(self.axis as usize), &*mats?)?;
+        let axis = normalize_axis(self.axis, inputs[0].shape().len())?;
+        let result = ::ndarray::stack(Axis(axis), &*mats?)?;
         Ok(tvec![result.into()])
     }
 }
@@ -21,6 +22,29 @@ impl Op for Concat {
     fn name(&self) -> Cow<str> {
         "Concat".into()
     }
+
+    fn pulsify(&self, inputs: TVec<&PulsedTensorFact>) -> TractResult<Vec<PulsifiedOp>> {
+        let first = inputs[0];
+        let axis = normalize_axis(self.axis, first.shape.len())?;
+        if axis == first.axis {
+            bail!("Can not pulsify Concat along the streaming axis");
+        }
+        for input in &inputs[1..] {
+            if input.axis != first.axis
+                || input.delay != first.delay
+                || input.pulse() != first.pulse()
+            {
+                bail!(
+                    "Can not pulsify Concat of inputs with different stream axis, delay or pulse ({:?} and {:?})",
+                    first,
+                    input
+                );
+            }
+        }
+        let mut fact = first.clone();
+        fact.shape[axis] = inputs.iter().map(|i| i.shape[axis]).sum();
+        Ok(vec![PulsifiedOp::new(Box::new(self.clone()), tvec!(fact))])
+    }
 }
 
 impl StatelessOp for Concat {
@@ -40,30 +64,140 @@ impl InferenceRulesOp for Concat {
         s.equals(&outputs.len, 1)?;
         s.equals(&outputs[0].datum_type, &inputs[0].datum_type)?;
         s.equals(&outputs[0].rank, &inputs[0].rank)?;
-        s.given(&inputs.len, move |s, n| {
+        s.given_2(&inputs.len, &inputs[0].rank, move |s, n, rank| {
             let n = n as usize;
+            let rank = rank as usize;
+            let axis = normalize_axis(self.axis, rank)?;
             s.equals_all((0..n).map(|i| (&inputs[i].datum_type).bex()).collect())?;
             s.equals_all((0..n).map(|i| (&inputs[i].rank).bex()).collect())?;
+            // works backward too: with the output and all inputs but one
+            // known, the missing dimension is solved by subtraction
             s.equals(
                 crate::analyser::rules::expr::SumExp::new(
-                    (0..n)
-                        .map(|i| (&inputs[i].shape[self.axis]).bex())
-                        .collect(),
+                    (0..n).map(|i| (&inputs[i].shape[axis]).bex()).collect(),
                 ),
-                &outputs[0].shape[self.axis],
+                &outputs[0].shape[axis],
             )?;
-            for axis in 0..self.axis {
-                s.equals(&outputs[0].shape[axis], &inputs[0].shape[axis])?;
-                s.equals_all((0..n).map(|i| inputs[i].shape[axis].bex()).collect())?;
+            for other in (0..rank).filter(|&ax| ax != axis) {
+                s.equals(&outputs[0].shape[other], &inputs[0].shape[other])?;
+                s.equals_all((0..n).map(|i| inputs[i].shape[other].bex()).collect())?;
             }
-            s.given(&inputs[0].rank, move |s, axes| {
-                let axes = axes as usize;
-                for axis in (self.axis + 1)..axes {
-                    s.equals(&outputs[0].shape[axis], &inputs[0].shape[axis])?;
-                    s.equals_all((0..n).map(|i| inputs[i].shape[axis].bex()).collect())?;
-                }
-                Ok(())
-            })
+            Ok(())
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::dsl::*;
+
+    fn two_streams(axis: i64) -> Model {
+        let mut model = Model::default();
+        let a = model
+            .add_source_fact(
+                "a",
+                TensorFact::dt_shape(DatumType::F32, vec![1.to_dim(), 2.to_dim(), TDim::s()]),
+            )
+            .unwrap();
+        let b = model
+            .add_source_fact(
+                "b",
+                TensorFact::dt_shape(DatumType::F32, vec![1.to_dim(), 3.to_dim(), TDim::s()]),
+            )
+            .unwrap();
+        let concat = model
+            .add_node("concat".to_string(), Box::new(Concat::new(axis)))
+            .unwrap();
+        model
+            .add_edge(OutletId::new(a, 0), InletId::new(concat, 0))
+            .unwrap();
+        model
+            .add_edge(OutletId::new(b, 0), InletId::new(concat, 1))
+            .unwrap();
+        model
+    }
+
+    #[test]
+    fn concat_infers_missing_input_backward() {
+        let op = Concat::new(1);
+        let known = TensorFact::dt_shape(DatumType::F32, shapefact!(1, 2, S));
+        let other = TensorFact::dt_shape(DatumType::F32, shapefact!(1, 3, S));
+        let any = TensorFact::default();
+        let output = TensorFact::dt_shape(DatumType::F32, shapefact!(1, 10, S));
+        let (inputs, _) = op
+            .infer_facts(tvec!(&known, &any, &other), tvec!(&output))
+            .unwrap();
+        assert_eq!(
+            inputs[1],
+            TensorFact::dt_shape(DatumType::F32, shapefact!(1, 5, S))
+        );
+        let (inputs, _) = op
+            .infer_facts(tvec!(&any, &known, &other), tvec!(&output))
+            .unwrap();
+        assert_eq!(
+            inputs[0],
+            TensorFact::dt_shape(DatumType::F32, shapefact!(1, 5, S))
+        );
+        let output = TensorFact::dt_shape(
+            DatumType::F32,
+            ShapeFact::from(tvec!(1.to_dim(), TDim::s() + 5, TDim::s())),
+        );
+        let (inputs, _) = op
+            .infer_facts(tvec!(&known, &any, &other), tvec!(&output))
+            .unwrap();
+        assert_eq!(
+            inputs[1],
+            TensorFact::dt_shape(DatumType::F32, shapefact!(1, S, S))
+        );
+    }
+
+    #[test]
+    fn concat_pulsify_on_channel_axis() {
+        let mut model = two_streams(1);
+        model.analyse().unwrap();
+        let (pulsed, _, output) = crate::pulse::pulsify(&model, 4).unwrap();
+        assert_eq!(output.shape, tvec!(1, 5, 4));
+        assert_eq!(output.axis, 2);
+        assert_eq!(output.delay, 0);
+
+        let plan = crate::plan::SimplePlan::new(&pulsed).unwrap();
+        let a = Array3::<f32>::zeros((1, 2, 4));
+        let b = Array3::<f32>::ones((1, 3, 4));
+        let outputs = plan.run(tvec!(a.into(), b.into())).unwrap();
+        let result = outputs[0].to_array_view::<f32>().unwrap();
+        assert_eq!(result.shape(), &[1, 5, 4]);
+        assert_eq!(result.sum(), 12.0);
+    }
+
+    #[test]
+    fn concat_pulsify_on_stream_axis_fails() {
+        let mut model = Model::default();
+        model
+            .add_source_fact(
+                "a",
+                TensorFact::dt_shape(DatumType::F32, vec![1.to_dim(), TDim::s()]),
+            )
+            .unwrap();
+        model.chain("concat", Box::new(Concat::new(1))).unwrap();
+        model.analyse().unwrap();
+        assert!(crate::pulse::pulsify(&model, 4).is_err());
+    }
+
+    #[test]
+    fn concat_negative_axis() {
+        let a = Tensor::from(Array3::<f32>::zeros((1, 2, 2)));
+        let b = Tensor::from(Array3::<f32>::ones((1, 2, 3)));
+        let op = Concat::new(-1);
+        let outputs = op.eval(tvec!(a.into(), b.into())).unwrap();
+        assert_eq!(outputs[0].shape(), &[1, 2, 5]);
+
+        let mut model = two_streams(-2);
+        model.analyse().unwrap();
+        let fact = model.fact(OutletId::new(2, 0)).unwrap();
+        assert_eq!(
+            fact.shape,
+            ShapeFact::from(vec![1.to_dim(), 5.to_dim(), TDim::s()])
+        );
+    }
+}
@@ -13,7 +13,8 @@ impl ConstantLike {
         T: Datum,
         f32: AsPrimitive<T>,
     {
-        Ok(Array::<T, _>::from_elem(shape, self.value.as_()).into())
+        let value: Tensor = arr0::<T>(self.value.as_()).into();
+        Ok(value.broadcast_to(shape)?.into())
     }
 }
 
@@ -64,6 +65,83 @@ impl InferenceRulesOp for ConstantLike {
     }
 }
 
+/// Fills a tensor, whose shape is given by the input, with the single
+/// element of `value`.
+#[derive(Debug, Clone, new)]
+pub struct ConstantOfShape {
+    value: Tensor,
+}
+
+impl ConstantOfShape {
+    pub fn make<T: Datum>(&self, shape: &[usize]) -> TractResult<SharedTensor> {
+        let value = self.value.to_array_view::<T>()?;
+        if value.len() != 1 {
+            bail!(
+                "ConstantOfShape expects a single element value, got {:?}",
+                value.shape()
+            )
+        }
+        let value = value.iter().next().unwrap().clone();
+        Ok(ArrayD::from_elem(shape, value).into())
+    }
+
+    /// Reads the requested output shape, rejecting negative dimensions.
+    fn shape(shape: &Tensor) -> TractResult<TVec<usize>> {
+        shape
+            .cast_to::<i64>()?
+            .to_array_view::<i64>()?
+            .iter()
+            .map(|&d| {
+                if d < 0 {
+                    bail!("ConstantOfShape got a negative dimension: {}", d)
+                }
+                Ok(d as usize)
+            })
+            .collect()
+    }
+}
+
+impl Op for ConstantOfShape {
+    fn name(&self) -> Cow<str> {
+        "ConstantOfShape".into()
+    }
+}
+
+impl StatelessOp for ConstantOfShape {
+    /// Evaluates the operation given the input tensors.
+    fn eval(&self, mut inputs: TVec<SharedTensor>) -> TractResult<TVec<SharedTensor>> {
+        let shape = args_1!(inputs);
+        let shape = Self::shape(&shape)?;
+        Ok(tvec!(dispatch_datum!(
+            Self::make(self.value.datum_type())(self, &shape)
+        )?))
+    }
+}
+
+impl InferenceRulesOp for ConstantOfShape {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p SharedTensorsProxy,
+        outputs: &'p SharedTensorsProxy,
+    ) -> InferenceResult {
+        s.equals(&inputs.len, 1)?;
+        s.equals(&outputs.len, 1)?;
+        s.equals(&outputs[0].datum_type, self.value.datum_type())?;
+        s.equals(&inputs[0].rank, 1)?;
+        s.given(&inputs[0].shape[0], move |s, rank| {
+            if let Ok(rank) = rank.to_integer() {
+                s.equals(&outputs[0].rank, rank)?;
+            }
+            Ok(())
+        })?;
+        s.given(&inputs[0].value, move |s, shape| {
+            let shape = Self::shape(&shape)?;
+            s.equals(&outputs[0].shape, ShapeFact::from(&*shape))
+        })
+    }
+}
+
 #[derive(Debug, Clone, new, Default)]
 pub struct EyeLike {
     dt: Option<DatumType>,
@@ -137,3 +215,33 @@ impl InferenceRulesOp for EyeLike {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn constant_of_shape_rules() {
+        let op = ConstantOfShape::new(Tensor::from(arr1(&[1i32])));
+        let shape = TensorFact::dt_shape(DatumType::I64, vec![3]);
+        let any = TensorFact::default();
+        let (_, outputs) = op.infer_facts(tvec!(&shape), tvec!(&any)).unwrap();
+        assert_eq!(
+            outputs[0],
+            TensorFact::dt(DatumType::I32).with_shape(shapefact!(_, _, _))
+        );
+        let shape = TensorFact::from(arr1(&[2i64, 3]));
+        let (_, outputs) = op.infer(tvec!(&shape), tvec!(&any)).unwrap();
+        assert_eq!(outputs[0], TensorFact::from(Array2::<i32>::ones((2, 3))));
+    }
+
+    #[test]
+    fn constant_of_shape_rejects_negative_dims() {
+        let op = ConstantOfShape::new(Tensor::from(arr1(&[1i32])));
+        let shape: SharedTensor = arr1(&[2i64, -1]).into();
+        assert!(op.eval(tvec!(shape)).is_err());
+        let shape = TensorFact::from(arr1(&[2i64, -1]));
+        let any = TensorFact::default();
+        assert!(op.infer_facts(tvec!(&shape), tvec!(&any)).is_err());
+    }
+}
@@ -0,0 +1,285 @@
+use ndarray::*;
+
+use crate::ops::prelude::*;
+
+/// Order of the depth elements moved to a block, as in ONNX DepthToSpace.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DepthToSpaceMode {
+    /// Depth, then column, then row: the block index is the outermost part
+    /// of the channel.
+    DCR,
+    /// Column, then row, then depth: the block index is the innermost part
+    /// of the channel.
+    CRD,
+}
+
+impl Default for DepthToSpaceMode {
+    fn default() -> DepthToSpaceMode {
+        DepthToSpaceMode::DCR
+    }
+}
+
+/// Reshapes the input to `split`, permutes its axes, and reshapes the result
+/// to `shape`.
+fn rearrange<T: Datum>(
+    input: SharedTensor,
+    split: &[usize],
+    axes: &[usize],
+    shape: &[usize],
+) -> TractResult<SharedTensor> {
+    let permuted = input
+        .to_array::<T>()?
+        .into_shape(split)?
+        .permuted_axes(axes);
+    let data: Vec<T> = permuted.iter().cloned().collect();
+    Ok(ArrayD::from_shape_vec(shape, data)?.into())
+}
+
+fn nchw_shape(input: &SharedTensor) -> TractResult<(usize, usize, usize, usize)> {
+    match input.shape() {
+        &[n, c, h, w] => Ok((n, c, h, w)),
+        other => bail!("Expected a NCHW input, got shape {:?}", other),
+    }
+}
+
+/// Moves blocks of `blocksize²` channels into `blocksize x blocksize`
+/// spatial blocks: `[N,C,H,W]` becomes `[N,C/b²,H*b,W*b]`.
+#[derive(Debug, Clone, new, Default)]
+pub struct DepthToSpace {
+    blocksize: usize,
+    mode: DepthToSpaceMode,
+}
+
+impl DepthToSpace {
+    fn eval_t<T: Datum>(&self, input: SharedTensor) -> TractResult<TVec<SharedTensor>> {
+        let b = self.blocksize;
+        let (n, c, h, w) = nchw_shape(&input)?;
+        if c % (b * b) != 0 {
+            bail!(
+                "DepthToSpace needs a channel count divisible by {}, got {}",
+                b * b,
+                c
+            )
+        }
+        let (split, axes): (&[usize], &[usize]) = match self.mode {
+            DepthToSpaceMode::DCR => (&[n, b, b, c / (b * b), h, w], &[0, 3, 4, 1, 5, 2]),
+            DepthToSpaceMode::CRD => (&[n, c / (b * b), b, b, h, w], &[0, 1, 4, 2, 5, 3]),
+        };
+        let output = rearrange::<T>(input, split, axes, &[n, c / (b * b), h * b, w * b])?;
+        Ok(tvec!(output))
+    }
+}
+
+impl Op for DepthToSpace {
+    fn name(&self) -> Cow<str> {
+        "DepthToSpace".into()
+    }
+}
+
+impl StatelessOp for DepthToSpace {
+    fn eval(&self, mut inputs: TVec<SharedTensor>) -> TractResult<TVec<SharedTensor>> {
+        let input = args_1!(inputs);
+        dispatch_datum!(Self::eval_t(input.datum_type())(self, input))
+    }
+}
+
+impl InferenceRulesOp for DepthToSpace {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p SharedTensorsProxy,
+        outputs: &'p SharedTensorsProxy,
+    ) -> InferenceResult {
+        let b = self.blocksize as i32;
+        s.equals(&inputs.len, 1)?;
+        s.equals(&outputs.len, 1)?;
+        s.equals(&outputs[0].datum_type, &inputs[0].datum_type)?;
+        s.equals(&inputs[0].rank, 4)?;
+        s.equals(&outputs[0].rank, 4)?;
+        s.equals(&outputs[0].shape[0], &inputs[0].shape[0])?;
+        s.given(&inputs[0].shape[1], move |s, c| {
+            if let Ok(c) = c.to_integer() {
+                if c % (b * b) != 0 {
+                    bail!(
+                        "DepthToSpace needs a channel count divisible by {}, got {}",
+                        b * b,
+                        c
+                    )
+                }
+            }
+            s.equals(&outputs[0].shape[1], c / (b * b))
+        })?;
+        s.equals(&outputs[0].shape[2], b * inputs[0].shape[2].bex())?;
+        s.equals(&outputs[0].shape[3], b * inputs[0].shape[3].bex())
+    }
+}
+
+/// Moves `blocksize x blocksize` spatial blocks into channels: `[N,C,H,W]`
+/// becomes `[N,C*b²,H/b,W/b]`. This is the inverse of DepthToSpace in DCR
+/// mode.
+#[derive(Debug, Clone, new, Default)]
+pub struct SpaceToDepth {
+    blocksize: usize,
+}
+
+impl SpaceToDepth {
+    fn eval_t<T: Datum>(&self, input: SharedTensor) -> TractResult<TVec<SharedTensor>> {
+        let b = self.blocksize;
+        let (n, c, h, w) = nchw_shape(&input)?;
+        if h % b != 0 || w % b != 0 {
+            bail!(
+                "SpaceToDepth needs spatial dims divisible by {}, got {}x{}",
+                b,
+                h,
+                w
+            )
+        }
+        let output = rearrange::<T>(
+            input,
+            &[n, c, h / b, b, w / b, b],
+            &[0, 3, 5, 1, 2, 4],
+            &[n, c * b * b, h / b, w / b],
+        )?;
+        Ok(tvec!(output))
+    }
+}
+
+impl Op for SpaceToDepth {
+    fn name(&self) -> Cow<str> {
+        "SpaceToDepth".into()
+    }
+}
+
+impl StatelessOp for SpaceToDepth {
+    fn eval(&self, mut inputs: TVec<SharedTensor>) -> TractResult<TVec<SharedTensor>> {
+        let input = args_1!(inputs);
+        dispatch_datum!(Self::eval_t(input.datum_type())(self, input))
+    }
+}
+
+impl InferenceRulesOp for SpaceToDepth {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p SharedTensorsProxy,
+        outputs: &'p SharedTensorsProxy,
+    ) -> InferenceResult {
+        let b = self.blocksize as i32;
+        s.equals(&inputs.len, 1)?;
+        s.equals(&outputs.len, 1)?;
+        s.equals(&outputs[0].datum_type, &inputs[0].datum_type)?;
+        s.equals(&inputs[0].rank, 4)?;
+        s.equals(&outputs[0].rank, 4)?;
+        s.equals(&outputs[0].shape[0], &inputs[0].shape[0])?;
+        s.equals(&outputs[0].shape[1], b * b * inputs[0].shape[1].bex())?;
+        for axis in 2..4 {
+            s.given(&inputs[0].shape[axis], move |s, d| {
+                if let Ok(d) = d.to_integer() {
+                    if d % b != 0 {
+                        bail!(
+                            "SpaceToDepth needs spatial dims divisible by {}, got {}",
+                            b,
+                            d
+                        )
+                    }
+                }
+                s.equals(&outputs[0].shape[axis], d / b)
+            })?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input() -> SharedTensor {
+        let data: Vec<f32> = (0..16).map(|i| i as f32).collect();
+        ArrayD::from_shape_vec(vec![1, 4, 2, 2], data)
+            .unwrap()
+            .into()
+    }
+
+    fn channels() -> SharedTensor {
+        let data: Vec<f32> = (0..8).map(|i| i as f32).collect();
+        ArrayD::from_shape_vec(vec![1, 8, 1, 1], data)
+            .unwrap()
+            .into()
+    }
+
+    #[test]
+    fn depth_to_space_dcr() {
+        let op = DepthToSpace::new(2, DepthToSpaceMode::DCR);
+        let output = op.eval(tvec!(channels())).unwrap();
+        let expected = arr3(&[[[0.0f32, 2.0], [4.0, 6.0]], [[1.0, 3.0], [5.0, 7.0]]])
+            .into_shape(vec![1, 2, 2, 2])
+            .unwrap();
+        assert_eq!(output[0], SharedTensor::from(expected));
+    }
+
+    #[test]
+    fn depth_to_space_crd() {
+        let op = DepthToSpace::new(2, DepthToSpaceMode::CRD);
+        let output = op.eval(tvec!(channels())).unwrap();
+        let expected = arr3(&[[[0.0f32, 1.0], [2.0, 3.0]], [[4.0, 5.0], [6.0, 7.0]]])
+            .into_shape(vec![1, 2, 2, 2])
+            .unwrap();
+        assert_eq!(output[0], SharedTensor::from(expected));
+    }
+
+    #[test]
+    fn depth_to_space_interleaves_blocks() {
+        let op = DepthToSpace::new(2, DepthToSpaceMode::DCR);
+        let output = op.eval(tvec!(input())).unwrap();
+        let expected = arr2(&[
+            [0.0f32, 4.0, 1.0, 5.0],
+            [8.0, 12.0, 9.0, 13.0],
+            [2.0, 6.0, 3.0, 7.0],
+            [10.0, 14.0, 11.0, 15.0],
+        ])
+        .into_shape(vec![1, 1, 4, 4])
+        .unwrap();
+        assert_eq!(output[0], SharedTensor::from(expected));
+    }
+
+    #[test]
+    fn space_to_depth_inverts_dcr() {
+        let op = DepthToSpace::new(2, DepthToSpaceMode::DCR);
+        let spread = op.eval(tvec!(input())).unwrap();
+        let output = SpaceToDepth::new(2).eval(spread).unwrap();
+        assert_eq!(output[0], input());
+        let spread = op.eval(tvec!(channels())).unwrap();
+        let output = SpaceToDepth::new(2).eval(spread).unwrap();
+        assert_eq!(output[0], channels());
+    }
+
+    #[test]
+    fn depth_to_space_rules() {
+        let op = DepthToSpace::new(2, DepthToSpaceMode::CRD);
+        let ifact = TensorFact::dt_shape(DatumType::F32, shapefact!(1, 8, S, 3));
+        let ofact = TensorFact::default();
+        let (_, outputs) = op.infer_facts(tvec!(&ifact), tvec!(&ofact)).unwrap();
+        assert_eq!(
+            outputs[0],
+            TensorFact::dt_shape(
+                DatumType::F32,
+                ShapeFact::from(tvec!(1.to_dim(), 2.to_dim(), TDim::s() * 2, 6.to_dim()))
+            )
+        );
+        let ifact = TensorFact::dt_shape(DatumType::F32, shapefact!(1, 6, 2, 2));
+        assert!(op.infer_facts(tvec!(&ifact), tvec!(&ofact)).is_err());
+    }
+
+    #[test]
+    fn space_to_depth_rules() {
+        let op = SpaceToDepth::new(2);
+        let ifact = TensorFact::dt_shape(DatumType::F32, shapefact!(1, 3, 4, 6));
+        let ofact = TensorFact::default();
+        let (_, outputs) = op.infer_facts(tvec!(&ifact), tvec!(&ofact)).unwrap();
+        assert_eq!(
+            outputs[0],
+            TensorFact::dt_shape(DatumType::F32, shapefact!(1, 12, 2, 3))
+        );
+    }
+}
@@ -2,10 +2,19 @@ use crate::ops::prelude::*;
 
 #[derive(Debug, Clone, new, Default)]
 pub struct Flatten {
-    axis: usize,
+    axis: i64,
 }
 
 impl Flatten {
+    /// Unlike most ops, Flatten accepts `rank` itself as an axis.
+    fn resolved_axis(&self, rank: usize) -> TractResult<usize> {
+        if self.axis == rank as i64 {
+            Ok(rank)
+        } else {
+            normalize_axis(self.axis, rank)
+        }
+    }
+
     /// Evaluates the operation given the input tensors.
     fn eval_t<T: Datum>(
         &self,
@@ -25,8 +34,9 @@ impl Op for Flatten {
 impl StatelessOp for Flatten {
     fn eval(&self, mut inputs: TVec<SharedTensor>) -> TractResult<TVec<SharedTensor>> {
         let input = args_1!(inputs);
-        let shape_0 = input.shape()[..self.axis].iter().product::<usize>();
-        let shape_1 = input.shape()[self.axis..].iter().product::<usize>();
+        let axis = self.resolved_axis(input.shape().len())?;
+        let shape_0 = input.shape()[..axis].iter().product::<usize>();
+        let shape_1 = input.shape()[axis..].iter().product::<usize>();
         dispatch_datum!(Self::eval_t(input.datum_type())(
             self,
             input,
@@ -42,15 +52,62 @@ impl InferenceRulesOp for Flatten {
         inputs: &'p SharedTensorsProxy,
         outputs: &'p SharedTensorsProxy,
     ) -> InferenceResult {
+        s.equals(&inputs.len, 1)?;
+        s.equals(&outputs.len, 1)?;
         s.equals(&outputs[0].datum_type, &inputs[0].datum_type)?;
+        s.equals(&outputs[0].rank, 2)?;
         s.given(&inputs[0].shape, move |s, shape| {
-            let shape_0 = shape[..self.axis]
-                .iter()
-                .fold(TDim::from(1), |acc, &v| acc * v);
-            let shape_1 = shape[self.axis..]
-                .iter()
-                .fold(TDim::from(1), |acc, &v| acc * v);
+            let axis = self.resolved_axis(shape.len())?;
+            let shape_0 = shape[..axis].iter().fold(TDim::from(1), |acc, &v| acc * v);
+            let shape_1 = shape[axis..].iter().fold(TDim::from(1), |acc, &v| acc * v);
             s.equals(&outputs[0].shape, ShapeFact::from(vec![shape_0, shape_1]))
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::Array3;
+
+    #[test]
+    fn flatten_negative_axis() {
+        let input = Tensor::from(Array3::<f32>::zeros((2, 3, 4)));
+        let outputs = Flatten::new(-1).eval(tvec!(input.into())).unwrap();
+        assert_eq!(outputs[0].shape(), &[6, 4]);
+    }
+
+    #[test]
+    fn flatten_negative_axis_rules() {
+        let input = TensorFact::dt_shape(DatumType::F32, vec![2, 3, 4]);
+        let any = TensorFact::default();
+        let (_, outputs) = Flatten::new(-1)
+            .infer_facts(tvec!(&input), tvec!(&any))
+            .unwrap();
+        assert_eq!(outputs[0].shape, ShapeFact::from(vec![6, 4]));
+    }
+
+    #[test]
+    fn flatten_rules() {
+        let input = TensorFact::dt_shape(DatumType::F32, vec![2, 3, 4]);
+        let any = TensorFact::default();
+        let (_, outputs) = Flatten::new(1)
+            .infer_facts(tvec!(&input), tvec!(&any))
+            .unwrap();
+        assert_eq!(outputs[0].shape, ShapeFact::from(vec![2, 12]));
+        let (_, outputs) = Flatten::new(0)
+            .infer_facts(tvec!(&input), tvec!(&any))
+            .unwrap();
+        assert_eq!(outputs[0].shape, ShapeFact::from(vec![1, 24]));
+    }
+
+    #[test]
+    fn flatten_rules_unknown_shape() {
+        let input = TensorFact::dt(DatumType::F32);
+        let any = TensorFact::default();
+        let (_, outputs) = Flatten::new(1)
+            .infer_facts(tvec!(&input), tvec!(&any))
+            .unwrap();
+        assert_eq!(outputs[0].shape, shapefact!(_, _));
+    }
+}
@@ -0,0 +1,115 @@
+use crate::ops::prelude::*;
+use ndarray::*;
+
+#[derive(Debug, Clone, new, Default)]
+pub struct Gather {
+    axis: i64,
+}
+
+impl Gather {
+    fn resolved_axis(&self, rank: usize) -> TractResult<usize> {
+        normalize_axis(self.axis, rank)
+    }
+
+    fn eval_t<T: Datum>(
+        &self,
+        data: SharedTensor,
+        indices: &SharedTensor,
+    ) -> TractResult<SharedTensor> {
+        let data = data.to_array_view::<T>()?;
+        let axis = self.resolved_axis(data.ndim())?;
+        let indices = indices.cast_to::<i64>()?;
+        let indices = indices.to_array_view::<i64>()?;
+        let dim = data.shape()[axis] as i64;
+        let flat = indices
+            .iter()
+            .map(|&i| {
+                let resolved = if i < 0 { i + dim } else { i };
+                if resolved < 0 || resolved >= dim {
+                    bail!("Gather index {} out of bounds for axis of size {}", i, dim)
+                }
+                Ok(resolved as usize)
+            })
+            .collect::<TractResult<Vec<usize>>>()?;
+        let mut shape: Vec<usize> = data.shape()[..axis].to_vec();
+        shape.extend(indices.shape());
+        shape.extend(&data.shape()[axis + 1..]);
+        let gathered = data.select(Axis(axis), &flat);
+        Ok(gathered.into_shape(shape)?.into())
+    }
+}
+
+impl Op for Gather {
+    fn name(&self) -> Cow<str> {
+        "Gather".into()
+    }
+
+    fn is_foldable(&self) -> bool {
+        true
+    }
+}
+
+impl StatelessOp for Gather {
+    /// Evaluates the operation given the input tensors.
+    fn eval(&self, mut inputs: TVec<SharedTensor>) -> TractResult<TVec<SharedTensor>> {
+        let (data, indices) = args_2!(inputs);
+        Ok(tvec!(dispatch_datum!(Self::eval_t(data.datum_type())(
+            self, data, &indices
+        ))?))
+    }
+}
+
+impl InferenceRulesOp for Gather {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p SharedTensorsProxy,
+        outputs: &'p SharedTensorsProxy,
+    ) -> InferenceResult {
+        s.equals(&inputs.len, 2)?;
+        s.equals(&outputs.len, 1)?;
+        s.equals(&outputs[0].datum_type, &inputs[0].datum_type)?;
+        s.equals(
+            inputs[0].rank.bex() + inputs[1].rank.bex() - 1,
+            &outputs[0].rank,
+        )?;
+        s.given_2(
+            &inputs[0].shape,
+            &inputs[1].shape,
+            move |s, data_shape, indices_shape| {
+                let axis = self.resolved_axis(data_shape.len())?;
+                let mut output_shape: TVec<TDim> = data_shape[..axis].into();
+                output_shape.extend(indices_shape.iter().cloned());
+                output_shape.extend(data_shape[axis + 1..].iter().cloned());
+                s.equals(&outputs[0].shape, output_shape)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gather_rows() {
+        let op = Gather::new(0);
+        let data: SharedTensor = Array::from_shape_fn((3, 4), |(i, j)| (i * 4 + j) as f32).into();
+        let indices: SharedTensor = arr1(&[2i64, 0]).into();
+        let result = op.eval(tvec!(data, indices)).unwrap();
+        assert_eq!(
+            result[0].to_array_view::<f32>().unwrap(),
+            arr2(&[[8.0f32, 9.0, 10.0, 11.0], [0.0, 1.0, 2.0, 3.0]]).into_dyn()
+        );
+    }
+
+    #[test]
+    fn gather_rows_inference() {
+        let op = Gather::new(0);
+        let data = TensorFact::dt_shape(DatumType::F32, vec![3, 4]);
+        let indices = TensorFact::dt_shape(DatumType::I64, vec![2]);
+        let output = TensorFact::default();
+        let (_, outputs) = op.infer(tvec!(&data, &indices), tvec!(&output)).unwrap();
+        assert_eq!(outputs[0], TensorFact::dt_shape(DatumType::F32, vec![2, 4]));
+    }
+}
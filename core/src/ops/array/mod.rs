@@ -35,7 +35,7 @@
 ///     unary, attr are: begins, ends, and optional axes remapping them
 /// * [Upsample](https://github.com/onnx/onnx/blob/master/docs/Operators.md#Upsample),
 ///     unary, attrs are scales (floats) and mode of interpolation (nearest or
-///     linear). not impl.
+///     linear). Built as a Resize.
 /// * DynamicSlice, experimental, not impl
 ///
 /// ### Ours
@@ -45,29 +45,38 @@ mod add_dims;
 mod broadcast;
 mod concat;
 mod constant_like;
+mod depth_to_space;
 mod flatten;
+mod gather;
 mod pad;
 mod permute_axes;
 mod reshape;
+mod resize;
 mod rm_dims;
 mod shape;
 mod size;
 mod slice;
 mod split;
 mod squeeze;
+mod tile;
 
 pub use self::add_dims::AddDims;
 pub use self::broadcast::MultiBroadcastTo;
 pub use self::concat::Concat;
 pub use self::constant_like::ConstantLike;
+pub use self::constant_like::ConstantOfShape;
 pub use self::constant_like::EyeLike;
+pub use self::depth_to_space::{DepthToSpace, DepthToSpaceMode, SpaceToDepth};
 pub use self::flatten::Flatten;
+pub use self::gather::Gather;
 pub use self::pad::{Pad, PadMode};
 pub use self::permute_axes::PermuteAxes;
-pub use self::reshape::Reshape;
+pub use self::reshape::{Reshape, ReshapeUnary};
+pub use self::resize::{CoordTransform, InterpolationMode, NearestMode, Resize};
 pub use self::rm_dims::RmDims;
 pub use self::shape::Shape;
 pub use self::size::Size;
-pub use self::slice::Slice;
+pub use self::slice::{AxisSlice, Slice};
 pub use self::split::Split;
 pub use self::squeeze::Squeeze;
+pub use self::tile::Tile;
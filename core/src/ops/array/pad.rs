@@ -91,6 +91,83 @@ impl Op for Pad {
     fn name(&self) -> Cow<str> {
         "Pad".into()
     }
+
+    fn pulsify(&self, mut inputs: TVec<&PulsedTensorFact>) -> TractResult<Vec<PulsifiedOp>> {
+        let input = args_1!(inputs);
+        input.validate()?;
+        if self.pads.len() != input.shape.len() {
+            bail!(
+                "Pad has {} axes, but its input has rank {}",
+                self.pads.len(),
+                input.shape.len()
+            )
+        }
+        let (before, after) = self.pads[input.axis];
+        if after != 0 {
+            bail!("Can not pad the end of a stream")
+        }
+        let value = match self.mode {
+            PadMode::Constant(value) => value,
+            _ if before == 0 => 0.0,
+            _ => bail!("Can only pulsify constant padding along the streaming axis"),
+        };
+        let mut fact = input.clone();
+        let mut pulsed = vec![];
+        if self
+            .pads
+            .iter()
+            .enumerate()
+            .any(|(ax, &pads)| !input.is_stream_axis(ax) && pads != (0, 0))
+        {
+            let mut pads = self.pads.clone();
+            pads[input.axis] = (0, 0);
+            for (ax, &(a, b)) in pads.iter().enumerate() {
+                fact.shape[ax] += a + b;
+            }
+            pulsed.push(PulsifiedOp::new(
+                Box::new(Pad::new(pads, self.mode.clone())),
+                tvec!(fact.clone()),
+            ));
+        }
+        if before > 0 {
+            // The padding takes the place of the frames before the stream
+            // start, delaying the stream if there are not enough of them.
+            let missing = before.saturating_sub(fact.delay);
+            let undelayed = fact.clone();
+            let mut delayed = fact.clone();
+            delayed.delay += missing;
+            fact.delay = delayed.delay - before;
+            fact.dim += before.to_dim();
+            // the delay buffer starts zeroed, so zero padding of an
+            // undelayed stream comes for free
+            let filled = input.delay > 0 || value != 0.0;
+            if missing > 0 {
+                let output = if filled { &delayed } else { &fact };
+                pulsed.push(PulsifiedOp::new(
+                    Box::new(crate::pulse::delay::Delay::new(undelayed, missing, 0)),
+                    tvec!(output.clone()),
+                ));
+            }
+            if filled {
+                pulsed.push(PulsifiedOp::new(
+                    Box::new(PulsePad::new(
+                        input.axis,
+                        fact.delay,
+                        fact.delay + before,
+                        value,
+                    )),
+                    tvec!(fact.clone()),
+                ));
+            }
+        }
+        if pulsed.is_empty() {
+            pulsed.push(PulsifiedOp::new(
+                Box::new(crate::ops::identity::Identity::default()),
+                tvec!(fact),
+            ));
+        }
+        Ok(pulsed)
+    }
 }
 
 impl StatelessOp for Pad {
@@ -123,3 +200,169 @@ impl InferenceRulesOp for Pad {
         Ok(())
     }
 }
+
+/// Overwrites the frames `begin..end` of a pulsed stream with `value`, to
+/// pad the start of the stream.
+#[derive(Debug, Clone, new)]
+struct PulsePad {
+    axis: usize,
+    begin: usize,
+    end: usize,
+    value: f32,
+}
+
+impl Op for PulsePad {
+    fn name(&self) -> Cow<str> {
+        "PulsePad".into()
+    }
+
+    fn is_stateless(&self) -> bool {
+        false
+    }
+}
+
+impl StatefullOp for PulsePad {
+    fn state(&self) -> TractResult<Option<Box<OpState>>> {
+        Ok(Some(Box::new(PulsePadState::default())))
+    }
+}
+
+impl InferenceRulesOp for PulsePad {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p SharedTensorsProxy,
+        outputs: &'p SharedTensorsProxy,
+    ) -> InferenceResult {
+        s.equals(&inputs.len, 1)?;
+        s.equals(&outputs.len, 1)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[0].shape, &outputs[0].shape)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct PulsePadState {
+    current_pos: usize,
+}
+
+impl PulsePadState {
+    fn eval_t<T>(&mut self, op: &PulsePad, input: SharedTensor) -> TractResult<SharedTensor>
+    where
+        T: Datum,
+        f32: AsPrimitive<T>,
+    {
+        let mut input = input.to_array::<T>()?;
+        let pulse = input.shape()[op.axis];
+        let begin = op.begin.max(self.current_pos);
+        let end = op.end.min(self.current_pos + pulse);
+        if begin < end {
+            input
+                .slice_axis_mut(
+                    Axis(op.axis),
+                    Slice::from((begin - self.current_pos)..(end - self.current_pos)),
+                )
+                .fill(op.value.as_());
+        }
+        self.current_pos += pulse;
+        Ok(input.into())
+    }
+}
+
+impl OpState for PulsePadState {
+    fn eval(&mut self, op: &Op, mut inputs: TVec<SharedTensor>) -> TractResult<TVec<SharedTensor>> {
+        let input = args_1!(inputs);
+        let op = op.downcast_ref::<PulsePad>().ok_or("Wrong Op type")?;
+        Ok(tvec!(dispatch_numbers!(Self::eval_t(input.datum_type())(
+            self, op, input
+        ))?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::dsl::ModelDsl;
+    use crate::Model;
+
+    fn stream_model(pads: Vec<(usize, usize)>, mode: PadMode, skip: usize) -> Model {
+        let mut model = Model::default();
+        model
+            .add_source_fact(
+                "a",
+                TensorFact::dt_shape(DatumType::F32, vec![TDim::s(), 2.to_dim()]),
+            )
+            .unwrap();
+        if skip > 0 {
+            model
+                .chain(
+                    "skip",
+                    Box::new(super::super::Slice::new(vec![(skip, 0), (0, 0)])),
+                )
+                .unwrap();
+        }
+        model.chain("pad", Box::new(Pad::new(pads, mode))).unwrap();
+        model.analyse().unwrap();
+        model
+    }
+
+    /// Runs the pulsed model on `chunks` pulses of 4 frames of (t * 2 + c + 1)
+    /// values, and returns the output frames after the output delay.
+    fn run_pulsed(model: &Model, chunks: usize) -> (PulsedTensorFact, Vec<Vec<f32>>) {
+        let (pulsed, _, output) = crate::pulse::pulsify(model, 4).unwrap();
+        let plan = crate::plan::SimplePlan::new(&pulsed).unwrap();
+        let mut state = crate::plan::SimpleState::new(&plan).unwrap();
+        let mut frames = vec![];
+        for i in 0..chunks {
+            let chunk = Tensor::from(Array::from_shape_fn((4, 2), |(t, c)| {
+                ((i * 4 + t) * 2 + c + 1) as f32
+            }));
+            let result = state.run(tvec!(chunk)).unwrap();
+            let result = result[0].to_array_view::<f32>().unwrap();
+            frames.extend(result.outer_iter().map(|f| f.iter().cloned().collect()));
+        }
+        (output.clone(), frames.split_off(output.delay))
+    }
+
+    fn frame(t: usize, before: f32, after: f32) -> Vec<f32> {
+        let mut frame = vec![before];
+        frame.extend((0..2).map(|c| (t * 2 + c + 1) as f32));
+        frame.push(after);
+        frame
+    }
+
+    #[test]
+    fn pad_pulsify_prepends_frames() {
+        let model = stream_model(vec![(2, 0), (0, 0)], PadMode::default(), 0);
+        let (output, frames) = run_pulsed(&model, 3);
+        assert_eq!(output.shape, tvec!(4, 2));
+        assert_eq!(output.delay, 0);
+        assert_eq!(output.dim, TDim::s() + 2);
+        assert_eq!(&frames[..2], &[vec![0.0, 0.0], vec![0.0, 0.0]]);
+        for (t, f) in frames[2..].iter().enumerate() {
+            assert_eq!(f, &vec![(t * 2 + 1) as f32, (t * 2 + 2) as f32]);
+        }
+    }
+
+    #[test]
+    fn pad_pulsify_fills_delayed_stream_and_pads_other_axis() {
+        let model = stream_model(vec![(3, 0), (1, 1)], PadMode::Constant(-1.0), 2);
+        let (output, frames) = run_pulsed(&model, 4);
+        assert_eq!(output.shape, tvec!(4, 4));
+        assert_eq!(output.dim, TDim::s() + 1);
+        for f in &frames[..3] {
+            assert_eq!(f, &vec![-1.0; 4]);
+        }
+        for (t, f) in frames[3..].iter().enumerate() {
+            assert_eq!(f, &frame(t + 2, -1.0, -1.0));
+        }
+    }
+
+    #[test]
+    fn pad_pulsify_rejects_end_of_stream() {
+        let model = stream_model(vec![(0, 2), (0, 0)], PadMode::default(), 0);
+        assert!(crate::pulse::pulsify(&model, 4).is_err());
+        let model = stream_model(vec![(1, 0), (0, 0)], PadMode::Edge, 0);
+        assert!(crate::pulse::pulsify(&model, 4).is_err());
+    }
+}
@@ -34,6 +34,10 @@ impl Op for PermuteAxes {
     fn name(&self) -> Cow<str> {
         "PermuteAxes".into()
     }
+
+    fn is_foldable(&self) -> bool {
+        true
+    }
 }
 
 impl StatelessOp for PermuteAxes {
@@ -59,3 +63,61 @@ impl InferenceRulesOp for PermuteAxes {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::dsl::ModelDsl;
+    use crate::model::{InletId, Model, OutletId};
+    use ndarray::arr2;
+
+    fn add_to_constant(op: Box<Op>) -> (Model, usize, usize) {
+        let mut model = Model::default();
+        let source = model
+            .add_source_fact("a", TensorFact::dt_shape(DatumType::F32, vec![2, 2]))
+            .unwrap();
+        model
+            .add_const("k", arr2(&[[1.0f32, 2.0], [3.0, 4.0]]).into())
+            .unwrap();
+        let folded = model.chain("op", op).unwrap();
+        let add = model
+            .add_node(
+                "add".to_string(),
+                Box::new(crate::ops::math::Add::default()),
+            )
+            .unwrap();
+        model
+            .add_edge(OutletId::new(source, 0), InletId::new(add, 0))
+            .unwrap();
+        model
+            .add_edge(OutletId::new(folded, 0), InletId::new(add, 1))
+            .unwrap();
+        (model, folded, add)
+    }
+
+    #[test]
+    fn permute_axes_of_constant_is_folded() {
+        let (model, permute, _) = add_to_constant(Box::new(PermuteAxes::new(Some(vec![1, 0]))));
+        assert!(model.node(permute).op().is_foldable());
+        let model = model.into_optimized().unwrap();
+        assert!(model.nodes().iter().all(|n| n.op().name() != "PermuteAxes"));
+        assert_eq!(model.nodes().len(), 2);
+        let plan = crate::plan::SimplePlan::new(&model).unwrap();
+        let zeros: Tensor = arr2(&[[0.0f32, 0.0], [0.0, 0.0]]).into();
+        let result = plan.run(tvec!(zeros)).unwrap();
+        assert_eq!(
+            result[0].to_array_view::<f32>().unwrap(),
+            arr2(&[[1.0f32, 3.0], [2.0, 4.0]]).into_dyn()
+        );
+    }
+
+    #[test]
+    fn other_ops_of_constant_are_not_folded() {
+        use crate::optim::{OptimizerPass, PropConst};
+        let (mut model, neg, add) = add_to_constant(Box::new(crate::ops::math::Neg::default()));
+        assert!(!model.node(neg).op().is_foldable());
+        model.analyse().unwrap();
+        assert!(!PropConst::default().pass(&mut model).unwrap());
+        assert_eq!(model.node(add).inputs[1].node, neg);
+    }
+}
@@ -10,31 +10,60 @@ impl Reshape {
         }
         let mut result: Vec<D> = shape
             .iter()
-            .zip(input.iter())
-            .map(|(&shape, &input)| {
+            .enumerate()
+            .map(|(ix, &shape)| {
                 if shape > 0 {
-                    D::from(shape as usize)
+                    Ok(D::from(shape as usize))
+                } else if shape == -1 {
+                    Ok(D::one())
                 } else {
-                    input
+                    input.get(ix).cloned().ok_or_else(|| {
+                        format!("Can not copy dimension {} from shape {:?}", ix, input).into()
+                    })
                 }
             })
-            .collect();
+            .collect::<TractResult<_>>()?;
         if let Some(minus_one) = shape.iter().position(|d| *d == -1) {
-            let prod_input: usize = input
-                .iter()
-                .try_fold(1, |acc, dim| dim.to_integer().map(|a| a as usize * acc))?;
-            let prod_shape: usize = result
-                .iter()
-                .enumerate()
-                .filter(|(ix, _)| *ix != minus_one)
-                .try_fold(1, |acc, (_, dim)| {
-                    dim.to_integer().map(|a| a as usize * acc)
-                })?;
-            result[minus_one] = D::from(prod_input / prod_shape);
+            let (input_sym, input_prod) = Self::split_product(input.iter())?;
+            let (others_sym, others_prod) = Self::split_product(
+                result
+                    .iter()
+                    .enumerate()
+                    .filter(|(ix, _)| *ix != minus_one)
+                    .map(|(_, d)| d),
+            )?;
+            let inferred = input_prod / others_prod;
+            result[minus_one] = match (input_sym, others_sym) {
+                (Some(a), Some(b)) if a == b => D::from(inferred),
+                (Some(a), None) => a * inferred,
+                (None, None) => D::from(inferred),
+                _ => bail!(
+                    "Can not infer dimension {} reshaping {:?}",
+                    minus_one,
+                    input
+                ),
+            };
         }
         Ok(result)
     }
 
+    /// Splits a product of dimensions into its symbolic factor, if any, and
+    /// its integer one. More than one symbolic factor is an error.
+    fn split_product<'a, D: DimLike>(
+        dims: impl Iterator<Item = &'a D>,
+    ) -> TractResult<(Option<D>, usize)> {
+        let mut sym = None;
+        let mut prod = 1;
+        for dim in dims {
+            match dim.to_integer() {
+                Ok(d) => prod *= d as usize,
+                Err(_) if sym.is_none() => sym = Some(*dim),
+                Err(e) => return Err(e),
+            }
+        }
+        Ok((sym, prod))
+    }
+
     /// Evaluates the operation given the input tensors.
     fn eval_t<T: Datum>(
         &self,
@@ -49,6 +78,35 @@ impl Op for Reshape {
     fn name(&self) -> Cow<str> {
         "Reshape".into()
     }
+
+    fn is_foldable(&self) -> bool {
+        true
+    }
+
+    fn const_inputs(&self) -> TVec<usize> {
+        tvec!(1)
+    }
+
+    fn reduce(
+        &self,
+        inputs: TVec<&TensorFact>,
+        _outputs: TVec<&TensorFact>,
+        phase: ReductionPhase,
+    ) -> TractResult<Option<ReducedOpRewire>> {
+        if phase == ReductionPhase::Normalize {
+            if let Some(shape) = inputs[1].value.concretize() {
+                if let Ok(shape) = shape.cast_to::<i64>() {
+                    let shape = shape
+                        .to_array_view::<i64>()?
+                        .iter()
+                        .map(|&i| i as isize)
+                        .collect();
+                    return Ok(Some(ReducedOpRewire::unary(ReshapeUnary::new(shape))));
+                }
+            }
+        }
+        Ok(None)
+    }
 }
 
 impl StatelessOp for Reshape {
@@ -65,6 +123,91 @@ impl StatelessOp for Reshape {
     }
 }
 
+/// Reshape to a constant shape, with the same conventions as `Reshape`: a
+/// zero copies the input dimension, a `-1` is inferred from the others.
+#[derive(Debug, Clone, new)]
+pub struct ReshapeUnary {
+    shape: Vec<isize>,
+}
+
+impl Op for ReshapeUnary {
+    fn name(&self) -> Cow<str> {
+        "ReshapeUnary".into()
+    }
+
+    fn is_foldable(&self) -> bool {
+        true
+    }
+
+    /// The stream axis must be kept apart: it has to be copied (`0`) or
+    /// inferred (`-1`), and the dimensions on both of its sides can only be
+    /// reshaped among themselves.
+    fn pulsify(&self, mut inputs: TVec<&PulsedTensorFact>) -> TractResult<Vec<PulsifiedOp>> {
+        let input = args_1!(inputs);
+        let stream_axis = if self.shape.get(input.axis) == Some(&0) {
+            input.axis
+        } else {
+            match self.shape.iter().position(|&d| d == -1) {
+                Some(ix) => ix,
+                None => bail!(
+                    "ReshapeUnary to {:?} loses the stream axis {}",
+                    self.shape,
+                    input.axis
+                ),
+            }
+        };
+        let shape: TVec<usize> = Reshape::default()
+            .compute_shape(&input.shape, &self.shape)?
+            .into();
+        let before: usize = input.shape[..input.axis].iter().product();
+        let after: usize = input.shape[input.axis + 1..].iter().product();
+        if shape[..stream_axis].iter().product::<usize>() != before
+            || shape[stream_axis + 1..].iter().product::<usize>() != after
+        {
+            bail!(
+                "ReshapeUnary from {:?} to {:?} would merge the stream axis {} with other dimensions",
+                input.shape,
+                self.shape,
+                input.axis
+            )
+        }
+        let mut fact = input.clone();
+        fact.shape = shape;
+        fact.axis = stream_axis;
+        Ok(vec![PulsifiedOp::new(Box::new(self.clone()), tvec!(fact))])
+    }
+}
+
+impl StatelessOp for ReshapeUnary {
+    fn eval(&self, mut inputs: TVec<SharedTensor>) -> TractResult<TVec<SharedTensor>> {
+        let input = args_1!(inputs);
+        let oshape = Reshape::default().compute_shape(input.shape(), &self.shape)?;
+        dispatch_datum!(Reshape::eval_t(input.datum_type())(
+            &Reshape::default(),
+            input,
+            &oshape
+        ))
+    }
+}
+
+impl InferenceRulesOp for ReshapeUnary {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p SharedTensorsProxy,
+        outputs: &'p SharedTensorsProxy,
+    ) -> InferenceResult {
+        s.equals(&inputs.len, 1)?;
+        s.equals(&outputs.len, 1)?;
+        s.equals(&outputs[0].datum_type, &inputs[0].datum_type)?;
+        s.equals(&outputs[0].rank, self.shape.len() as i32)?;
+        s.given(&inputs[0].shape, move |s, ishape| {
+            let shape = Reshape::default().compute_shape(&ishape, &self.shape)?;
+            s.equals(&outputs[0].shape, ShapeFact::from(shape))
+        })
+    }
+}
+
 impl InferenceRulesOp for Reshape {
     fn rules<'r, 'p: 'r, 's: 'r>(
         &'s self,
@@ -77,6 +220,16 @@ impl InferenceRulesOp for Reshape {
             &inputs[0].shape,
             &inputs[1].value,
             move |s, ishape, shape| {
+                if shape.datum_type() == DatumType::TDim {
+                    let dims = shape.to_array_view::<TDim>()?;
+                    if dims
+                        .iter()
+                        .all(|d| d.to_integer().map(|i| i > 0).unwrap_or(true))
+                    {
+                        let dims: TVec<TDim> = dims.iter().cloned().collect();
+                        return s.equals(&outputs[0].shape, ShapeFact::from(dims));
+                    }
+                }
                 let shape: Vec<isize> = shape
                     .cast_to::<i64>()?
                     .to_array_view::<i64>()?
@@ -89,3 +242,42 @@ impl InferenceRulesOp for Reshape {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyser::Analyser;
+    use crate::model::dsl::ModelDsl;
+    use crate::model::*;
+    use ndarray::arr1;
+
+    fn streaming_reshape(shape: &[i64]) -> TractResult<Vec<PulsifiedOp>> {
+        let mut model = Model::default();
+        let target = model.add_const("shape", arr1(shape).into())?;
+        model.add_source_fact("a", TensorFact::dt_shape(DatumType::F32, shapefact!(S, 6)))?;
+        let reshape = model.chain("reshape", Box::new(Reshape::default()))?;
+        model.add_edge(OutletId::new(target, 0), InletId::new(reshape, 1))?;
+        model.analyse()?;
+        let mut model = model.into_optimized()?;
+        let pulsed = Analyser::new(&mut model)?.pulsify_all(0, 4)?;
+        Ok(pulsed)
+    }
+
+    #[test]
+    fn pulsify_split_inner_dim() {
+        for shape in &[[0, 2, 3], [-1, 2, 3], [0, -1, 3]] {
+            let pulsed = streaming_reshape(shape).unwrap();
+            let fact = &pulsed.last().unwrap().outputs[0];
+            assert_eq!(fact.shape, tvec!(4, 2, 3));
+            assert_eq!(fact.axis, 0);
+            assert_eq!(fact.dim, TDim::s());
+        }
+    }
+
+    #[test]
+    fn pulsify_merging_stream_axis_fails() {
+        assert!(streaming_reshape(&[-1]).is_err());
+        assert!(streaming_reshape(&[0, 3, 2]).is_ok());
+        assert!(streaming_reshape(&[2, -1]).is_err());
+    }
+}
@@ -0,0 +1,456 @@
+use crate::ops::prelude::*;
+use ndarray::*;
+use num_traits::{AsPrimitive, Float};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InterpolationMode {
+    Nearest(NearestMode),
+    Linear,
+}
+
+impl Default for InterpolationMode {
+    fn default() -> InterpolationMode {
+        InterpolationMode::Nearest(NearestMode::default())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NearestMode {
+    RoundPreferFloor,
+    RoundPreferCeil,
+    Floor,
+    Ceil,
+}
+
+impl Default for NearestMode {
+    fn default() -> NearestMode {
+        NearestMode::RoundPreferFloor
+    }
+}
+
+impl NearestMode {
+    fn apply(&self, x: f32) -> f32 {
+        match self {
+            NearestMode::RoundPreferFloor if x.fract() == 0.5 => x.floor(),
+            NearestMode::RoundPreferCeil if x.fract() == 0.5 => x.ceil(),
+            NearestMode::RoundPreferFloor | NearestMode::RoundPreferCeil => x.round(),
+            NearestMode::Floor => x.floor(),
+            NearestMode::Ceil => x.ceil(),
+        }
+    }
+}
+
+/// How an output coordinate maps to a coordinate in the input.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CoordTransform {
+    HalfPixel,
+    PytorchHalfPixel,
+    AlignCorners,
+    Asymmetric,
+}
+
+impl Default for CoordTransform {
+    fn default() -> CoordTransform {
+        CoordTransform::HalfPixel
+    }
+}
+
+impl CoordTransform {
+    fn apply(&self, x: usize, scale: f32, input_len: usize, output_len: usize) -> f32 {
+        let x = x as f32;
+        match self {
+            CoordTransform::HalfPixel => (x + 0.5) / scale - 0.5,
+            CoordTransform::PytorchHalfPixel if output_len > 1 => (x + 0.5) / scale - 0.5,
+            CoordTransform::PytorchHalfPixel => 0.0,
+            CoordTransform::AlignCorners if output_len > 1 => {
+                x * (input_len - 1) as f32 / (output_len - 1) as f32
+            }
+            CoordTransform::AlignCorners => 0.0,
+            CoordTransform::Asymmetric => x / scale,
+        }
+    }
+}
+
+/// Resizes a tensor, taking either the scales or the output sizes from its
+/// inputs, or constant scales given with `with_scales`.
+#[derive(Debug, Clone, new, Default)]
+pub struct Resize {
+    mode: InterpolationMode,
+    coord_transform: CoordTransform,
+    scales_input: Option<usize>,
+    sizes_input: Option<usize>,
+    #[new(default)]
+    scales: Option<Vec<f32>>,
+}
+
+impl Resize {
+    /// Uses constant scales instead of reading them from an input.
+    pub fn with_scales(self, scales: Vec<f32>) -> Resize {
+        Resize {
+            scales: Some(scales),
+            ..self
+        }
+    }
+
+    fn output_shape(
+        &self,
+        input_shape: &[usize],
+        scales: Option<&[f32]>,
+        sizes: Option<&[usize]>,
+    ) -> TractResult<Vec<usize>> {
+        let shape = if let Some(sizes) = sizes {
+            check_rank("sizes", sizes.len(), input_shape.len())?;
+            sizes.to_vec()
+        } else {
+            let scales = scales.ok_or("Resize needs either scales or sizes")?;
+            check_rank("scales", scales.len(), input_shape.len())?;
+            input_shape
+                .iter()
+                .zip(scales.iter())
+                .map(|(&d, &s)| scaled_dim(d as i32, s).map(|d| d as usize))
+                .collect::<TractResult<_>>()?
+        };
+        for (axis, (&input, &output)) in input_shape.iter().zip(shape.iter()).enumerate() {
+            if input == 0 && output != 0 {
+                bail!(
+                    "Resize can not fill axis {} of size {} from an empty input axis",
+                    axis,
+                    output
+                )
+            }
+        }
+        Ok(shape)
+    }
+
+    fn input_coord(&self, axis: usize, x: usize, input_shape: &[usize], shape: &[usize]) -> f32 {
+        let scale = shape[axis] as f32 / input_shape[axis] as f32;
+        self.coord_transform
+            .apply(x, scale, input_shape[axis], shape[axis])
+    }
+
+    fn eval_nearest<T: Datum>(
+        &self,
+        input: &Tensor,
+        shape: &[usize],
+        nearest: NearestMode,
+    ) -> TractResult<SharedTensor> {
+        let input = input.to_array_view::<T>()?;
+        let input_shape = input.shape();
+        let output = ArrayD::from_shape_fn(shape, |coords| {
+            let coords: Vec<usize> = coords
+                .slice()
+                .iter()
+                .enumerate()
+                .map(|(axis, &x)| {
+                    let x = nearest.apply(self.input_coord(axis, x, input_shape, shape));
+                    (x.max(0.0) as usize).min(input_shape[axis] - 1)
+                })
+                .collect();
+            input[&*coords]
+        });
+        Ok(output.into())
+    }
+
+    fn eval_linear<T>(&self, input: &Tensor, shape: &[usize]) -> TractResult<SharedTensor>
+    where
+        T: Datum + Float,
+        f32: AsPrimitive<T>,
+    {
+        let input = input.to_array_view::<T>()?;
+        let input_shape = input.shape();
+        if input_shape.len() != 4 || shape[0] != input_shape[0] || shape[1] != input_shape[1] {
+            bail!("Linear resize is only supported on the spatial axes of NCHW tensors")
+        }
+        let locate = |axis: usize, x: usize| {
+            let max = input_shape[axis] - 1;
+            let x = self
+                .input_coord(axis, x, input_shape, shape)
+                .max(0.0)
+                .min(max as f32);
+            let low = x.floor() as usize;
+            (low, (low + 1).min(max), (x - low as f32).as_())
+        };
+        let output =
+            Array4::from_shape_fn((shape[0], shape[1], shape[2], shape[3]), |(n, c, y, x)| {
+                let (y0, y1, dy) = locate(2, y);
+                let (x0, x1, dx) = locate(3, x);
+                let one = T::one();
+                let top = input[[n, c, y0, x0]] * (one - dx) + input[[n, c, y0, x1]] * dx;
+                let bottom = input[[n, c, y1, x0]] * (one - dx) + input[[n, c, y1, x1]] * dx;
+                top * (one - dy) + bottom * dy
+            });
+        Ok(output.into())
+    }
+}
+
+impl Op for Resize {
+    fn name(&self) -> Cow<str> {
+        "Resize".into()
+    }
+
+    fn const_inputs(&self) -> TVec<usize> {
+        self.scales_input
+            .iter()
+            .chain(self.sizes_input.iter())
+            .cloned()
+            .collect()
+    }
+}
+
+impl StatelessOp for Resize {
+    fn eval(&self, inputs: TVec<SharedTensor>) -> TractResult<TVec<SharedTensor>> {
+        let input = &inputs[0];
+        let scales: Option<Vec<f32>> = match self.scales_input {
+            None if self.scales.is_some() => self.scales.clone(),
+            Some(ix) => Some(
+                inputs[ix]
+                    .cast_to::<f32>()?
+                    .to_array_view::<f32>()?
+                    .iter()
+                    .cloned()
+                    .collect(),
+            ),
+            None => None,
+        };
+        let sizes: Option<Vec<usize>> = match self.sizes_input {
+            Some(ix) => Some(
+                read_sizes(&inputs[ix])?
+                    .into_iter()
+                    .map(|d| d as usize)
+                    .collect(),
+            ),
+            None => None,
+        };
+        let shape = self.output_shape(
+            input.shape(),
+            scales.as_ref().map(|s| &**s),
+            sizes.as_ref().map(|s| &**s),
+        )?;
+        let output = match self.mode {
+            InterpolationMode::Nearest(nearest) => {
+                dispatch_datum!(Self::eval_nearest(input.datum_type())(
+                    self,
+                    input.as_tensor(),
+                    &shape,
+                    nearest
+                ))?
+            }
+            InterpolationMode::Linear => dispatch_floatlike!(Self::eval_linear(
+                input.datum_type()
+            )(
+                self, input.as_tensor(), &shape
+            ))?,
+        };
+        Ok(tvec!(output))
+    }
+}
+
+impl InferenceRulesOp for Resize {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p SharedTensorsProxy,
+        outputs: &'p SharedTensorsProxy,
+    ) -> InferenceResult {
+        s.equals(&outputs.len, 1)?;
+        s.equals(&outputs[0].datum_type, &inputs[0].datum_type)?;
+        s.equals(&outputs[0].rank, &inputs[0].rank)?;
+        if let Some(ix) = self.sizes_input {
+            s.given(&inputs[ix].value, move |s, sizes| {
+                let sizes: TVec<TDim> = read_sizes(&sizes)?
+                    .into_iter()
+                    .map(|d| d.to_dim())
+                    .collect();
+                s.equals(&inputs[0].rank, sizes.len() as i32)?;
+                s.equals(&outputs[0].shape, ShapeFact::from(sizes))
+            })?;
+        }
+        if let Some(ix) = self.scales_input {
+            s.given_2(
+                &inputs[0].shape,
+                &inputs[ix].value,
+                move |s, shape, scales| {
+                    let scales = scales.cast_to::<f32>()?;
+                    let scales = scales.to_array_view::<f32>()?;
+                    scaled_shape_rules(
+                        s,
+                        outputs,
+                        &shape,
+                        &scales.iter().cloned().collect::<Vec<_>>(),
+                    )
+                },
+            )?;
+        } else if let Some(ref scales) = self.scales {
+            s.given(&inputs[0].shape, move |s, shape| {
+                scaled_shape_rules(s, outputs, &shape, scales)
+            })?;
+        }
+        Ok(())
+    }
+}
+
+fn scaled_shape_rules<'r>(
+    s: &mut Solver<'r>,
+    outputs: &'r SharedTensorsProxy,
+    shape: &[TDim],
+    scales: &[f32],
+) -> InferenceResult {
+    check_rank("scales", scales.len(), shape.len())?;
+    for (axis, (&dim, &scale)) in shape.iter().zip(scales.iter()).enumerate() {
+        if scale == 1.0 {
+            s.equals(&outputs[0].shape[axis], dim)?;
+        } else if let Ok(dim) = dim.to_integer() {
+            s.equals(&outputs[0].shape[axis], scaled_dim(dim, scale)?.to_dim())?;
+        }
+    }
+    Ok(())
+}
+
+fn check_rank(what: &str, len: usize, rank: usize) -> TractResult<()> {
+    if len != rank {
+        bail!(
+            "Resize got {} {}, but its input has rank {}",
+            len,
+            what,
+            rank
+        )
+    }
+    Ok(())
+}
+
+/// Reads output sizes, rejecting negative ones.
+fn read_sizes(sizes: &Tensor) -> TractResult<Vec<i32>> {
+    sizes
+        .cast_to::<i64>()?
+        .to_array_view::<i64>()?
+        .iter()
+        .map(|&d| {
+            if d < 0 {
+                bail!("Resize got a negative output size: {}", d)
+            }
+            Ok(d as i32)
+        })
+        .collect()
+}
+
+fn scaled_dim(dim: i32, scale: f32) -> TractResult<i32> {
+    if scale.is_nan() || scale < 0.0 {
+        bail!("Resize got an invalid scale: {}", scale)
+    }
+    Ok((dim as f32 * scale).floor() as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nearest_upsample_2x() {
+        let op = Resize::new(
+            InterpolationMode::Nearest(NearestMode::Floor),
+            CoordTransform::Asymmetric,
+            Some(1),
+            None,
+        );
+        let input: SharedTensor = arr4(&[[[[1.0f32, 2.0], [3.0, 4.0]]]]).into();
+        let scales: SharedTensor = arr1(&[1.0f32, 1.0, 2.0, 2.0]).into();
+        let result = op.eval(tvec!(input, scales)).unwrap();
+        assert_eq!(
+            result[0].to_array_view::<f32>().unwrap(),
+            arr4(&[[[
+                [1.0f32, 1.0, 2.0, 2.0],
+                [1.0, 1.0, 2.0, 2.0],
+                [3.0, 3.0, 4.0, 4.0],
+                [3.0, 3.0, 4.0, 4.0]
+            ]]])
+            .into_dyn()
+        );
+    }
+
+    #[test]
+    fn linear_upsample_2x() {
+        let op = Resize::new(
+            InterpolationMode::Linear,
+            CoordTransform::HalfPixel,
+            Some(1),
+            None,
+        );
+        let input: SharedTensor = arr4(&[[[[1.0f32, 2.0], [3.0, 4.0]]]]).into();
+        let scales: SharedTensor = arr1(&[1.0f32, 1.0, 2.0, 2.0]).into();
+        let result = op.eval(tvec!(input, scales)).unwrap();
+        // onnx backend test "resize_upsample_scales_linear"
+        assert_close!(
+            *result[0].as_tensor(),
+            Tensor::from(arr4(&[[[
+                [1.0f32, 1.25, 1.75, 2.0],
+                [1.5, 1.75, 2.25, 2.5],
+                [2.5, 2.75, 3.25, 3.5],
+                [3.0, 3.25, 3.75, 4.0]
+            ]]]))
+        );
+    }
+
+    #[test]
+    fn resize_inference() {
+        let op = Resize::new(
+            InterpolationMode::default(),
+            CoordTransform::default(),
+            Some(1),
+            None,
+        );
+        let input = TensorFact::dt_shape(DatumType::F32, vec![1, 3, 5, 4]);
+        let scales: TensorFact = SharedTensor::from(arr1(&[1.0f32, 1.0, 1.5, 2.0])).into();
+        let output = TensorFact::default();
+        let (_, outputs) = op.infer(tvec!(&input, &scales), tvec!(&output)).unwrap();
+        assert_eq!(
+            outputs[0],
+            TensorFact::dt_shape(DatumType::F32, vec![1, 3, 7, 8])
+        );
+    }
+
+    #[test]
+    fn resize_rejects_invalid_scales_and_sizes() {
+        let nearest = Resize::new(
+            InterpolationMode::default(),
+            CoordTransform::default(),
+            Some(1),
+            None,
+        );
+        let input: SharedTensor = arr2(&[[1.0f32, 2.0]]).into();
+        let scales: SharedTensor = arr1(&[2.0f32]).into();
+        assert!(nearest.eval(tvec!(input.clone(), scales)).is_err());
+        let scales: SharedTensor = arr1(&[1.0f32, -2.0]).into();
+        assert!(nearest.eval(tvec!(input.clone(), scales)).is_err());
+
+        let sized = Resize::new(
+            InterpolationMode::default(),
+            CoordTransform::default(),
+            None,
+            Some(1),
+        );
+        let sizes: SharedTensor = arr1(&[1i64, -4]).into();
+        assert!(sized.eval(tvec!(input.clone(), sizes)).is_err());
+        let sizes: SharedTensor = arr1(&[1i64, 4, 2]).into();
+        assert!(sized.eval(tvec!(input, sizes)).is_err());
+
+        let empty: SharedTensor = Array2::<f32>::zeros((1, 0)).into();
+        let sizes: SharedTensor = arr1(&[1i64, 4]).into();
+        assert!(sized.eval(tvec!(empty, sizes)).is_err());
+    }
+
+    #[test]
+    fn resize_rules_check_rank() {
+        let op = Resize::new(
+            InterpolationMode::default(),
+            CoordTransform::default(),
+            None,
+            Some(1),
+        );
+        let input = TensorFact::dt_shape(DatumType::F32, vec![1, 3, 5, 4]);
+        let sizes = TensorFact::from(arr1(&[1i64, 3, 10]));
+        let output = TensorFact::default();
+        assert!(op.infer(tvec!(&input, &sizes), tvec!(&output)).is_err());
+        let sizes = TensorFact::from(arr1(&[1i64, 3, 10, -8]));
+        assert!(op.infer(tvec!(&input, &sizes), tvec!(&output)).is_err());
+    }
+}
@@ -52,6 +52,13 @@ impl InferenceRulesOp for Shape {
             }
             Ok(())
         })?;
+        s.with(&inputs[0].shape, move |s, shape| {
+            if !shape.is_open() && !shape.is_concrete() {
+                let dims = Array1::from_iter(shape.dims()).into_dyn();
+                s.equals(&outputs[0].value, ValueFact::Partial(dims))?;
+            }
+            Ok(())
+        })?;
         s.given(&inputs[0].shape, move |s, shape| {
             if shape.iter().any(|&d| d.to_integer().is_err()) {
                 s.equals(&outputs[0].datum_type, DatumType::TDim)?;
@@ -157,3 +164,110 @@ mod tests {
     }
 }
 */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::dsl::ModelDsl;
+    use crate::model::{InletId, OutletId};
+    use crate::Model;
+
+    #[test]
+    fn shape_of_partially_known_shape() {
+        let mut model = Model::default();
+        model
+            .add_source_fact(
+                "a",
+                TensorFact {
+                    shape: shapefact![1, _, 3],
+                    ..TensorFact::default()
+                },
+            )
+            .unwrap();
+        model
+            .chain("shape", Box::new(Shape::new(DatumType::I64)))
+            .unwrap();
+        model.analyse().unwrap();
+        let partial = arr1(&[
+            GenericFact::Only(1.to_dim()),
+            GenericFact::Any,
+            GenericFact::Only(3.to_dim()),
+        ]);
+        assert_eq!(
+            model.output_fact().unwrap().value,
+            ValueFact::Partial(partial.into_dyn())
+        );
+    }
+
+    #[test]
+    fn unify_partial_with_tensor() {
+        let partial =
+            ValueFact::Partial(arr1(&[GenericFact::Only(1.to_dim()), GenericFact::Any]).into_dyn());
+        let tensor: ValueFact = SharedTensor::from(arr1(&[1i64, 5])).into();
+        assert_eq!(partial.unify(&tensor).unwrap(), tensor);
+        let wrong: ValueFact = SharedTensor::from(arr1(&[2i64, 5])).into();
+        assert!(partial.unify(&wrong).is_err());
+    }
+
+    #[test]
+    fn shape_feeds_reshape() {
+        let mut model = Model::default();
+        let source = model
+            .add_source_fact("a", TensorFact::dt_shape(DatumType::F32, vec![2, 3, 4]))
+            .unwrap();
+        let shape = model
+            .chain("shape", Box::new(Shape::new(DatumType::I64)))
+            .unwrap();
+        let reshape = model
+            .add_node(
+                "reshape".to_string(),
+                Box::new(crate::ops::array::Reshape::default()),
+            )
+            .unwrap();
+        model
+            .add_edge(OutletId::new(source, 0), InletId::new(reshape, 0))
+            .unwrap();
+        model
+            .add_edge(OutletId::new(shape, 0), InletId::new(reshape, 1))
+            .unwrap();
+        model.analyse().unwrap();
+        let dims: ValueFact = SharedTensor::from(arr1(&[2i64, 3, 4])).into();
+        assert_eq!(model.fact(OutletId::new(shape, 0)).unwrap().value, dims);
+        assert_eq!(
+            model.fact(OutletId::new(reshape, 0)).unwrap(),
+            &TensorFact::dt_shape(DatumType::F32, vec![2, 3, 4])
+        );
+    }
+
+    #[test]
+    fn symbolic_shape_feeds_reshape() {
+        let mut model = Model::default();
+        let n = TDim::sym('N');
+        let source = model
+            .add_source_fact(
+                "a",
+                TensorFact::dt_shape(DatumType::F32, vec![n, 3.to_dim(), 4.to_dim()]),
+            )
+            .unwrap();
+        let shape = model
+            .chain("shape", Box::new(Shape::new(DatumType::I64)))
+            .unwrap();
+        let reshape = model
+            .add_node(
+                "reshape".to_string(),
+                Box::new(crate::ops::array::Reshape::default()),
+            )
+            .unwrap();
+        model
+            .add_edge(OutletId::new(source, 0), InletId::new(reshape, 0))
+            .unwrap();
+        model
+            .add_edge(OutletId::new(shape, 0), InletId::new(reshape, 1))
+            .unwrap();
+        model.analyse().unwrap();
+        assert_eq!(
+            model.fact(OutletId::new(reshape, 0)).unwrap(),
+            &TensorFact::dt_shape(DatumType::F32, vec![n, 3.to_dim(), 4.to_dim()])
+        );
+    }
+}
@@ -1,21 +1,70 @@
 use crate::ops::prelude::*;
 use ndarray::*;
 
-#[derive(Debug, Clone, new, Default)]
+/// How a single axis is sliced: `start` and `end` elements are dropped at
+/// the beginning and at the end of the axis, then one element every `step`
+/// is kept from what remains.
+#[derive(Debug, Clone, Copy, PartialEq, new)]
+pub struct AxisSlice {
+    pub start: usize,
+    pub end: usize,
+    pub step: usize,
+}
+
+impl AxisSlice {
+    /// Length of the axis after slicing, for a `dim` long input axis.
+    pub fn output_dim<D: DimLike>(&self, dim: D) -> D {
+        (dim - (self.start + self.end)).div_ceil(self.step)
+    }
+
+    fn is_noop(&self) -> bool {
+        self.start == 0 && self.end == 0 && self.step == 1
+    }
+}
+
+impl Default for AxisSlice {
+    fn default() -> AxisSlice {
+        AxisSlice::new(0, 0, 1)
+    }
+}
+
+impl From<(usize, usize)> for AxisSlice {
+    fn from((start, end): (usize, usize)) -> AxisSlice {
+        AxisSlice::new(start, end, 1)
+    }
+}
+
+impl From<(usize, usize, usize)> for AxisSlice {
+    fn from((start, end, step): (usize, usize, usize)) -> AxisSlice {
+        AxisSlice::new(start, end, step)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct Slice {
-    prune: Vec<(usize, usize)>,
+    prune: Vec<AxisSlice>,
 }
 
 impl Slice {
+    pub fn new<S: Into<AxisSlice>>(prune: impl IntoIterator<Item = S>) -> Slice {
+        Slice {
+            prune: prune.into_iter().map(|s| s.into()).collect(),
+        }
+    }
+
     fn eval_t<T: Datum>(&self, input: SharedTensor) -> TractResult<SharedTensor> {
         let input = input.to_array_view::<T>()?;
         let slice_spec: Vec<SliceOrIndex> = self
             .prune
             .iter()
-            .map(|&(a, b)| SliceOrIndex::Slice {
-                start: a as isize,
-                end: if b != 0 { Some(-(b as isize)) } else { None },
-                step: 1,
+            .map(|spec| SliceOrIndex::Slice {
+                start: spec.start as isize,
+                end: if spec.end != 0 {
+                    Some(-(spec.end as isize))
+                } else {
+                    None
+                },
+                step: spec.step as isize,
             })
             .collect();
         let slice_info = SliceInfo::<_, IxDyn>::new(slice_spec).unwrap();
@@ -31,22 +80,39 @@ impl Op for Slice {
 
     fn pulsify(&self, mut inputs: TVec<&PulsedTensorFact>) -> TractResult<Vec<PulsifiedOp>> {
         let input = args_1!(inputs);
+        input.validate()?;
+        if self.prune.len() != input.shape.len() {
+            bail!(
+                "Slice prunes {} axes, but its input has rank {}",
+                self.prune.len(),
+                input.shape.len()
+            )
+        }
+        if self.prune[input.axis].step != 1 {
+            bail!("Can not pulsify a Slice striding along the streaming axis")
+        }
+        let delay = self.prune[input.axis].start;
+        let mut fact = input.clone();
+        fact.delay += delay;
+        fact.dim = *fact.stream_dim() - delay.to_dim();
+        let mut pulsed = vec![PulsifiedOp::new(
+            Box::new(crate::ops::identity::Identity::default()),
+            tvec!(fact.clone()),
+        )];
         if self
             .prune
             .iter()
             .enumerate()
-            .all(|(ax, &(a, b))| ax == input.axis || (a == 0 && b == 0))
+            .any(|(ax, spec)| !input.is_stream_axis(ax) && !spec.is_noop())
         {
-            let delay = self.prune[input.axis].0;
-            let mut fact = input.clone();
-            fact.delay += delay;
-            fact.dim -= delay.to_dim();
-            return Ok(vec![PulsifiedOp::new(
-                Box::new(crate::ops::identity::Identity::default()),
-                tvec!(fact),
-            )]);
+            let mut prune = self.prune.clone();
+            prune[input.axis] = AxisSlice::default();
+            for (ax, spec) in prune.iter().enumerate() {
+                fact.shape[ax] = spec.output_dim(fact.shape[ax]);
+            }
+            pulsed.push(PulsifiedOp::new(Box::new(Slice { prune }), tvec!(fact)));
         }
-        unimplemented!();
+        Ok(pulsed)
     }
 }
 
@@ -71,12 +137,122 @@ impl InferenceRulesOp for Slice {
         s.equals(&outputs.len, 1)?;
         s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
         s.equals(&inputs[0].rank, &outputs[0].rank)?;
-        for (ix, &(a, b)) in self.prune.iter().enumerate() {
-            s.equals(
-                &inputs[0].shape[ix],
-                outputs[0].shape[ix].bex() + a.to_dim() + b.to_dim(),
-            )?;
+        s.equals(&inputs[0].rank, self.prune.len() as i32)?;
+        for (ix, spec) in self.prune.iter().enumerate() {
+            if spec.step == 1 {
+                s.equals(
+                    &inputs[0].shape[ix],
+                    outputs[0].shape[ix].bex() + spec.start.to_dim() + spec.end.to_dim(),
+                )?;
+            } else {
+                let spec = *spec;
+                s.given(&inputs[0].shape[ix], move |s, dim| {
+                    s.equals(&outputs[0].shape[ix], spec.output_dim(dim))
+                })?;
+            }
         }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::dsl::ModelDsl;
+    use crate::Model;
+
+    #[test]
+    fn slice_infers_input_from_output() {
+        let op = Slice::new(vec![(1, 2), (0, 0)]);
+        let input = TensorFact::default();
+        let output = TensorFact::dt_shape(DatumType::F32, vec![2, 3]);
+        let (inputs, _) = op.infer(tvec!(&input), tvec!(&output)).unwrap();
+        assert_eq!(inputs[0], TensorFact::dt_shape(DatumType::F32, vec![5, 3]));
+    }
+
+    #[test]
+    fn slice_infers_streaming_input_from_output() {
+        let op = Slice::new(vec![(0, 0), (2, 1)]);
+        let input = TensorFact::default();
+        let output = TensorFact::dt_shape(DatumType::F32, vec![3.to_dim(), TDim::s()]);
+        let (inputs, _) = op.infer(tvec!(&input), tvec!(&output)).unwrap();
+        assert_eq!(
+            inputs[0],
+            TensorFact::dt_shape(DatumType::F32, vec![3.to_dim(), TDim::s() + 3])
+        );
+    }
+
+    #[test]
+    fn slice_pulsify_on_stream_and_other_axis() {
+        let mut model = Model::default();
+        model
+            .add_source_fact(
+                "a",
+                TensorFact::dt_shape(DatumType::F32, vec![TDim::s(), 5.to_dim()]),
+            )
+            .unwrap();
+        model
+            .chain("slice", Box::new(Slice::new(vec![(2, 0), (1, 1)])))
+            .unwrap();
+        model.analyse().unwrap();
+        let (pulsed, _, output) = crate::pulse::pulsify(&model, 4).unwrap();
+        assert_eq!(pulsed.nodes().len(), 3); // source - delay - slice
+        assert_eq!(output.shape, tvec!(4, 3));
+        assert_eq!(output.delay, 2);
+        assert_eq!(output.dim, TDim::s() - 2);
+        let plan = crate::plan::SimplePlan::new(&pulsed).unwrap();
+        let chunk = Tensor::from(Array::from_shape_fn((4, 5), |(t, c)| (t * 5 + c) as f32));
+        let result = plan.run(tvec!(chunk)).unwrap();
+        assert_eq!(
+            result[0].to_array_view::<f32>().unwrap(),
+            Array::from_shape_fn((4, 3), |(t, c)| (t * 5 + c + 1) as f32).into_dyn()
+        );
+    }
+
+    #[test]
+    fn slice_pulsify_rejects_out_of_range_axis() {
+        let op = Slice::new(vec![(2, 0), (1, 1)]);
+        let fact = PulsedTensorFact {
+            dt: DatumType::F32,
+            shape: tvec!(4, 5),
+            axis: 2,
+            dim: TDim::s(),
+            delay: 0,
+        };
+        let err = op.pulsify(tvec!(&fact)).err().unwrap().to_string();
+        assert!(err.contains("out of bounds"), "{}", err);
+    }
+
+    #[test]
+    fn slice_with_step_2() {
+        let op = Slice::new(vec![(0, 0, 1), (1, 0, 2)]);
+        let input = Tensor::from(Array::from_shape_fn((2, 6), |(i, j)| (i * 6 + j) as f32));
+        let outputs = op.eval(tvec!(input.into())).unwrap();
+        assert_eq!(
+            *outputs[0],
+            Tensor::from(arr2(&[[1.0f32, 3.0, 5.0], [7.0, 9.0, 11.0]]))
+        );
+    }
+
+    #[test]
+    fn slice_with_step_2_infers_output() {
+        let op = Slice::new(vec![(1, 0, 2), (0, 1, 2)]);
+        let input = TensorFact::dt_shape(DatumType::F32, vec![6, 6]);
+        let output = TensorFact::default();
+        let (_, outputs) = op.infer(tvec!(&input), tvec!(&output)).unwrap();
+        assert_eq!(outputs[0], TensorFact::dt_shape(DatumType::F32, vec![3, 3]));
+    }
+
+    #[test]
+    fn slice_pulsify_rejects_step_on_stream_axis() {
+        let op = Slice::new(vec![(0, 0, 2), (0, 0, 1)]);
+        let fact = PulsedTensorFact {
+            dt: DatumType::F32,
+            shape: tvec!(4, 5),
+            axis: 0,
+            dim: TDim::s(),
+            delay: 0,
+        };
+        assert!(op.pulsify(tvec!(&fact)).is_err());
+    }
+}
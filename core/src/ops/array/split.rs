@@ -3,7 +3,7 @@ use ndarray::*;
 
 #[derive(Debug, Clone, new, Default)]
 pub struct Split {
-    axis: usize,
+    axis: i64,
     outputs: usize,
     split: Option<Vec<usize>>,
 }
@@ -19,12 +19,13 @@ impl Split {
     fn eval_t<T: Datum>(&self, input: SharedTensor) -> TractResult<TVec<SharedTensor>> {
         let mut current = 0;
         let input = input.to_array_view::<T>()?;
+        let axis = normalize_axis(self.axis, input.ndim())?;
         Ok(self
-            .split_dims(input.shape()[self.axis])?
+            .split_dims(input.shape()[axis])?
             .iter()
             .map(|d| {
                 let slice = input
-                    .slice_axis(Axis(self.axis), (current..current + d).into())
+                    .slice_axis(Axis(axis), (current..current + d).into())
                     .to_owned();
                 current += d;
                 slice.into()
@@ -37,6 +38,10 @@ impl Op for Split {
     fn name(&self) -> Cow<str> {
         "Split".into()
     }
+
+    fn noutputs(&self) -> usize {
+        self.outputs
+    }
 }
 
 impl StatelessOp for Split {
@@ -61,10 +66,11 @@ impl InferenceRulesOp for Split {
             s.equals(&inputs[0].rank, &outputs[i].rank)
         })?;
         s.given(&inputs[0].shape, move |s, shape| {
-            let dims = self.split_dims(shape[self.axis])?;
+            let axis = normalize_axis(self.axis, shape.len())?;
+            let dims = self.split_dims(shape[axis])?;
             for i in 0..self.outputs {
                 let mut shape = shape.clone();
-                shape[self.axis] = dims[i];
+                shape[axis] = dims[i];
                 s.equals(&outputs[i].shape, shape)?;
             }
             Ok(())
@@ -72,3 +78,45 @@ impl InferenceRulesOp for Split {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::dsl::ModelDsl;
+    use crate::model::{Model, OutletId};
+
+    #[test]
+    fn split_negative_axis() {
+        let input = Tensor::from(Array3::<f32>::zeros((1, 2, 6)));
+        let outputs = Split::new(-1, 2, Some(vec![2, 4]))
+            .eval(tvec!(input.into()))
+            .unwrap();
+        assert_eq!(outputs[0].shape(), &[1, 2, 2]);
+        assert_eq!(outputs[1].shape(), &[1, 2, 4]);
+    }
+
+    #[test]
+    fn split_infers_every_output() {
+        let mut model = Model::default();
+        model
+            .add_source_fact("a", TensorFact::dt_shape(DatumType::F32, vec![6, 2]))
+            .unwrap();
+        let split = model
+            .chain("split", Box::new(Split::new(0, 3, None)))
+            .unwrap();
+        model
+            .add_const("k", arr1(&[1i32, 2, 3, 4, 5, 6]).into())
+            .unwrap();
+        let split_k = model
+            .chain("split_k", Box::new(Split::new(0, 3, None)))
+            .unwrap();
+        model.analyse().unwrap();
+        for i in 0..3 {
+            let fact = model.fact(OutletId::new(split, i)).unwrap();
+            assert_eq!(fact.shape, TensorFact::shape(vec![2, 2]).shape);
+            let value = model.fact(OutletId::new(split_k, i)).unwrap();
+            let expected: Tensor = arr1(&[2 * i as i32 + 1, 2 * i as i32 + 2]).into();
+            assert_eq!(value.value, SharedTensor::from(expected).into());
+        }
+    }
+}
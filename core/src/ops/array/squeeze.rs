@@ -4,12 +4,25 @@ use super::RmDims;
 
 #[derive(Debug, Clone, new, Default)]
 pub struct Squeeze {
-    axes: Option<Vec<usize>>,
+    axes: Option<Vec<i64>>,
 }
 
 impl Squeeze {
-    fn compute_shape<D: DimLike>(&self, input: &[D]) -> TractResult<TVec<D>> {
+    fn resolved_axes(&self, rank: usize) -> TractResult<Option<Vec<usize>>> {
         if let Some(ref axes) = self.axes {
+            let mut axes = axes
+                .iter()
+                .map(|&axis| normalize_axis(axis, rank))
+                .collect::<TractResult<Vec<_>>>()?;
+            axes.sort();
+            Ok(Some(axes))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn compute_shape<D: DimLike>(&self, input: &[D]) -> TractResult<TVec<D>> {
+        if let Some(axes) = self.resolved_axes(input.len())? {
             let mut shape: TVec<D> = input.iter().cloned().collect();
             for &axis in axes.iter().rev() {
                 if shape.remove(axis) != D::one() {
@@ -34,15 +47,21 @@ impl Op for Squeeze {
         "Squeeze".into()
     }
 
+    fn is_foldable(&self) -> bool {
+        true
+    }
+
     fn reduce(
         &self,
-        _inputs: TVec<&TensorFact>,
+        inputs: TVec<&TensorFact>,
         _outputs: TVec<&TensorFact>,
         phase: ReductionPhase,
     ) -> TractResult<Option<ReducedOpRewire>> {
         if phase == ReductionPhase::Normalize {
-            if let Some(dims) = &self.axes {
-                return Ok(Some(ReducedOpRewire::unary(RmDims::new(dims.clone()))));
+            if let Some(rank) = inputs[0].shape.rank() {
+                if let Some(dims) = self.resolved_axes(rank)? {
+                    return Ok(Some(ReducedOpRewire::unary(RmDims::new(dims))));
+                }
             }
         }
         Ok(None)
@@ -71,10 +90,88 @@ impl InferenceRulesOp for Squeeze {
                 &outputs[0].rank,
                 (&inputs[0].rank).bex() - axes.len() as i32,
             )?;
+            s.given(&inputs[0].rank, move |s, rank| {
+                let axes = self.resolved_axes(rank as usize)?.unwrap();
+                let mut kept = 0;
+                for axis in 0..rank as usize {
+                    if axes.contains(&axis) {
+                        s.given(&inputs[0].shape[axis], move |_, dim| {
+                            if dim != 1.to_dim() {
+                                bail!("Can not squeeze axis {} of dimension {:?}", axis, dim)
+                            }
+                            Ok(())
+                        })?;
+                        s.equals(&inputs[0].shape[axis], 1.to_dim())?;
+                    } else {
+                        s.equals(&outputs[0].shape[kept], &inputs[0].shape[axis])?;
+                        kept += 1;
+                    }
+                }
+                Ok(())
+            })
+        } else {
+            // Which axes go away is only known once every dimension is.
+            s.given(&inputs[0].shape, move |s, shape| {
+                if shape.iter().all(|d| d.to_integer().is_ok()) {
+                    let output_shape = self.compute_shape(&shape)?;
+                    s.equals(&outputs[0].shape, output_shape)?;
+                }
+                Ok(())
+            })
         }
-        s.given(&inputs[0].shape, move |s, shape| {
-            let output_shape = self.compute_shape(&shape)?;
-            s.equals(&outputs[0].shape, output_shape)
-        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::Array3;
+
+    #[test]
+    fn squeeze_negative_axis() {
+        let input = Tensor::from(Array3::<f32>::zeros((2, 3, 1)));
+        let outputs = Squeeze::new(Some(vec![-1]))
+            .eval(tvec!(input.into()))
+            .unwrap();
+        assert_eq!(outputs[0].shape(), &[2, 3]);
+    }
+
+    #[test]
+    fn squeeze_rules_explicit_axes() {
+        let op = Squeeze::new(Some(vec![0, -2]));
+        let ifact = TensorFact::dt_shape(DatumType::F32, shapefact!(_, S, _, 3));
+        let ofact = TensorFact::default();
+        let (inputs, outputs) = op.infer_facts(tvec!(&ifact), tvec!(&ofact)).unwrap();
+        assert_eq!(
+            inputs[0],
+            TensorFact::dt_shape(DatumType::F32, shapefact!(1, S, 1, 3))
+        );
+        assert_eq!(
+            outputs[0],
+            TensorFact::dt_shape(DatumType::F32, shapefact!(S, 3))
+        );
+    }
+
+    #[test]
+    fn squeeze_rules_non_unit_axis() {
+        let op = Squeeze::new(Some(vec![1]));
+        let ifact = TensorFact::dt_shape(DatumType::F32, shapefact!(1, 3));
+        let ofact = TensorFact::default();
+        assert!(op.infer_facts(tvec!(&ifact), tvec!(&ofact)).is_err());
+    }
+
+    #[test]
+    fn squeeze_rules_all_ones() {
+        let op = Squeeze::new(None);
+        let ofact = TensorFact::default();
+        let ifact = TensorFact::dt_shape(DatumType::F32, shapefact!(1, S, 1, 3));
+        let (_, outputs) = op.infer_facts(tvec!(&ifact), tvec!(&ofact)).unwrap();
+        assert_eq!(outputs[0], TensorFact::dt(DatumType::F32));
+        let ifact = TensorFact::dt_shape(DatumType::F32, shapefact!(1, 2, 1, 3));
+        let (_, outputs) = op.infer_facts(tvec!(&ifact), tvec!(&ofact)).unwrap();
+        assert_eq!(
+            outputs[0],
+            TensorFact::dt_shape(DatumType::F32, shapefact!(2, 3))
+        );
     }
 }
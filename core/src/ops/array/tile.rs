@@ -0,0 +1,141 @@
+use ndarray::*;
+
+use crate::ops::prelude::*;
+
+#[derive(Debug, Clone, new, Default)]
+pub struct Tile;
+
+impl Tile {
+    /// Reads the repeat counts, one per axis of a tensor of rank `rank`.
+    fn repeats(repeats: &Tensor, rank: usize) -> TractResult<TVec<usize>> {
+        let repeats = repeats.cast_to::<i64>()?;
+        let repeats = repeats.to_array_view::<i64>()?;
+        if repeats.len() != rank {
+            bail!(
+                "Tile expects one repeat count per axis, got {:?} for rank {}",
+                repeats,
+                rank
+            )
+        }
+        if let Some(r) = repeats.iter().find(|&&r| r < 0) {
+            bail!("Tile repeat counts can not be negative, got {}", r)
+        }
+        Ok(repeats.iter().map(|&r| r as usize).collect())
+    }
+
+    /// Evaluates the operation given the input tensors.
+    fn eval_t<T: Datum>(
+        &self,
+        input: SharedTensor,
+        repeats: &[usize],
+    ) -> TractResult<TVec<SharedTensor>> {
+        let mut result = input.to_array::<T>()?;
+        for (axis, &r) in repeats.iter().enumerate() {
+            if r != 1 {
+                let views = vec![result.view(); r];
+                result = ::ndarray::stack(Axis(axis), &*views)?;
+            }
+        }
+        Ok(tvec![result.into()])
+    }
+}
+
+impl Op for Tile {
+    fn name(&self) -> Cow<str> {
+        "Tile".into()
+    }
+
+    fn const_inputs(&self) -> TVec<usize> {
+        tvec!(1)
+    }
+}
+
+impl StatelessOp for Tile {
+    fn eval(&self, mut inputs: TVec<SharedTensor>) -> TractResult<TVec<SharedTensor>> {
+        let (input, repeats) = args_2!(inputs);
+        let repeats = Self::repeats(&repeats, input.shape().len())?;
+        dispatch_datum!(Self::eval_t(input.datum_type())(self, input, &repeats))
+    }
+}
+
+impl InferenceRulesOp for Tile {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p SharedTensorsProxy,
+        outputs: &'p SharedTensorsProxy,
+    ) -> InferenceResult {
+        s.equals(&inputs.len, 2)?;
+        s.equals(&outputs.len, 1)?;
+        s.equals(&outputs[0].datum_type, &inputs[0].datum_type)?;
+        s.equals(&outputs[0].rank, &inputs[0].rank)?;
+        s.equals(&inputs[1].rank, 1)?;
+        s.given_2(
+            &inputs[0].shape,
+            &inputs[1].value,
+            move |s, shape, repeats| {
+                let repeats = Self::repeats(&repeats, shape.len())?;
+                let shape: TVec<TDim> = shape
+                    .iter()
+                    .zip(repeats.iter())
+                    .map(|(&d, &r)| d * r as i32)
+                    .collect();
+                s.equals(&outputs[0].shape, ShapeFact::from(shape))
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tile_2d() {
+        let data: SharedTensor = arr2(&[[1i32, 2], [3, 4]]).into();
+        let repeats: SharedTensor = arr1(&[2i64, 3]).into();
+        let result = Tile::new().eval(tvec!(data, repeats)).unwrap();
+        assert_eq!(
+            result[0].to_array_view::<i32>().unwrap(),
+            arr2(&[
+                [1, 2, 1, 2, 1, 2],
+                [3, 4, 3, 4, 3, 4],
+                [1, 2, 1, 2, 1, 2],
+                [3, 4, 3, 4, 3, 4]
+            ])
+            .into_dyn()
+        );
+    }
+
+    #[test]
+    fn tile_rejects_invalid_repeats() {
+        let data: SharedTensor = arr2(&[[1i32, 2], [3, 4]]).into();
+        let negative: SharedTensor = arr1(&[2i64, -1]).into();
+        assert!(Tile::new().eval(tvec!(data.clone(), negative)).is_err());
+        let short: SharedTensor = arr1(&[2i64]).into();
+        assert!(Tile::new().eval(tvec!(data, short)).is_err());
+
+        let data = TensorFact::dt_shape(DatumType::F32, vec![2, 3]);
+        let output = TensorFact::default();
+        for repeats in &[arr1(&[1i64, -2]), arr1(&[1i64, 2, 3])] {
+            let repeats: TensorFact = SharedTensor::from(repeats.clone()).into();
+            assert!(Tile::new()
+                .infer_facts(tvec!(&data, &repeats), tvec!(&output))
+                .is_err());
+        }
+    }
+
+    #[test]
+    fn tile_inference() {
+        let data = TensorFact::dt_shape(DatumType::F32, vec![TDim::s(), 3.to_dim()]);
+        let repeats: TensorFact = SharedTensor::from(arr1(&[1i64, 2])).into();
+        let output = TensorFact::default();
+        let (_, outputs) = Tile::new()
+            .infer(tvec!(&data, &repeats), tvec!(&output))
+            .unwrap();
+        assert_eq!(
+            outputs[0],
+            TensorFact::dt_shape(DatumType::F32, vec![TDim::s(), 6.to_dim()])
+        );
+    }
+}
@@ -41,3 +41,37 @@ impl InferenceRulesOp for Cast {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::arr1;
+
+    fn cast(to: DatumType, input: Tensor) -> Tensor {
+        Cast::new(to)
+            .eval(tvec!(input.into()))
+            .unwrap()
+            .remove(0)
+            .to_tensor()
+    }
+
+    #[test]
+    fn float_to_int_truncates_toward_zero() {
+        let input = Tensor::from(arr1(&[1.7f32, -1.7, 0.2]));
+        assert_eq!(
+            cast(DatumType::I32, input),
+            Tensor::from(arr1(&[1i32, -1, 0]))
+        );
+    }
+
+    #[test]
+    fn float_to_int_saturates() {
+        let input = Tensor::from(arr1(&[1e30f32, -1e30, std::f32::NAN]));
+        assert_eq!(
+            cast(DatumType::I32, input),
+            Tensor::from(arr1(&[std::i32::MAX, std::i32::MIN, 0]))
+        );
+        let input = Tensor::from(arr1(&[-3.0f32, 300.0]));
+        assert_eq!(cast(DatumType::U8, input), Tensor::from(arr1(&[0u8, 255])));
+    }
+}
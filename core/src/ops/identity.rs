@@ -25,8 +25,23 @@ impl InferenceRulesOp for Identity {
     ) -> InferenceResult {
         s.equals(&inputs.len, 1)?;
         s.equals(&outputs.len, 1)?;
-        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
-        s.equals(&inputs[0].shape, &outputs[0].shape)?;
-        Ok(())
+        s.equals_fact(&inputs[0], &outputs[0])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ops::InferenceOp;
+    use ndarray::arr1;
+
+    #[test]
+    fn identity_propagates_whole_fact() {
+        let known = TensorFact::from(Tensor::from(arr1(&[1.0f32, 2.0])));
+        let any = TensorFact::default();
+        let (_, outputs) = Identity.infer_facts(tvec!(&known), tvec!(&any)).unwrap();
+        assert_eq!(outputs[0], known);
+        let (inputs, _) = Identity.infer_facts(tvec!(&any), tvec!(&known)).unwrap();
+        assert_eq!(inputs[0], known);
     }
 }
@@ -9,3 +9,118 @@ element_bin!(Xor, [bool] { |a, b| a ^ b});
 element_bin!(Equals, [bool, u8, i8, i16, i32, i64, f32, f64, TDim] => bool { |a,b| a==b });
 element_bin!(Lesser, [u8, i8, i16, i32, i64, f32, f64] => bool { |a,b| a<b });
 element_bin!(Greater, [u8, i8, i16, i32, i64, f32, f64] => bool { |a,b| a>b });
+
+/// Picks elements from `x` where `condition` is true, and from `y`
+/// elsewhere, broadcasting the three inputs together.
+#[derive(Debug, Clone, new, Default)]
+pub struct Where;
+
+impl Where {
+    fn eval_t<T: Datum>(
+        &self,
+        cond: &SharedTensor,
+        x: &SharedTensor,
+        y: &SharedTensor,
+    ) -> TractResult<SharedTensor> {
+        let shape: TVec<usize> =
+            crate::broadcast::multi_broadcast(&[cond.shape(), x.shape(), y.shape()]).ok_or_else(
+                || {
+                    format!(
+                        "Incompatible shapes {:?}, {:?} and {:?}",
+                        cond.shape(),
+                        x.shape(),
+                        y.shape()
+                    )
+                },
+            )?;
+        let mut result = ::ndarray::ArrayD::<T>::default(&*shape);
+        ::ndarray::Zip::from(&mut result)
+            .and_broadcast(&cond.to_array_view::<bool>()?)
+            .and_broadcast(&x.to_array_view::<T>()?)
+            .and_broadcast(&y.to_array_view::<T>()?)
+            .apply(|r, &c, x, y| *r = if c { x.clone() } else { y.clone() });
+        Ok(result.into())
+    }
+}
+
+impl Op for Where {
+    fn name(&self) -> Cow<str> {
+        "Where".into()
+    }
+}
+
+impl StatelessOp for Where {
+    fn eval(&self, mut inputs: TVec<SharedTensor>) -> TractResult<TVec<SharedTensor>> {
+        let (cond, x, y) = args_3!(inputs);
+        if x.datum_type() != y.datum_type() {
+            bail!(
+                "Where branches have different types: {:?} and {:?}",
+                x.datum_type(),
+                y.datum_type()
+            )
+        }
+        Ok(tvec!(dispatch_datum!(Self::eval_t(x.datum_type())(
+            self, &cond, &x, &y
+        ))?))
+    }
+}
+
+impl InferenceRulesOp for Where {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p SharedTensorsProxy,
+        outputs: &'p SharedTensorsProxy,
+    ) -> InferenceResult {
+        s.equals(&inputs.len, 3)?;
+        s.equals(&outputs.len, 1)?;
+        s.equals(&inputs[0].datum_type, DatumType::Bool)?;
+        s.equals(&inputs[1].datum_type, &inputs[2].datum_type)?;
+        s.equals(&outputs[0].datum_type, &inputs[1].datum_type)?;
+        s.with(&inputs[0].shape, move |s, cond| {
+            s.with(&inputs[1].shape, move |s, x| {
+                let cond = cond.clone();
+                s.with(&inputs[2].shape, move |s, y| {
+                    if let Ok(Some(shape)) =
+                        crate::analyser::helpers::infer_shape_broadcasting(&[&cond, &x, &y])
+                    {
+                        s.equals(&outputs[0].shape, shape)?;
+                    }
+                    Ok(())
+                })
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::arr2;
+
+    #[test]
+    fn where_broadcasts_condition() {
+        let cond = arr2(&[[true], [false], [true]]);
+        let x = arr2(&[[1i32, 2, 3, 4], [5, 6, 7, 8], [9, 10, 11, 12]]);
+        let y = arr2(&[[-1i32, -2, -3, -4], [-5, -6, -7, -8], [-9, -10, -11, -12]]);
+        let result = Where
+            .eval(tvec!(cond.into(), x.into(), y.into()))
+            .unwrap()
+            .remove(0);
+        assert_eq!(
+            result.to_array_view::<i32>().unwrap(),
+            arr2(&[[1, 2, 3, 4], [-5, -6, -7, -8], [9, 10, 11, 12]]).into_dyn()
+        );
+    }
+
+    #[test]
+    fn where_infers_broadcast_shape() {
+        let cond = TensorFact::dt_shape(DatumType::Bool, vec![3, 1]);
+        let x = TensorFact::dt_shape(DatumType::F32, vec![3, 4]);
+        let y = TensorFact::shape(vec![4]);
+        let output = TensorFact::default();
+        let (inputs, outputs) = Where.infer(tvec!(&cond, &x, &y), tvec!(&output)).unwrap();
+        assert_eq!(inputs[2].datum_type, TypeFact::Only(DatumType::F32));
+        assert_eq!(outputs[0], TensorFact::dt_shape(DatumType::F32, vec![3, 4]));
+    }
+}
@@ -27,6 +27,10 @@ macro_rules! element_map {
                 stringify!($Name).into()
             }
 
+            fn cost(&self, inputs: TVec<&TensorFact>) -> OpCost {
+                inputs.get(0).map(|i| OpCost::element_wise(i)).unwrap_or_default()
+            }
+
             fn pulsify( &self, inputs: TVec<&PulsedTensorFact>,) -> TractResult<Vec<PulsifiedOp>> {
                 Ok(vec!(PulsifiedOp::new(Box::new(self.clone()), tvec!(inputs[0].clone()))))
             }
@@ -78,6 +82,10 @@ macro_rules! element_map_with_params {
             fn name(&self) -> Cow<str> {
                 stringify!($Name).into()
             }
+
+            fn cost(&self, inputs: TVec<&TensorFact>) -> OpCost {
+                inputs.get(0).map(|i| OpCost::element_wise(i)).unwrap_or_default()
+            }
         }
 
         impl InferenceRulesOp for $Name {
@@ -205,14 +213,7 @@ macro_rules! element_bin {
                     )?;
                     s.equals(&inputs.len, 2)?;
                     s.equals(&outputs.len, 1)?;
-                    s.with(&a.shape, move |s, a_shape| {
-                        s.with(&b.shape, move |s, b_shape| {
-                            if let Ok(Some(c_shape)) = $crate::analyser::helpers::infer_shape_broadcasting(&[&a_shape, &b_shape]) {
-                                s.equals(&c.shape, c_shape)?;
-                            }
-                            Ok(())
-                        })
-                    })
+                    $crate::analyser::helpers::rules_for_binary_broadcast(s, a, b, c)
                 }
             }
 
@@ -355,21 +356,13 @@ macro_rules! element_nary {
                 }
                 s.equals(&outputs.len, 1)?;
                 s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
-                s.equals(&inputs[0].rank, &outputs[0].rank)?;
                 s.given(&inputs.len, move |s, n| {
                     let n = n as usize;
                     s.equals_all((0..n).map(|i| (&inputs[i].datum_type).bex()).collect())?;
-                    s.equals_all((0..n).map(|i| inputs[i].rank.bex()).collect())?;
-                    s.given(&inputs[0].rank, move |s, rank: i32| {
-                        for dim in 0..(rank as usize) {
-                            s.equals(&inputs[0].shape[dim], &outputs[0].shape[dim])?;
-                            s.equals_all(
-                                (0..n as usize)
-                                    .map(|i| inputs[i].shape[dim].bex())
-                                    .collect(),
-                            )?;
-                        }
-                        Ok(())
+                    s.given_all((0..n).map(|i| &inputs[i].shape), move |s, shapes| {
+                        let shape = $crate::broadcast::multi_broadcast(&shapes)
+                            .ok_or("Could not broadcast the input shapes together")?;
+                        s.equals(&outputs[0].shape, ShapeFact::from(shape))
                     })
                 })
             }
@@ -499,6 +492,9 @@ macro_rules! assert_backward {
     };
 }
 
+/// Calls a function generic over `Datum` with the type matching a
+/// `DatumType`, bool and TDim included. String is not a `Datum`, so it
+/// results in an error.
 #[macro_export]
 macro_rules! dispatch_datum {
     ($($path:ident)::* ($dt:expr) ($($args:expr),*)) => {
@@ -514,11 +510,12 @@ macro_rules! dispatch_datum {
             DatumType::F32  => $($path)::*::<f32>($($args),*),
             DatumType::F64  => $($path)::*::<f64>($($args),*),
             DatumType::TDim => $($path)::*::<TDim>($($args),*),
-            DatumType::String => bail!("String is not a Datum")
+            DatumType::String => bail!("Unsupported datum type String")
         }
     }
 }
 
+/// Like `dispatch_datum!`, restricted to numeric types.
 #[macro_export]
 macro_rules! dispatch_numbers {
     ($($path:ident)::* ($dt:expr) ($($args:expr),*)) => {
@@ -541,7 +538,7 @@ macro_rules! dispatch_numbers {
 macro_rules! dispatch_floatlike {
     ($($path:ident)::* ($dt:expr) ($($args:expr),*)) => {
         match $dt {
-            DatumType::F16  => $($path)::*::<f32>($($args),*),
+            DatumType::F16  => bail!("Unsupported datum type F16"),
             DatumType::F32  => $($path)::*::<f32>($($args),*),
             DatumType::F64  => $($path)::*::<f64>($($args),*),
             _ => bail!("{:?} is not float-like", $dt)
@@ -559,7 +556,7 @@ macro_rules! impl_op_same_as {
                 false
             }
         }
-    }
+    };
 }
 
 #[macro_export]
@@ -70,6 +70,16 @@ fn infer_shapes<D: DimLike>(
     while bshape.len() < ashape.len() {
         bshape.insert(0, D::one());
     }
+    let (k_a, k_b) = (ashape[ashape.len() - 1], bshape[bshape.len() - 2]);
+    if let (Ok(k_a), Ok(k_b)) = (k_a.to_integer(), k_b.to_integer()) {
+        if k_a != k_b {
+            bail!(
+                "Can not multiply matrices with mismatching inner dimensions {} and {}",
+                k_a,
+                k_b
+            )
+        }
+    }
     let cshape_prefix = crate::broadcast::multi_broadcast(&[
         &ashape[..(ashape.len() - 2)],
         &bshape[..(bshape.len() - 2)],
@@ -186,6 +196,17 @@ impl InferenceRulesOp for MatMul {
         s.equals(&outputs.len, 1)?;
         s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
         s.equals(&inputs[1].datum_type, &outputs[0].datum_type)?;
+        s.given_2(&inputs[0].rank, &inputs[1].rank, move |s, ra, rb| {
+            if ra >= 2 && rb >= 2 {
+                let (ra, rb) = (ra as usize, rb as usize);
+                let rc = ra.max(rb);
+                s.equals(&outputs[0].rank, rc as i32)?;
+                s.equals(&inputs[0].shape[ra - 1], &inputs[1].shape[rb - 2])?;
+                s.equals(&outputs[0].shape[rc - 2], &inputs[0].shape[ra - 2])?;
+                s.equals(&outputs[0].shape[rc - 1], &inputs[1].shape[rb - 1])?;
+            }
+            Ok(())
+        })?;
         s.given_2(
             &inputs[0].shape,
             &inputs[1].shape,
@@ -341,18 +362,10 @@ impl<T: Datum + Add + Mul + Zero> StatelessOp for MatMulUnaryImplASimpleB<T> {
 
         let mut c = unsafe { Array::uninitialized(&*self.c_shape) };
 
-        let mut pa = unsafe {
-            Tensor::uninitialized_aligned::<T>(
-                &[self.geo.mm.packed_a_len()],
-                self.geo.mm.packed_a_alignment(),
-            )?
-        };
-
-        self.geo
-            .mm
-            .pack_a(pa.as_ptr_mut()?, a.as_ptr(), self.geo.k as isize, 1);
-        self.geo.mm.mat_mul_prepacked(
-            pa.as_ptr()?,
+        self.geo.mm.mat_mul_with_prepacked_b(
+            a.as_ptr(),
+            self.geo.k as isize,
+            1,
             self.packed_b.as_ptr()?,
             c.as_mut_ptr(),
             self.geo.n as isize,
@@ -528,3 +541,77 @@ impl InferenceRulesOp for MatMulUnaryB {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tensor(shape: &[usize]) -> Tensor {
+        Tensor::from_shape_and_fn(DatumType::F32, shape, |ix| {
+            ix.iter().fold(0, |acc, &i| acc * 7 + i) as f64 % 5.0 - 2.0
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn mat_mul_broadcasts_2d_b() {
+        let (a, b) = (tensor(&[2, 3, 4]), tensor(&[4, 5]));
+        let c = MatMul::default()
+            .eval(tvec!(a.clone().into(), b.clone().into()))
+            .unwrap();
+        let (a, b) = (
+            a.into_array::<f32>().unwrap(),
+            b.into_array::<f32>().unwrap(),
+        );
+        let expected = ArrayD::from_shape_fn(vec![2, 3, 5], |ix| {
+            (0..4)
+                .map(|k| a[[ix[0], ix[1], k]] * b[[k, ix[2]]])
+                .sum::<f32>()
+        });
+        assert_eq!(c[0], expected.into());
+    }
+
+    #[test]
+    fn mat_mul_broadcasts_batch_dims() {
+        let (a, b) = (tensor(&[2, 1, 3, 4]), tensor(&[1, 5, 4, 6]));
+        let c = MatMul::default()
+            .eval(tvec!(a.clone().into(), b.clone().into()))
+            .unwrap();
+        let (a, b) = (
+            a.into_array::<f32>().unwrap(),
+            b.into_array::<f32>().unwrap(),
+        );
+        let expected = ArrayD::from_shape_fn(vec![2, 5, 3, 6], |ix| {
+            (0..4)
+                .map(|k| a[[ix[0], 0, ix[2], k]] * b[[0, ix[1], k, ix[3]]])
+                .sum::<f32>()
+        });
+        assert_eq!(c[0], expected.into());
+    }
+
+    #[test]
+    fn mat_mul_rules() {
+        let op = MatMul::default();
+        let a = TensorFact::dt_shape(DatumType::F32, shapefact!(S, 3, _));
+        let b = TensorFact::dt_shape(DatumType::F32, shapefact!(4, 5));
+        let any = TensorFact::default();
+        let (inputs, outputs) = op.infer_facts(tvec!(&a, &b), tvec!(&any)).unwrap();
+        assert_eq!(
+            inputs[0],
+            TensorFact::dt_shape(DatumType::F32, shapefact!(S, 3, 4))
+        );
+        assert_eq!(
+            outputs[0],
+            TensorFact::dt_shape(DatumType::F32, shapefact!(S, 3, 5))
+        );
+        let a = TensorFact::dt_shape(DatumType::F32, shapefact!(2, 1, 3, 4));
+        let b = TensorFact::dt_shape(DatumType::F32, shapefact!(1, 5, 4, 6));
+        let (_, outputs) = op.infer_facts(tvec!(&a, &b), tvec!(&any)).unwrap();
+        assert_eq!(
+            outputs[0],
+            TensorFact::dt_shape(DatumType::F32, shapefact!(2, 5, 3, 6))
+        );
+        let b = TensorFact::dt_shape(DatumType::F32, shapefact!(3, 6));
+        assert!(op.infer_facts(tvec!(&a, &b), tvec!(&any)).is_err());
+    }
+}
@@ -1,8 +1,10 @@
 pub mod gemm;
 pub mod mat_mul;
+pub mod unary;
 
 pub use self::gemm::Gemm;
 pub use self::mat_mul::MatMul;
+pub use self::unary::{UnaryElementwise, UnaryFunction};
 use crate::ops::prelude::*;
 use num_traits::AsPrimitive;
 use num_traits::Float;
@@ -92,4 +94,106 @@ mod tests {
         let b = arr2(&[[1., 0.], [0., 0.]]);
         assert_eq!(a.dot(&b), arr2(&[[1., 0.], [3., 0.]]));
     }
+
+    #[test]
+    fn f16_analyses_but_does_not_eval_in_mat_mul() {
+        use crate::analyser::types::Fact;
+        use crate::ops::prelude::*;
+        use tract_linalg::f16::f16;
+        let dt = TypeFact::from(DatumType::F16);
+        assert_eq!(dt.unify(&TypeFact::default()).unwrap(), dt);
+        assert!(dt.unify(&TypeFact::from(DatumType::F32)).is_err());
+        let a: SharedTensor = arr2(&[[f16::from(1.0f32), f16::from(2.0f32)]]).into();
+        let b: SharedTensor = arr2(&[[f16::from(1.0f32)], [f16::from(0.5f32)]]).into();
+        let err = super::MatMul::default().eval(tvec!(a, b)).unwrap_err();
+        assert!(format!("{}", err).contains("Unsupported datum type F16"));
+    }
+
+    #[test]
+    fn sum_broadcasts_three_inputs() {
+        use crate::ops::prelude::*;
+        use ndarray::arr1;
+        let op = super::AddN::default();
+        let a = TensorFact::dt_shape(DatumType::F32, vec![1]);
+        let b = TensorFact::dt_shape(DatumType::F32, vec![3]);
+        let c = TensorFact::dt_shape(DatumType::F32, vec![3]);
+        let output = TensorFact::default();
+        let (_, outputs) = op.infer(tvec!(&a, &b, &c), tvec!(&output)).unwrap();
+        assert_eq!(outputs[0], TensorFact::dt_shape(DatumType::F32, vec![3]));
+
+        let a: SharedTensor = arr1(&[1.0f32]).into();
+        let b: SharedTensor = arr1(&[1.0f32, 2.0, 3.0]).into();
+        let c: SharedTensor = arr1(&[10.0f32, 20.0, 30.0]).into();
+        let result = op.eval(tvec!(a, b, c)).unwrap();
+        assert_eq!(result[0], SharedTensor::from(arr1(&[12.0f32, 23.0, 34.0])));
+    }
+
+    #[test]
+    fn add_broadcasts_lower_rank_input() {
+        use crate::ops::prelude::*;
+        let a = TensorFact::dt_shape(DatumType::F32, vec![4]);
+        let b = TensorFact::dt_shape(DatumType::F32, vec![3, 4]);
+        let c = TensorFact::default();
+        let (_, outputs) = super::Add::default()
+            .infer(tvec!(&a, &b), tvec!(&c))
+            .unwrap();
+        assert_eq!(outputs[0], TensorFact::dt_shape(DatumType::F32, vec![3, 4]));
+    }
+
+    #[test]
+    fn add_broadcasts_with_unknown_dims() {
+        use crate::ops::prelude::*;
+        let a = TensorFact::dt_shape(DatumType::F32, shapefact![_]);
+        let b = TensorFact::dt_shape(DatumType::F32, vec![3, 4]);
+        let c = TensorFact::default();
+        let (_, outputs) = super::Add::default()
+            .infer(tvec!(&a, &b), tvec!(&c))
+            .unwrap();
+        assert_eq!(outputs[0], TensorFact::dt_shape(DatumType::F32, vec![3, 4]));
+
+        let a = TensorFact::dt_shape(DatumType::F32, vec![1, 4]);
+        let b = TensorFact::dt_shape(DatumType::F32, shapefact![_, 1]);
+        let (_, outputs) = super::Add::default()
+            .infer(tvec!(&a, &b), tvec!(&c))
+            .unwrap();
+        assert_eq!(
+            outputs[0],
+            TensorFact::dt_shape(DatumType::F32, shapefact![_, 4])
+        );
+    }
+
+    #[test]
+    fn add_binds_symbols_through_broadcast() {
+        use crate::ops::prelude::*;
+        let a = TensorFact::dt_shape(DatumType::F32, vec![TDim::sym('N'), 3.to_dim()]);
+        let b = TensorFact::dt_shape(DatumType::F32, vec![3]);
+        let c = TensorFact::shape(vec![2, 3]);
+        let (inputs, _) = super::Add::default()
+            .infer(tvec!(&a, &b), tvec!(&c))
+            .unwrap();
+        assert_eq!(inputs[0], TensorFact::dt_shape(DatumType::F32, vec![2, 3]));
+    }
+
+    #[test]
+    fn add_rejects_incompatible_broadcast() {
+        use crate::ops::prelude::*;
+        let a = TensorFact::dt_shape(DatumType::F32, vec![3]);
+        let b = TensorFact::dt_shape(DatumType::F32, vec![3, 4]);
+        let c = TensorFact::default();
+        assert!(super::Add::default()
+            .infer(tvec!(&a, &b), tvec!(&c))
+            .is_err());
+    }
+
+    #[test]
+    fn add_rejects_broadcasting_symbol_with_constant() {
+        use crate::ops::prelude::*;
+        let a = TensorFact::dt_shape(DatumType::F32, vec![TDim::sym('N')]);
+        let b = TensorFact::dt_shape(DatumType::F32, vec![3]);
+        let c = TensorFact::default();
+        let err = super::Add::default()
+            .infer(tvec!(&a, &b), tvec!(&c))
+            .unwrap_err();
+        assert!(format!("{}", err).contains("symbolic"), "{}", err);
+    }
 }
@@ -0,0 +1,175 @@
+use crate::ops::prelude::*;
+use num_traits::{Float, Signed};
+
+/// A scalar function applied element-wise by `UnaryElementwise`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UnaryFunction {
+    Abs,
+    Exp,
+    Ln,
+    Neg,
+    Recip,
+    Sigmoid,
+    Sqrt,
+    Tanh,
+}
+
+impl UnaryFunction {
+    fn eval_float<T: Float>(&self, x: T) -> T {
+        match self {
+            UnaryFunction::Abs => x.abs(),
+            UnaryFunction::Exp => x.exp(),
+            UnaryFunction::Ln => x.ln(),
+            UnaryFunction::Neg => -x,
+            UnaryFunction::Recip => x.recip(),
+            UnaryFunction::Sigmoid => ((-x).exp() + T::one()).recip(),
+            UnaryFunction::Sqrt => x.sqrt(),
+            UnaryFunction::Tanh => x.tanh(),
+        }
+    }
+
+    fn eval_signed<T: Signed>(&self, x: T) -> Option<T> {
+        match self {
+            UnaryFunction::Abs => Some(x.abs()),
+            UnaryFunction::Neg => Some(-x),
+            _ => None,
+        }
+    }
+}
+
+/// An element-wise op applying a `UnaryFunction`, preserving the type and
+/// shape of its input.
+///
+/// Floats support all the functions, signed integers only `Abs` and `Neg`,
+/// and `TDim` only `Neg`.
+#[derive(Debug, Clone, new)]
+pub struct UnaryElementwise {
+    pub function: UnaryFunction,
+}
+
+impl UnaryElementwise {
+    fn eval_t<T: Datum + Float>(&self, a: SharedTensor) -> TractResult<SharedTensor> {
+        let mut a = a.to_array::<T>()?;
+        a.mapv_inplace(|x| self.function.eval_float(x));
+        Ok(a.into())
+    }
+
+    fn eval_signed_t<T: Datum + Signed>(&self, a: SharedTensor) -> TractResult<SharedTensor> {
+        let mut a = a.to_array::<T>()?;
+        if self.function.eval_signed(T::zero()).is_none() {
+            bail!("{:?} not covering {:?}", self.function, T::datum_type())
+        }
+        a.mapv_inplace(|x| self.function.eval_signed(x).unwrap());
+        Ok(a.into())
+    }
+}
+
+impl Op for UnaryElementwise {
+    fn name(&self) -> Cow<str> {
+        format!("{:?}", self.function).into()
+    }
+
+    fn pulsify(&self, inputs: TVec<&PulsedTensorFact>) -> TractResult<Vec<PulsifiedOp>> {
+        Ok(vec![PulsifiedOp::new(
+            Box::new(self.clone()),
+            tvec!(inputs[0].clone()),
+        )])
+    }
+}
+
+impl StatelessOp for UnaryElementwise {
+    fn eval(&self, mut inputs: TVec<SharedTensor>) -> TractResult<TVec<SharedTensor>> {
+        let a = args_1!(inputs);
+        let output = match a.datum_type() {
+            DatumType::F16 => self.eval_t::<f16>(a)?,
+            DatumType::F32 => self.eval_t::<f32>(a)?,
+            DatumType::F64 => self.eval_t::<f64>(a)?,
+            DatumType::I8 => self.eval_signed_t::<i8>(a)?,
+            DatumType::I16 => self.eval_signed_t::<i16>(a)?,
+            DatumType::I32 => self.eval_signed_t::<i32>(a)?,
+            DatumType::I64 => self.eval_signed_t::<i64>(a)?,
+            DatumType::TDim if self.function == UnaryFunction::Neg => {
+                let mut a = a.to_array::<TDim>()?;
+                a.mapv_inplace(|x| -x);
+                a.into()
+            }
+            dt => bail!("{:?} not covering {:?}", self.function, dt),
+        };
+        Ok(tvec!(output))
+    }
+}
+
+impl InferenceRulesOp for UnaryElementwise {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p SharedTensorsProxy,
+        outputs: &'p SharedTensorsProxy,
+    ) -> InferenceResult {
+        s.equals(&inputs.len, 1)?;
+        s.equals(&outputs.len, 1)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[0].shape, &outputs[0].shape)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::arr1;
+
+    fn run(function: UnaryFunction, input: &[f32]) -> SharedTensor {
+        let op = UnaryElementwise::new(function);
+        let mut outputs = op.eval(tvec!(arr1(input).into())).unwrap();
+        outputs.remove(0)
+    }
+
+    #[test]
+    fn functions() {
+        let cases: &[(UnaryFunction, &[f32], &[f32])] = &[
+            (UnaryFunction::Abs, &[-1.0, 0.0, 2.0], &[1.0, 0.0, 2.0]),
+            (
+                UnaryFunction::Exp,
+                &[0.0, 1.0],
+                &[1.0, ::std::f32::consts::E],
+            ),
+            (
+                UnaryFunction::Ln,
+                &[1.0, ::std::f32::consts::E],
+                &[0.0, 1.0],
+            ),
+            (UnaryFunction::Neg, &[-1.0, 0.0, 2.0], &[1.0, 0.0, -2.0]),
+            (UnaryFunction::Recip, &[0.5, 4.0], &[2.0, 0.25]),
+            (UnaryFunction::Sigmoid, &[0.0, 3.0f32.ln()], &[0.5, 0.75]),
+            (UnaryFunction::Sqrt, &[4.0, 0.25], &[2.0, 0.5]),
+            (UnaryFunction::Tanh, &[0.0, 0.5f32.atanh()], &[0.0, 0.5]),
+        ];
+        for (function, input, expected) in cases {
+            let found = run(*function, input);
+            assert!(
+                found.approx_eq(&Tensor::from(arr1(expected)), 1e-6),
+                "{:?}: {:?}",
+                function,
+                found
+            );
+        }
+    }
+
+    #[test]
+    fn integers() {
+        let op = UnaryElementwise::new(UnaryFunction::Abs);
+        let outputs = op.eval(tvec!(arr1(&[-3i32, 2]).into())).unwrap();
+        assert_eq!(*outputs[0], Tensor::from(arr1(&[3i32, 2])));
+        let op = UnaryElementwise::new(UnaryFunction::Exp);
+        assert!(op.eval(tvec!(arr1(&[1i32]).into())).is_err());
+    }
+
+    #[test]
+    fn pass_through_facts() {
+        let op = UnaryElementwise::new(UnaryFunction::Sqrt);
+        let input = TensorFact::dt_shape(DatumType::F32, shapefact![2, 3]);
+        let any = TensorFact::default();
+        let (_, outputs) = op.infer_facts(tvec!(&input), tvec!(&any)).unwrap();
+        assert_eq!(outputs[0], input);
+    }
+}
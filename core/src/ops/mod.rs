@@ -36,8 +36,8 @@ pub enum ReductionPhase {
 
 pub mod prelude {
     pub use super::{
-        InferenceOp, Op, OpState, ReducedOpRewire, ReductionPhase, StatefullOp, StatelessOp,
-        StreamInfo,
+        normalize_axis, InferenceOp, Op, OpCost, OpState, ReducedOpRewire, ReductionPhase,
+        StatefullOp, StatelessOp, StreamInfo,
     };
     pub use crate::analyser::rules::expr::{IntoExp, ToDimExp};
     pub use crate::analyser::rules::{
@@ -60,6 +60,47 @@ pub mod prelude {
 
 use self::prelude::*;
 
+/// Maps an axis in `-rank..rank` to its position in `0..rank`, negative axes
+/// counting from the last one.
+pub fn normalize_axis(axis: i64, rank: usize) -> TractResult<usize> {
+    if 0 <= axis && axis < rank as i64 {
+        Ok(axis as usize)
+    } else if -(rank as i64) <= axis && axis < 0 {
+        Ok((axis + rank as i64) as usize)
+    } else {
+        bail!("Invalid axis {} for rank {} tensor", axis, rank)
+    }
+}
+
+/// Estimated cost of evaluating an operator, used to order the plan.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct OpCost {
+    /// Arithmetic operations performed.
+    pub flops: u64,
+    /// Size of the outputs, in bytes.
+    pub out_bytes: u64,
+}
+
+impl OpCost {
+    /// Cost of an operator doing one operation per element of a tensor
+    /// shaped like `fact`, and producing a tensor of the same size. Zero if
+    /// the fact is not fully determined.
+    pub fn element_wise(fact: &TensorFact) -> OpCost {
+        let len = match fact.shape.as_concrete_finite() {
+            Ok(Some(shape)) => shape.iter().product::<usize>() as u64,
+            _ => return OpCost::default(),
+        };
+        let size = match fact.datum_type.concretize() {
+            Some(dt) => dt.size_of() as u64,
+            None => return OpCost::default(),
+        };
+        OpCost {
+            flops: len,
+            out_bytes: len * size,
+        }
+    }
+}
+
 pub trait OpState: Debug + Send + objekt::Clone {
     fn eval(&mut self, op: &Op, inputs: TVec<SharedTensor>) -> TractResult<TVec<SharedTensor>>;
 }
@@ -112,14 +153,36 @@ pub trait Op:
                     .iter()
                     .map(|i| i.value.concretize().unwrap().clone().into())
                     .collect(); // checked
-                let output_value = stateless.eval(input_values)?.pop().unwrap();
-                return Ok((infered_inputs, tvec![output_value.into(),]));
+                let output_values = stateless.eval(input_values)?;
+                if output_values.len() != infered_outputs.len() {
+                    bail!(
+                        "{} evaluated to {} outputs, but the node has {}",
+                        self.name(),
+                        output_values.len(),
+                        infered_outputs.len()
+                    );
+                }
+                let output_facts = output_values.into_iter().map(|v| v.into()).collect();
+                return Ok((infered_inputs, output_facts));
             }
         }
 
         Ok((infered_inputs, infered_outputs))
     }
 
+    /// Whether `PropConst` may replace the output of the operator by a
+    /// constant when all its inputs are known. Operators opt in explicitly.
+    fn is_foldable(&self) -> bool {
+        false
+    }
+
+    /// Whether the operator evaluates each call independently of the
+    /// previous ones. Operators carrying state across calls, for instance
+    /// across pulses, must return false.
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
     fn reduce(
         &self,
         _inputs: TVec<&TensorFact>,
@@ -129,6 +192,20 @@ pub trait Op:
         Ok(None)
     }
 
+    /// Indices of the inputs, like shapes or parameters, that must be
+    /// constant for the operator to be fully analysed.
+    ///
+    /// Indices of optional inputs the node does not have are ignored.
+    fn const_inputs(&self) -> TVec<usize> {
+        tvec!()
+    }
+
+    /// Estimates the cost of evaluating the operator on inputs matching
+    /// `inputs`. Defaults to zero for operators with no estimate.
+    fn cost(&self, _inputs: TVec<&TensorFact>) -> OpCost {
+        OpCost::default()
+    }
+
     fn pulsify(
         &self,
         _inputs: TVec<&PulsedTensorFact>,
@@ -163,6 +240,28 @@ pub trait InferenceOp {
         inputs: TVec<&TensorFact>,
         outputs: TVec<&TensorFact>,
     ) -> TractResult<(TVec<TensorFact>, TVec<TensorFact>)>;
+
+    /// Infers facts about the `noutputs` outputs from the input facts only.
+    fn infer_forward(
+        &self,
+        inputs: TVec<&TensorFact>,
+        noutputs: usize,
+    ) -> TractResult<TVec<TensorFact>> {
+        let outputs = tvec![TensorFact::default(); noutputs];
+        let (_, outputs) = self.infer_facts(inputs, outputs.iter().collect())?;
+        Ok(outputs)
+    }
+
+    /// Infers facts about the `ninputs` inputs from the output facts only.
+    fn infer_backward(
+        &self,
+        ninputs: usize,
+        outputs: TVec<&TensorFact>,
+    ) -> TractResult<TVec<TensorFact>> {
+        let inputs = tvec![TensorFact::default(); ninputs];
+        let (inputs, _) = self.infer_facts(inputs.iter().collect(), outputs)?;
+        Ok(inputs)
+    }
 }
 
 clone_trait_object!(Op);
@@ -187,3 +286,17 @@ impl ReducedOpRewire {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_negative_axis() {
+        assert_eq!(normalize_axis(-1, 3).unwrap(), 2);
+        assert_eq!(normalize_axis(-3, 3).unwrap(), 0);
+        assert_eq!(normalize_axis(1, 3).unwrap(), 1);
+        assert!(normalize_axis(3, 3).is_err());
+        assert!(normalize_axis(-4, 3).is_err());
+    }
+}
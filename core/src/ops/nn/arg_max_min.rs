@@ -6,19 +6,28 @@ pub struct ArgMaxMin {
     max: bool,
     axis: usize,
     keepdims: bool,
+    /// On ties, pick the last index instead of the first one.
+    select_last_index: bool,
 }
 
 impl ArgMaxMin {
     fn eval_t<T: Datum + PartialOrd>(&self, input: SharedTensor) -> TractResult<SharedTensor> {
         use std::cmp::Ordering;
         let array = input.to_array_view::<T>()?;
-        let f: fn(&(usize, &T), &(usize, &T)) -> Ordering = if self.max {
-            |a, b| a.1.partial_cmp(&b.1).unwrap_or(a.0.cmp(&b.0))
-        } else {
-            |a, b| b.1.partial_cmp(&a.1).unwrap_or(a.0.cmp(&b.0))
-        };
         let mut values = array.map_axis(Axis(self.axis), |row| {
-            row.iter().enumerate().max_by(f).unwrap().0 as i64
+            let mut best = 0;
+            for (ix, v) in row.iter().enumerate().skip(1) {
+                let better = match v.partial_cmp(&row[best]) {
+                    Some(Ordering::Greater) => self.max,
+                    Some(Ordering::Less) => !self.max,
+                    Some(Ordering::Equal) => self.select_last_index,
+                    None => false,
+                };
+                if better {
+                    best = ix;
+                }
+            }
+            best as i64
         });
         if self.keepdims {
             values = values.insert_axis(Axis(self.axis));
@@ -79,3 +88,31 @@ impl InferenceRulesOp for ArgMaxMin {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(op: ArgMaxMin) -> Tensor {
+        let input = Tensor::from(arr2(&[[1.0f32, 3.0, 3.0], [5.0, 2.0, 5.0]]));
+        op.eval(tvec!(input.into())).unwrap().remove(0).to_tensor()
+    }
+
+    #[test]
+    fn argmax_ties_pick_first_index() {
+        let found = run(ArgMaxMin::new(true, 1, false, false));
+        assert_eq!(found, Tensor::from(arr1(&[1i64, 0])));
+    }
+
+    #[test]
+    fn argmax_ties_select_last_index() {
+        let found = run(ArgMaxMin::new(true, 1, true, true));
+        assert_eq!(found, Tensor::from(arr2(&[[2i64], [2]])));
+    }
+
+    #[test]
+    fn argmin_along_axis_0() {
+        let found = run(ArgMaxMin::new(false, 0, false, false));
+        assert_eq!(found, Tensor::from(arr1(&[0i64, 1, 0])));
+    }
+}
@@ -2,11 +2,19 @@ use self::super::DataFormat;
 use crate::ops::prelude::*;
 use num_traits::AsPrimitive;
 
+/// Batch normalization, in inference mode: the input is normalized along
+/// the channel axis with the given mean and variance, then scaled and
+/// shifted.
+///
+/// Inputs are x, scale, bias, mean and var, the last four being of rank 1
+/// and as long as the channel dimension. The momentum is only relevant to
+/// the training of the model, and is kept for reference.
 #[derive(Debug, Clone, new, Default)]
 pub struct BatchNorm {
-    data_format: DataFormat,
-    epsilon: f32,
-    spatial: bool,
+    pub data_format: DataFormat,
+    pub epsilon: f32,
+    pub momentum: f32,
+    pub spatial: bool,
 }
 
 impl BatchNorm {
@@ -40,6 +48,13 @@ impl Op for BatchNorm {
     fn name(&self) -> Cow<str> {
         "BatchNorm".into()
     }
+
+    fn info(&self) -> TractResult<Option<String>> {
+        Ok(Some(format!(
+            "epsilon: {}, momentum: {}, spatial: {}",
+            self.epsilon, self.momentum, self.spatial
+        )))
+    }
 }
 
 impl StatelessOp for BatchNorm {
@@ -66,6 +81,9 @@ impl InferenceRulesOp for BatchNorm {
             &inputs[4].datum_type
         ))?;
         s.equals(&inputs[0].shape, &outputs[0].shape)?;
+        for param in 1..5 {
+            s.equals(&inputs[param].rank, 1)?;
+        }
         s.equals_all(wrap!(
             &inputs[1].shape,
             &inputs[2].shape,
@@ -79,3 +97,56 @@ impl InferenceRulesOp for BatchNorm {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::{arr1, arr3, Axis};
+
+    fn batch_norm() -> BatchNorm {
+        BatchNorm::new(DataFormat::NCHW, 0.0, 0.9, true)
+    }
+
+    #[test]
+    fn eval_1x2x2x2() {
+        let x = arr3(&[[[1.0f32, 2.0], [3.0, 4.0]], [[0.0, 2.0], [4.0, 6.0]]]);
+        let x = x.insert_axis(Axis(0));
+        let scale = arr1(&[2.0f32, 1.0]);
+        let bias = arr1(&[0.0f32, -1.0]);
+        let mean = arr1(&[2.5f32, 3.0]);
+        let var = arr1(&[4.0f32, 16.0]);
+        let outputs = batch_norm()
+            .eval(tvec!(
+                x.into(),
+                scale.into(),
+                bias.into(),
+                mean.into(),
+                var.into()
+            ))
+            .unwrap();
+        // channel 0: (x - 2.5) / 2 * 2, channel 1: (x - 3) / 4 - 1
+        let expected = arr3(&[
+            [[-1.5f32, -0.5], [0.5, 1.5]],
+            [[-1.75, -1.25], [-0.75, -0.25]],
+        ])
+        .insert_axis(Axis(0));
+        assert!(outputs[0].approx_eq(&Tensor::from(expected), 1e-6));
+    }
+
+    #[test]
+    fn rules() {
+        let x = TensorFact::dt_shape(DatumType::F32, vec![1, 3, 5, 5]);
+        let any = TensorFact::default();
+        let (inputs, outputs) = batch_norm()
+            .infer_facts(tvec!(&x, &any, &any, &any, &any), tvec!(&any))
+            .unwrap();
+        assert_eq!(outputs[0], x);
+        for param in &inputs[1..] {
+            assert_eq!(param, &TensorFact::dt_shape(DatumType::F32, vec![3]));
+        }
+        let wrong = TensorFact::shape(vec![1, 3]);
+        assert!(batch_norm()
+            .infer_facts(tvec!(&x, &wrong, &any, &any, &any), tvec!(&any))
+            .is_err());
+    }
+}
@@ -181,14 +181,35 @@ impl InferenceRulesOp for Conv {
             };
             s.equals(input_c.bex(), self.group as i32 * filter_i.bex())
         })?;
-        s.given_2(
-            &inputs[0].shape,
-            &inputs[1].shape,
-            move |s, ishape, kshape| {
-                let oshape = self.output_shape(&*ishape, &*kshape);
-                s.equals(&outputs[0].shape, oshape)
-            },
-        )
+        s.given_2(&inputs[0].rank, &inputs[1].rank, move |s, irank, krank| {
+            let ishape = self.data_fmt.shape(vec![0usize; irank as usize]);
+            s.equals(
+                &outputs[0].shape[ishape.n_axis()],
+                &inputs[0].shape[ishape.n_axis()],
+            )?;
+            let filter_o = match self.kernel_fmt {
+                KernelFormat::OIHW => &inputs[1].shape[0],
+                KernelFormat::HWIO => &inputs[1].shape[krank as usize - 1],
+            };
+            s.equals(&outputs[0].shape[ishape.c_axis()], filter_o)?;
+            // each spatial output dim only depends on its input and kernel
+            // dims, so a symbolic axis does not hold back the other ones
+            for ax in 0..ishape.hw_rank() {
+                let axis = ishape.h_axis() + ax;
+                s.given_2(
+                    &inputs[0].shape[axis],
+                    &inputs[1].shape[self.kernel_fmt.h_axis() + ax],
+                    move |s, idim, kdim| {
+                        let kdim = kdim.to_integer()? as usize;
+                        let dilation = self.dilations.as_ref().map(|d| d[ax]).unwrap_or(1);
+                        let stride = self.strides.as_ref().map(|d| d[ax]).unwrap_or(1);
+                        let computed = self.padding.compute_one(ax, idim, kdim, dilation, stride);
+                        s.equals(&outputs[0].shape[axis], computed.output[0])
+                    },
+                )?;
+            }
+            Ok(())
+        })
     }
 }
 
@@ -216,6 +237,53 @@ mod test {
         );
     }
 
+    fn strided_padded_conv() -> Conv {
+        let mut op = Conv::default();
+        op.strides = Some(tvec![2, 2]);
+        op.padding = PaddingSpec::Explicit(tvec![1, 1], tvec![1, 1]);
+        op
+    }
+
+    #[test]
+    fn test_infer_stride_2_pad_1_concrete() {
+        let op = strided_padded_conv();
+        let ifact = TensorFact::dt_shape(DatumType::F32, shapefact!(1, 1, 7, 5));
+        let kfact = TensorFact::dt_shape(DatumType::F32, shapefact!(1, 1, 3, 3));
+        let ofact = TensorFact::default();
+        let facts = op
+            .infer_facts(tvec!(&ifact, &kfact), tvec!(&ofact))
+            .unwrap();
+        assert_eq!(
+            facts.1,
+            tvec!(TensorFact::dt_shape(DatumType::F32, shapefact!(1, 1, 4, 3)))
+        );
+    }
+
+    #[test]
+    fn test_infer_stride_2_pad_1_symbolic() {
+        let op = strided_padded_conv();
+        let ifact = TensorFact::dt_shape(DatumType::F32, shapefact!(_, 1, S, 5));
+        let kfact = TensorFact::dt_shape(DatumType::F32, shapefact!(1, 1, 3, 3));
+        let ofact = TensorFact::default();
+        let facts = op
+            .infer_facts(tvec!(&ifact, &kfact), tvec!(&ofact))
+            .unwrap();
+        // floor((S + 1 + 1 - 3) / 2) + 1 == ceil(S / 2)
+        let height = TDim::s().div_ceil(2.to_dim());
+        assert_eq!(
+            facts.1,
+            tvec!(TensorFact::dt_shape(
+                DatumType::F32,
+                ShapeFact::closed(tvec!(
+                    DimFact::default(),
+                    1.to_dim().into(),
+                    height.into(),
+                    3.to_dim().into()
+                ))
+            ))
+        );
+    }
+
     #[test]
     fn test_infer_channels() {
         let op = Conv::default(); // NCHW - OIHW
@@ -182,3 +182,44 @@ fn rules<'r, 'p: 'r, 's: 'r>(
         Ok(())
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ops::InferenceOp;
+
+    fn input() -> SharedTensor {
+        let data: Vec<f32> = (0..48).map(|x| x as f32).collect();
+        Tensor::from(Array4::from_shape_vec((1, 3, 4, 4), data).unwrap()).into()
+    }
+
+    #[test]
+    fn global_avg_pool_1x3x4x4() {
+        let outputs = GlobalAvgPool::default().eval(tvec!(input())).unwrap();
+        let expected = Array4::from_shape_vec((1, 3, 1, 1), vec![7.5f32, 23.5, 39.5]).unwrap();
+        assert_eq!(*outputs[0], Tensor::from(expected));
+    }
+
+    #[test]
+    fn global_max_pool_1x3x4x4() {
+        let outputs = GlobalMaxPool::default().eval(tvec!(input())).unwrap();
+        let expected = Array4::from_shape_vec((1, 3, 1, 1), vec![15.0f32, 31.0, 47.0]).unwrap();
+        assert_eq!(*outputs[0], Tensor::from(expected));
+    }
+
+    #[test]
+    fn global_pool_rules() {
+        let input = TensorFact::dt_shape(
+            DatumType::F32,
+            vec![1.to_dim(), 3.to_dim(), TDim::s(), 4.to_dim()],
+        );
+        let any = TensorFact::default();
+        let (_, outputs) = GlobalAvgPool::default()
+            .infer_facts(tvec!(&input), tvec!(&any))
+            .unwrap();
+        assert_eq!(
+            outputs[0],
+            TensorFact::dt_shape(DatumType::F32, vec![1, 3, 1, 1])
+        );
+    }
+}
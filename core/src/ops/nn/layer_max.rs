@@ -1,4 +1,5 @@
 use crate::ops::prelude::*;
+use ndarray::Axis;
 
 #[derive(Debug, Clone, new, Default)]
 pub struct LayerHardmax {
@@ -177,6 +178,61 @@ impl InferenceRulesOp for LayerSoftmax {
     }
 }
 
+/// Softmax applied along a single axis, as in ONNX opset 13.
+#[derive(Debug, Clone, new, Default)]
+pub struct Softmax {
+    axis: i64,
+}
+
+impl Softmax {
+    fn eval_t<D: Datum + ::num_traits::Float + ::std::iter::Sum>(
+        &self,
+        input: SharedTensor,
+    ) -> TractResult<TVec<SharedTensor>> {
+        let mut array = input.to_array::<D>()?;
+        let axis = normalize_axis(self.axis, array.ndim())?;
+        array
+            .lanes_mut(Axis(axis))
+            .into_iter()
+            .for_each(|mut lane| {
+                // subtracting the max keeps exp() from overflowing
+                let max = lane.iter().cloned().fold(D::neg_infinity(), D::max);
+                lane.mapv_inplace(|x| (x - max).exp());
+                let divisor: D = lane.iter().cloned().sum();
+                lane.mapv_inplace(|x| x / divisor);
+            });
+        Ok(tvec!(array.into()))
+    }
+}
+
+impl Op for Softmax {
+    fn name(&self) -> Cow<str> {
+        "Softmax".into()
+    }
+}
+
+impl StatelessOp for Softmax {
+    fn eval(&self, mut inputs: TVec<SharedTensor>) -> TractResult<TVec<SharedTensor>> {
+        let input = args_1!(inputs);
+        dispatch_floatlike!(Self::eval_t(input.datum_type())(self, input))
+    }
+}
+
+impl InferenceRulesOp for Softmax {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        solver: &mut Solver<'r>,
+        inputs: &'p SharedTensorsProxy,
+        outputs: &'p SharedTensorsProxy,
+    ) -> InferenceResult {
+        rules(solver, inputs, outputs)?;
+        solver.given(&inputs[0].rank, move |_, rank| {
+            normalize_axis(self.axis, rank as usize)?;
+            Ok(())
+        })
+    }
+}
+
 fn rules<'r, 'p: 'r, 's: 'r>(
     s: &mut Solver<'r>,
     inputs: &'p SharedTensorsProxy,
@@ -188,3 +244,41 @@ fn rules<'r, 'p: 'r, 's: 'r>(
     s.equals(&outputs[0].shape, &inputs[0].shape)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::arr2;
+
+    #[test]
+    fn softmax_does_not_overflow_on_large_inputs() {
+        let input: Tensor = arr2(&[[1000.0f32, 1000.0], [0.0, 0.0]]).into();
+        let output = Softmax::new(-1).eval(tvec!(input.into())).unwrap();
+        assert_eq!(
+            output[0].to_array_view::<f32>().unwrap(),
+            arr2(&[[0.5f32, 0.5], [0.5, 0.5]]).into_dyn()
+        );
+    }
+
+    #[test]
+    fn softmax_rejects_invalid_axis() {
+        let input: Tensor = arr2(&[[0.0f32, 1.0], [0.0, 1.0]]).into();
+        assert!(Softmax::new(2).eval(tvec!(input.clone().into())).is_err());
+        assert!(Softmax::new(-3).eval(tvec!(input.into())).is_err());
+        let fact = TensorFact::dt_shape(DatumType::F32, vec![2, 2]);
+        let any = TensorFact::default();
+        assert!(Softmax::new(2)
+            .infer_facts(tvec!(&fact), tvec!(&any))
+            .is_err());
+    }
+
+    #[test]
+    fn softmax_normalizes_along_axis_only() {
+        let input: Tensor = arr2(&[[0.0f32, 1.0], [0.0, 1.0]]).into();
+        let output = Softmax::new(0).eval(tvec!(input.into())).unwrap();
+        assert_eq!(
+            output[0].to_array_view::<f32>().unwrap(),
+            arr2(&[[0.5f32, 0.5], [0.5, 0.5]]).into_dyn()
+        );
+    }
+}
@@ -0,0 +1,281 @@
+use crate::ops::prelude::*;
+use ndarray::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LstmDirection {
+    Forward,
+    Reverse,
+    Bidirectional,
+}
+
+impl LstmDirection {
+    pub fn num_directions(&self) -> usize {
+        match self {
+            LstmDirection::Bidirectional => 2,
+            _ => 1,
+        }
+    }
+}
+
+impl Default for LstmDirection {
+    fn default() -> LstmDirection {
+        LstmDirection::Forward
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LstmActivation {
+    Sigmoid,
+    Tanh,
+    Relu,
+}
+
+impl LstmActivation {
+    fn apply(&self, x: f32) -> f32 {
+        match self {
+            LstmActivation::Sigmoid => ((-x).exp() + 1.0).recip(),
+            LstmActivation::Tanh => x.tanh(),
+            LstmActivation::Relu => x.max(0.0),
+        }
+    }
+}
+
+/// Long Short-Term Memory layer, following the ONNX conventions.
+///
+/// Inputs are X, W, R, and then the optional B, sequence_lens, initial_h,
+/// initial_c and P, at the slots given by the `*_input` fields. Gates are
+/// laid out in the "iofc" order in W, R and B. Outputs are Y, Y_h and Y_c.
+#[derive(Debug, Clone, new)]
+pub struct Lstm {
+    hidden_size: usize,
+    direction: LstmDirection,
+    /// f, g and h activations
+    activations: [LstmActivation; 3],
+    bias_input: Option<usize>,
+    sequence_lens_input: Option<usize>,
+    initial_h_input: Option<usize>,
+    initial_c_input: Option<usize>,
+    peephole_input: Option<usize>,
+}
+
+impl Lstm {
+    fn initial_state(
+        &self,
+        inputs: &TVec<SharedTensor>,
+        slot: Option<usize>,
+        batch: usize,
+    ) -> TractResult<Array2<f32>> {
+        match slot {
+            Some(slot) => {
+                let state = inputs[slot]
+                    .to_array_view::<f32>()?
+                    .into_dimensionality::<Ix3>()?;
+                Ok(state.index_axis(Axis(0), 0).to_owned())
+            }
+            None => Ok(Array2::zeros((batch, self.hidden_size))),
+        }
+    }
+}
+
+impl Op for Lstm {
+    fn name(&self) -> Cow<str> {
+        "Lstm".into()
+    }
+
+    fn noutputs(&self) -> usize {
+        3
+    }
+}
+
+impl StatelessOp for Lstm {
+    fn eval(&self, inputs: TVec<SharedTensor>) -> TractResult<TVec<SharedTensor>> {
+        if self.direction != LstmDirection::Forward {
+            bail!(
+                "Lstm: only forward direction is supported, got {:?}",
+                self.direction
+            )
+        }
+        if self.peephole_input.is_some() {
+            bail!("Lstm: peepholes are not supported")
+        }
+        let x = inputs[0]
+            .to_array_view::<f32>()?
+            .into_dimensionality::<Ix3>()?;
+        let w = inputs[1]
+            .to_array_view::<f32>()?
+            .into_dimensionality::<Ix3>()?;
+        let r = inputs[2]
+            .to_array_view::<f32>()?
+            .into_dimensionality::<Ix3>()?;
+        let w = w.index_axis(Axis(0), 0);
+        let r = r.index_axis(Axis(0), 0);
+        let (seq_length, batch_size, _) = x.dim();
+        let hs = self.hidden_size;
+
+        if let Some(slot) = self.sequence_lens_input {
+            let lens = inputs[slot].cast_to::<i32>()?;
+            if lens
+                .to_array_view::<i32>()?
+                .iter()
+                .any(|&l| l as usize != seq_length)
+            {
+                bail!("Lstm: variable sequence lengths are not supported")
+            }
+        }
+
+        let bias = match self.bias_input {
+            Some(slot) => {
+                let b = inputs[slot]
+                    .to_array_view::<f32>()?
+                    .into_dimensionality::<Ix2>()?;
+                let b = b.index_axis(Axis(0), 0);
+                &b.slice(s![0..4 * hs]) + &b.slice(s![4 * hs..8 * hs])
+            }
+            None => Array1::zeros(4 * hs),
+        };
+
+        let mut h = self.initial_state(&inputs, self.initial_h_input, batch_size)?;
+        let mut c = self.initial_state(&inputs, self.initial_c_input, batch_size)?;
+        let mut y = Array4::<f32>::zeros((seq_length, 1, batch_size, hs));
+
+        let [f, g, h_act] = self.activations;
+        for t in 0..seq_length {
+            let gates = x.index_axis(Axis(0), t).dot(&w.t()) + h.dot(&r.t()) + &bias;
+            let i_gate = gates.slice(s![.., 0..hs]).mapv(|v| f.apply(v));
+            let o_gate = gates.slice(s![.., hs..2 * hs]).mapv(|v| f.apply(v));
+            let f_gate = gates.slice(s![.., 2 * hs..3 * hs]).mapv(|v| f.apply(v));
+            let c_gate = gates.slice(s![.., 3 * hs..4 * hs]).mapv(|v| g.apply(v));
+            c = f_gate * &c + i_gate * c_gate;
+            h = o_gate * c.mapv(|v| h_act.apply(v));
+            y.slice_mut(s![t, 0, .., ..]).assign(&h);
+        }
+
+        Ok(tvec!(
+            y.into(),
+            h.insert_axis(Axis(0)).into(),
+            c.insert_axis(Axis(0)).into()
+        ))
+    }
+}
+
+impl InferenceRulesOp for Lstm {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p SharedTensorsProxy,
+        outputs: &'p SharedTensorsProxy,
+    ) -> InferenceResult {
+        let optional_inputs = [
+            self.bias_input,
+            self.sequence_lens_input,
+            self.initial_h_input,
+            self.initial_c_input,
+            self.peephole_input,
+        ];
+        let ninputs = 3 + optional_inputs.iter().filter(|i| i.is_some()).count();
+        s.equals(&inputs.len, ninputs as i32)?;
+        s.equals(&outputs.len, 3)?;
+        s.equals_all(wrap!(
+            &inputs[0].datum_type,
+            &inputs[1].datum_type,
+            &inputs[2].datum_type,
+            &outputs[0].datum_type,
+            &outputs[1].datum_type,
+            &outputs[2].datum_type
+        ))?;
+        s.equals(&inputs[0].rank, 3)?;
+        s.equals(&inputs[1].rank, 3)?;
+        s.equals(&inputs[2].rank, 3)?;
+        s.equals(&outputs[0].rank, 4)?;
+        s.equals(&outputs[1].rank, 3)?;
+        s.equals(&outputs[2].rank, 3)?;
+
+        let num_directions = self.direction.num_directions().to_dim();
+        let hidden_size = self.hidden_size.to_dim();
+        s.equals(&inputs[1].shape[0], num_directions)?;
+        s.equals(&inputs[1].shape[1], (4 * self.hidden_size).to_dim())?;
+        s.equals(&inputs[1].shape[2], &inputs[0].shape[2])?;
+        s.equals(&inputs[2].shape[0], num_directions)?;
+        s.equals(&inputs[2].shape[1], (4 * self.hidden_size).to_dim())?;
+        s.equals(&inputs[2].shape[2], hidden_size)?;
+        if let Some(b) = self.bias_input {
+            s.equals(&inputs[b].datum_type, &inputs[0].datum_type)?;
+            s.equals(&inputs[b].rank, 2)?;
+            s.equals(&inputs[b].shape[0], num_directions)?;
+            s.equals(&inputs[b].shape[1], (8 * self.hidden_size).to_dim())?;
+        }
+
+        s.equals(&outputs[0].shape[0], &inputs[0].shape[0])?;
+        s.equals(&outputs[0].shape[1], num_directions)?;
+        s.equals(&outputs[0].shape[2], &inputs[0].shape[1])?;
+        s.equals(&outputs[0].shape[3], hidden_size)?;
+        for o in 1..3 {
+            s.equals(&outputs[o].shape[0], num_directions)?;
+            s.equals(&outputs[o].shape[1], &inputs[0].shape[1])?;
+            s.equals(&outputs[o].shape[2], hidden_size)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::arr3;
+
+    fn lstm(bias: bool) -> Lstm {
+        Lstm::new(
+            1,
+            LstmDirection::Forward,
+            [
+                LstmActivation::Sigmoid,
+                LstmActivation::Tanh,
+                LstmActivation::Tanh,
+            ],
+            if bias { Some(3) } else { None },
+            None,
+            None,
+            None,
+            None,
+        )
+    }
+
+    #[test]
+    fn single_step() {
+        // one input feature, one hidden unit, W in "iofc" order.
+        // i = sigmoid(ln(3)) = 0.75, o = f = sigmoid(0) = 0.5,
+        // c~ = tanh(atanh(0.6)) = 0.6
+        // C = 0.5 * 0 + 0.75 * 0.6 = 0.45, H = 0.5 * tanh(0.45)
+        let x = Tensor::from(arr3(&[[[1.0f32]]]));
+        let w = Tensor::from(arr3(&[[[0.0f32], [0.0], [0.0], [0.6f32.atanh()]]]));
+        let r = Tensor::from(arr3(&[[[2.0f32], [2.0], [2.0], [2.0]]]));
+        let b = Tensor::from(arr2(&[[3.0f32.ln(), 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]]));
+        let outputs = lstm(true)
+            .eval(tvec!(x.into(), w.into(), r.into(), b.into()))
+            .unwrap();
+        let h = 0.5 * 0.45f32.tanh();
+        assert!(outputs[0].approx_eq(&Tensor::from(arr3(&[[[h]]]).insert_axis(Axis(0))), 1e-6));
+        assert!(outputs[1].approx_eq(&Tensor::from(arr3(&[[[h]]])), 1e-6));
+        assert!(outputs[2].approx_eq(&Tensor::from(arr3(&[[[0.45f32]]])), 1e-6));
+    }
+
+    #[test]
+    fn output_shapes() {
+        let x = TensorFact::dt_shape(DatumType::F32, shapefact![7, 2, 5]);
+        let w = TensorFact::default();
+        let r = TensorFact::default();
+        let any = TensorFact::default();
+        let (_, outputs) = lstm(false)
+            .infer_facts(tvec!(&x, &w, &r), tvec!(&any, &any, &any))
+            .unwrap();
+        assert_eq!(
+            outputs[0],
+            TensorFact::dt_shape(DatumType::F32, shapefact![7, 1, 2, 1])
+        );
+        assert_eq!(
+            outputs[1],
+            TensorFact::dt_shape(DatumType::F32, shapefact![1, 2, 1])
+        );
+        assert_eq!(outputs[1], outputs[2]);
+    }
+}
@@ -6,6 +6,7 @@ mod data_formats;
 mod global_pools;
 mod layer_max;
 mod lrn;
+mod lstm;
 mod maxpool;
 mod padding;
 mod patches;
@@ -17,8 +18,9 @@ pub use self::batch_norm::BatchNorm;
 pub use self::conv::{Conv, ConvUnary, KernelFormat};
 pub use self::data_formats::{DataFormat, DataShape};
 pub use self::global_pools::{GlobalAvgPool, GlobalLpPool, GlobalMaxPool};
-pub use self::layer_max::{LayerHardmax, LayerLogSoftmax, LayerSoftmax};
+pub use self::layer_max::{LayerHardmax, LayerLogSoftmax, LayerSoftmax, Softmax};
 pub use self::lrn::Lrn;
+pub use self::lstm::{Lstm, LstmActivation, LstmDirection};
 pub use self::maxpool::MaxPool;
 pub use self::padding::PaddingSpec;
 pub use self::patches::Patch;
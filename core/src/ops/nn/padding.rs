@@ -87,6 +87,25 @@ impl PaddingSpec {
         }
     }
 
+    /// Computes the padding and output size along the spatial axis `axis`
+    /// alone, so that it does not depend on the other axes being known.
+    pub fn compute_one<D: DimLike, KD: Into<D> + Copy>(
+        &self,
+        axis: usize,
+        input: D,
+        kernel: KD,
+        dilation: usize,
+        stride: usize,
+    ) -> ComputedPaddedDim<D> {
+        let spec = match self {
+            PaddingSpec::Explicit(bef, aft) => {
+                PaddingSpec::Explicit(tvec!(bef[axis]), tvec!(aft[axis]))
+            }
+            other => other.clone(),
+        };
+        spec.compute(&[input], &[kernel], &[dilation], &[stride])
+    }
+
     fn explicit<D: DimLike, KD: Into<D> + Copy>(
         data_spatial_shape: &[D],
         kernel_spatial_shape: &[KD],
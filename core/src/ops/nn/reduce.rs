@@ -87,11 +87,13 @@ impl Reducer {
     {
         use ndarray::*;
         let input = input.to_array::<T>()?;
+        let rank = input.ndim();
+        reduce.resolved_axes(rank)?;
         let full_output_shape: Vec<usize> = input
             .shape()
             .iter()
             .enumerate()
-            .map(|(ax, &d)| if reduce.must_reduce(ax) { 1 } else { d })
+            .map(|(ax, &d)| if reduce.must_reduce(ax, rank) { 1 } else { d })
             .collect();
         let mut result = Array::from_shape_fn(&*full_output_shape, |coords| {
             let slice_spec: Vec<SliceOrIndex> = coords
@@ -99,7 +101,7 @@ impl Reducer {
                 .iter()
                 .enumerate()
                 .map(|(ax, &d)| {
-                    if reduce.must_reduce(ax) {
+                    if reduce.must_reduce(ax, rank) {
                         (..).into()
                     } else {
                         d.into()
@@ -112,7 +114,7 @@ impl Reducer {
         });
         if !reduce.keep_dims {
             for ax in (0..full_output_shape.len()).rev() {
-                if reduce.must_reduce(ax) {
+                if reduce.must_reduce(ax, rank) {
                     result = result.index_axis_move(Axis(ax), 0);
                 }
             }
@@ -204,17 +206,37 @@ where
     v.fold(T::zero(), |acc, &v| acc + v * v)
 }
 
+/// Reduction over some axes of the input.
+///
+/// Negative axes count from the last one, and no axes (or an empty list)
+/// means reducing over all of them.
 #[derive(Clone, Debug, new)]
 pub struct Reduce {
-    axes: Option<Vec<usize>>,
+    axes: Option<Vec<i64>>,
     keep_dims: bool,
     reducer: Reducer,
 }
 
 impl Reduce {
-    pub fn must_reduce(&self, ax: usize) -> bool {
-        self.axes
-            .as_ref()
+    fn resolved_axes(&self, rank: usize) -> TractResult<Option<Vec<usize>>> {
+        let axes = match self.axes.as_ref() {
+            Some(axes) if !axes.is_empty() => axes,
+            _ => return Ok(None),
+        };
+        let mut resolved = vec![];
+        for &axis in axes {
+            let ax = normalize_axis(axis, rank)?;
+            if !resolved.contains(&ax) {
+                resolved.push(ax);
+            }
+        }
+        Ok(Some(resolved))
+    }
+
+    pub fn must_reduce(&self, ax: usize, rank: usize) -> bool {
+        self.resolved_axes(rank)
+            .ok()
+            .and_then(|axes| axes)
             .map(|axes| axes.contains(&ax))
             .unwrap_or(true)
     }
@@ -241,22 +263,26 @@ impl InferenceRulesOp for Reduce {
     ) -> InferenceResult {
         s.equals(&inputs.len, 1)?;
         s.equals(&outputs.len, 1)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
         if self.keep_dims {
             s.equals(&inputs[0].rank, &outputs[0].rank)?;
-        } else if let Some(axes) = self.axes.as_ref() {
-            s.equals(
-                (&inputs[0].rank).bex() - axes.len() as i32,
-                &outputs[0].rank,
-            )?;
         } else {
-            s.equals(&outputs[0].rank, 0)?;
+            s.given(&inputs[0].rank, move |s, rank| {
+                let reduced = match self.resolved_axes(rank as usize)? {
+                    Some(axes) => axes.len(),
+                    None => rank as usize,
+                };
+                s.equals(&outputs[0].rank, (rank as usize - reduced) as i32)
+            })?;
         }
         s.given(&inputs[0].shape, move |s, shape| {
+            let rank = shape.len();
+            self.resolved_axes(rank)?;
             let out_shape: TVec<TDim> = shape
                 .iter()
                 .enumerate()
                 .filter_map(|(ix, &d)| {
-                    if self.must_reduce(ix) {
+                    if self.must_reduce(ix, rank) {
                         if self.keep_dims {
                             Some(1.to_dim())
                         } else {
@@ -271,3 +297,64 @@ impl InferenceRulesOp for Reduce {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mean(axes: Option<Vec<i64>>, keep_dims: bool) -> TractResult<SharedTensor> {
+        let input: Tensor = arr2(&[[1.0f32, 2.0, 3.0], [4.0, 5.0, 6.0]]).into();
+        let mut output = Reduce::new(axes, keep_dims, Reducer::Mean).eval(tvec!(input.into()))?;
+        Ok(output.remove(0))
+    }
+
+    #[test]
+    fn reduce_mean_keep_dims() {
+        let output = mean(Some(vec![1]), true).unwrap();
+        assert_eq!(
+            output.to_array_view::<f32>().unwrap(),
+            arr2(&[[2.0f32], [5.0]]).into_dyn()
+        );
+    }
+
+    #[test]
+    fn reduce_mean_drop_dims() {
+        let output = mean(Some(vec![1]), false).unwrap();
+        assert_eq!(
+            output.to_array_view::<f32>().unwrap(),
+            arr1(&[2.0f32, 5.0]).into_dyn()
+        );
+        assert_eq!(mean(Some(vec![-1]), false).unwrap(), output);
+    }
+
+    #[test]
+    fn reduce_mean_all_axes() {
+        let expected: SharedTensor = Tensor::from(arr0(3.5f32)).into();
+        assert_eq!(mean(None, false).unwrap(), expected);
+        assert_eq!(mean(Some(vec![]), false).unwrap(), expected);
+        assert!(mean(Some(vec![2]), false).is_err());
+    }
+
+    #[test]
+    fn reduce_infers_output_shape() {
+        use crate::model::dsl::ModelDsl;
+        use crate::Model;
+        for &(keep_dims, ref shape) in &[(true, vec![2, 1]), (false, vec![2])] {
+            let mut model = Model::default();
+            model
+                .add_source_fact("a", TensorFact::dt_shape(DatumType::F32, vec![2, 3]))
+                .unwrap();
+            model
+                .chain(
+                    "mean",
+                    Box::new(Reduce::new(Some(vec![-1]), keep_dims, Reducer::Mean)),
+                )
+                .unwrap();
+            model.analyse().unwrap();
+            assert_eq!(
+                model.output_fact().unwrap(),
+                &TensorFact::dt_shape(DatumType::F32, shape.clone())
+            );
+        }
+    }
+}
@@ -2,9 +2,28 @@ use crate::model::{InletId, OutletId};
 use crate::{Model, TractResult};
 use std::collections::HashMap;
 
+/// Rebuilds the model with only the nodes contributing to its outputs.
+///
+/// The model inputs are kept even when they are unused, so that the model
+/// still takes the same inputs. Hinted nodes that get pruned are logged, as
+/// their hints had no effect.
 pub fn compact(old: &Model) -> TractResult<Model> {
+    let (model, pruned_hints) = compact_with_pruned_hints(old)?;
+    if !pruned_hints.is_empty() {
+        let names: Vec<&str> = pruned_hints.iter().map(|&n| &*old.node(n).name).collect();
+        warn!(
+            "Hinted nodes do not contribute to any output and were pruned: {:?}",
+            names
+        );
+    }
+    Ok(model)
+}
+
+/// Same as `compact`, also returning the ids (in `old`) of the hinted nodes
+/// that were pruned.
+pub fn compact_with_pruned_hints(old: &Model) -> TractResult<(Model, Vec<usize>)> {
     let mut model = Model::default();
-    let mut map = HashMap::new();
+    let mut map: HashMap<usize, usize> = HashMap::new();
     for old_id in old.eval_order()? {
         let old_node = &old.nodes()[old_id];
         let new_id = model.add_node(old_node.name.clone(), old_node.op.clone())?;
@@ -16,22 +35,126 @@ pub fn compact(old: &Model) -> TractResult<Model> {
             continue;
         }
         for (ix, input) in old_node.inputs.iter().enumerate() {
-            model.add_edge(
-                OutletId::new(map[&input.node], input.slot),
-                InletId::new(new_id, ix),
-            )?;
+            let src = *map.get(&input.node).ok_or_else(|| {
+                format!(
+                    "Edge from node {} ({}) to input {} of node {} ({}) refers to a pruned node",
+                    input.node,
+                    old.node(input.node).name,
+                    ix,
+                    old_id,
+                    old_node.name
+                )
+            })?;
+            model.add_edge(OutletId::new(src, input.slot), InletId::new(new_id, ix))?;
+        }
+    }
+    for input in old.inputs()? {
+        if !map.contains_key(&input.node) {
+            let old_node = &old.nodes()[input.node];
+            let new_id = model.add_node(old_node.name.clone(), old_node.op.clone())?;
+            map.insert(input.node, new_id);
+            for (ix, output) in old_node.outputs.iter().enumerate() {
+                model.set_fact(OutletId::new(new_id, ix), output.fact.clone())?;
+            }
         }
     }
     // maintaining order of i/o interface
+    let remap = |outlet: &OutletId| -> TractResult<OutletId> {
+        let node = map
+            .get(&outlet.node)
+            .ok_or_else(|| format!("Node {} was pruned", old.node(outlet.node).name))?;
+        Ok(OutletId::new(*node, outlet.slot))
+    };
     model.inputs = old
         .inputs()?
         .iter()
-        .map(|i| OutletId::new(map[&i.node], i.slot))
-        .collect();
+        .map(remap)
+        .collect::<TractResult<_>>()?;
     model.outputs = old
         .outputs()?
         .iter()
-        .map(|o| OutletId::new(map[&o.node], o.slot))
-        .collect();
-    Ok(model)
+        .map(remap)
+        .collect::<TractResult<_>>()?;
+    let mut pruned_hints = vec![];
+    for &hinted in &old.hinted {
+        match map.get(&hinted) {
+            Some(&new_id) => {
+                model.hinted.insert(new_id);
+            }
+            None => pruned_hints.push(hinted),
+        }
+    }
+    pruned_hints.sort();
+    Ok((model, pruned_hints))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::dsl::ModelDsl;
+    use crate::ops::prelude::*;
+
+    #[test]
+    fn compact_keeps_unused_inputs() {
+        let mut model = Model::default();
+        let a = model
+            .add_source_fact("a", TensorFact::dt_shape(DatumType::F32, vec![2]))
+            .unwrap();
+        model
+            .add_source_fact("b", TensorFact::dt_shape(DatumType::F32, vec![2]))
+            .unwrap();
+        let abs = model
+            .add_node(
+                "abs".to_string(),
+                Box::new(crate::ops::math::Abs::default()),
+            )
+            .unwrap();
+        model
+            .add_edge(OutletId::new(a, 0), InletId::new(abs, 0))
+            .unwrap();
+        model.set_outputs(&["abs"]).unwrap();
+        let compacted = compact(&model).unwrap();
+        let inputs = compacted.inputs().unwrap();
+        assert_eq!(inputs.len(), 2);
+        assert_eq!(compacted.node(inputs[0].node).name, "a");
+        assert_eq!(compacted.node(inputs[1].node).name, "b");
+        assert_eq!(
+            compacted.fact(inputs[1]).unwrap(),
+            &TensorFact::dt_shape(DatumType::F32, vec![2])
+        );
+    }
+
+    #[test]
+    fn compact_lists_pruned_hinted_nodes() {
+        let mut model = Model::default();
+        model
+            .add_source_fact("a", TensorFact::dt_shape(DatumType::F32, vec![2]))
+            .unwrap();
+        let abs = model
+            .chain("abs", Box::new(crate::ops::math::Abs::default()))
+            .unwrap();
+        let dead = model
+            .add_node(
+                "dead".to_string(),
+                Box::new(crate::ops::math::Neg::default()),
+            )
+            .unwrap();
+        model
+            .add_edge(OutletId::new(abs, 0), InletId::new(dead, 0))
+            .unwrap();
+        model.set_outputs(&["abs"]).unwrap();
+        {
+            let mut analyser = crate::analyser::Analyser::new(&mut model).unwrap();
+            analyser
+                .hint_by_name("abs", &TensorFact::dt(DatumType::F32))
+                .unwrap();
+            analyser
+                .hint_by_name("dead", &TensorFact::dt(DatumType::F32))
+                .unwrap();
+        }
+        let (compacted, pruned_hints) = compact_with_pruned_hints(&model).unwrap();
+        assert_eq!(pruned_hints, vec![dead]);
+        assert_eq!(compacted.nodes().len(), 2);
+        assert_eq!(compacted.hinted.len(), 1);
+    }
 }
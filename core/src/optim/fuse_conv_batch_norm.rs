@@ -0,0 +1,212 @@
+use ndarray::*;
+use num_traits::{AsPrimitive, Float};
+
+use crate::model::{InletId, OutletId};
+use crate::ops::nn::{BatchNorm, ConvUnary, KernelFormat};
+use crate::ops::prelude::*;
+use crate::Model;
+
+/// Folds a BatchNorm with constant parameters into the ConvUnary feeding it,
+/// by scaling the kernel and shifting the bias of the convolution.
+#[derive(Debug)]
+pub struct FuseConvBatchNorm;
+
+impl super::OptimizerPass for FuseConvBatchNorm {
+    fn pass(&self, model: &mut Model) -> TractResult<bool> {
+        let mut done_something = false;
+        for id in model.eval_order()? {
+            let (conv_id, fused) = {
+                let node = model.node(id);
+                let bn = match node.op_as::<BatchNorm>() {
+                    Some(bn) => bn,
+                    None => continue,
+                };
+                let conv_outlet = node.inputs[0];
+                let conv_node = model.node(conv_outlet.node);
+                let conv = match conv_node.op_as::<ConvUnary>() {
+                    Some(conv) => conv,
+                    None => continue,
+                };
+                if conv_node.outputs[0].successors.len() != 1
+                    || model.outputs()?.contains(&conv_outlet)
+                    || conv.data_fmt != bn.data_format
+                {
+                    continue;
+                }
+                let params: Option<TVec<SharedTensor>> = node.inputs[1..]
+                    .iter()
+                    .map(|&i| model.fact(i).map(|f| f.value.concretize()))
+                    .collect::<TractResult<Option<_>>>()?;
+                let params = match params {
+                    Some(params) => params,
+                    None => continue,
+                };
+                let fused =
+                    dispatch_floatlike!(self::fuse(conv.kernel.datum_type())(conv, bn, &params))?;
+                match fused {
+                    Some(fused) => (conv_outlet.node, fused),
+                    None => continue,
+                }
+            };
+            trace!("fusing batch norm {} into conv {}", id, conv_id);
+            let conv_outlet = OutletId::new(conv_id, 0);
+            let bn_outlet = OutletId::new(id, 0);
+            model.node_mut(conv_id).op = Box::new(fused);
+            let fact = model.fact(bn_outlet)?.clone();
+            model.set_fact(conv_outlet, fact)?;
+            let successors: Vec<InletId> = model.node(id).outputs[0].successors.clone();
+            for succ in successors {
+                model.add_edge(conv_outlet, succ)?;
+            }
+            model.clear_inputs(id)?;
+            for output in model.outputs.iter_mut() {
+                if *output == bn_outlet {
+                    *output = conv_outlet;
+                }
+            }
+            done_something = true;
+        }
+        Ok(done_something)
+    }
+}
+
+fn fuse<T>(
+    conv: &ConvUnary,
+    bn: &BatchNorm,
+    params: &[SharedTensor],
+) -> TractResult<Option<ConvUnary>>
+where
+    T: Datum + Float,
+    f32: AsPrimitive<T>,
+{
+    let scale = params[0].to_array_view::<T>()?;
+    let beta = params[1].to_array_view::<T>()?;
+    let mean = params[2].to_array_view::<T>()?;
+    let var = params[3].to_array_view::<T>()?;
+    let mut kernel = conv.kernel.to_array_view::<T>()?.to_owned();
+    let o_axis = match conv.kernel_fmt {
+        KernelFormat::OIHW => 0,
+        KernelFormat::HWIO => kernel.ndim() - 1,
+    };
+    let channels = kernel.shape()[o_axis];
+    if [&scale, &beta, &mean, &var]
+        .iter()
+        .any(|p| p.len() != channels)
+    {
+        return Ok(None);
+    }
+    let epsilon: T = bn.epsilon.as_();
+    let factors: Vec<T> = scale
+        .iter()
+        .zip(var.iter())
+        .map(|(&s, &v)| s / (v + epsilon).sqrt())
+        .collect();
+    for (o, mut slice) in kernel.axis_iter_mut(Axis(o_axis)).enumerate() {
+        slice.mapv_inplace(|x| x * factors[o]);
+    }
+    let bias = match &conv.bias {
+        Some(bias) => bias.to_array_view::<T>()?.to_owned().into_shape(channels)?,
+        None => Array1::zeros(channels),
+    };
+    let bias: Array1<T> = (0..channels)
+        .map(|o| (bias[o] - mean[o]) * factors[o] + beta[o])
+        .collect();
+    Ok(Some(ConvUnary {
+        kernel: kernel.into(),
+        bias: Some(bias.into()),
+        ..conv.clone()
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::dsl::ModelDsl;
+    use crate::ops::nn::{BatchNorm, Conv, DataFormat, PaddingSpec};
+    use crate::optim::OptimizerPass;
+
+    #[test]
+    fn fused_conv_batch_norm_matches_unfused() {
+        let kernel = Array4::from_shape_fn((3, 2, 3, 3), |(o, i, h, w)| {
+            ((o * 18 + i * 9 + h * 3 + w) % 7) as f32 * 0.25 - 0.5
+        });
+        let mut model = Model::default();
+        let x = model
+            .add_source_fact("x", TensorFact::dt_shape(DatumType::F32, vec![1, 2, 5, 5]))
+            .unwrap();
+        let k = model
+            .add_const("kernel", Tensor::from(kernel).into())
+            .unwrap();
+        let b = model
+            .add_const("bias", Tensor::from(arr1(&[0.5f32, -1.0, 0.0])).into())
+            .unwrap();
+        let conv = Conv::new(
+            DataFormat::NCHW,
+            KernelFormat::OIHW,
+            None,
+            None,
+            PaddingSpec::SameUpper,
+            None,
+            1,
+        );
+        let conv = model.add_node("conv".to_string(), Box::new(conv)).unwrap();
+        model
+            .add_edge(OutletId::new(x, 0), InletId::new(conv, 0))
+            .unwrap();
+        model
+            .add_edge(OutletId::new(k, 0), InletId::new(conv, 1))
+            .unwrap();
+        model
+            .add_edge(OutletId::new(b, 0), InletId::new(conv, 2))
+            .unwrap();
+        let params = [
+            arr1(&[1.5f32, 0.5, -2.0]),
+            arr1(&[0.1f32, 0.2, 0.3]),
+            arr1(&[0.3f32, -0.7, 1.0]),
+            arr1(&[0.8f32, 2.0, 0.1]),
+        ];
+        let params: Vec<usize> = ["scale", "beta", "mean", "var"]
+            .iter()
+            .zip(params.iter())
+            .map(|(name, p)| {
+                model
+                    .add_const(*name, Tensor::from(p.clone()).into())
+                    .unwrap()
+            })
+            .collect();
+        let bn = model
+            .add_node(
+                "bn".to_string(),
+                Box::new(BatchNorm::new(DataFormat::NCHW, 1e-5, 0.9, true)),
+            )
+            .unwrap();
+        model
+            .add_edge(OutletId::new(conv, 0), InletId::new(bn, 0))
+            .unwrap();
+        for (ix, p) in params.iter().enumerate() {
+            model
+                .add_edge(OutletId::new(*p, 0), InletId::new(bn, ix + 1))
+                .unwrap();
+        }
+        model.set_outputs(&["bn"]).unwrap();
+        model.analyse().unwrap();
+
+        let input: Tensor = Array4::from_shape_fn((1, 2, 5, 5), |(_, c, h, w)| {
+            (((c * 25 + h * 5 + w) * 37) % 11) as f32 / 11.0 - 0.5
+        })
+        .into();
+        let plan = crate::plan::SimplePlan::new(&model).unwrap();
+        let expected = plan.run(tvec!(input.clone())).unwrap();
+
+        let mut fused = model.clone();
+        crate::optim::Reduce(ReductionPhase::Normalize)
+            .pass(&mut fused)
+            .unwrap();
+        assert!(FuseConvBatchNorm.pass(&mut fused).unwrap());
+        let fused = crate::optim::compact(&fused).unwrap();
+        assert!(fused.nodes().iter().all(|n| !n.op_is::<BatchNorm>()));
+        let plan = crate::plan::SimplePlan::new(&fused).unwrap();
+        let found = plan.run(tvec!(input)).unwrap();
+        assert!(found[0].approx_eq(&expected[0], 1e-4));
+    }
+}
@@ -2,20 +2,26 @@ use crate::{Model, TractResult};
 use std::fmt::Debug;
 
 mod compact;
+mod fuse_conv_batch_norm;
 mod prop_const;
 mod push_split_down;
 mod reduce;
+mod remove_identities;
 
-pub use self::compact::compact;
+pub use self::compact::{compact, compact_with_pruned_hints};
+pub use self::fuse_conv_batch_norm::FuseConvBatchNorm;
 pub use self::prop_const::PropConst;
 pub use self::push_split_down::PushSplitDown;
 pub use self::reduce::Reduce;
+pub use self::remove_identities::RemoveIdentities;
 pub use crate::ops::ReductionPhase;
 
 pub fn normalization() -> Vec<Box<OptimizerPass>> {
     vec![
-        Box::new(PropConst) as Box<OptimizerPass>,
+        Box::new(RemoveIdentities) as Box<OptimizerPass>,
+        Box::new(PropConst::default()),
         Box::new(Reduce(ReductionPhase::Normalize)),
+        Box::new(FuseConvBatchNorm),
     ]
 }
 
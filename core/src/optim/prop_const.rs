@@ -2,8 +2,31 @@ use crate::model::{InletId, OutletId};
 use crate::{Model, TractResult};
 use bit_set;
 
+/// Default maximum number of elements of a constant folded by `PropConst`.
+pub const DEFAULT_MAX_ELEMENTS: usize = 1 << 20;
+
+/// Replaces the inputs known to be constant by Const nodes.
+///
+/// Only the outputs of operators opting in through `Op::is_foldable` are
+/// folded, and constants of more than `max_elements` elements are not
+/// materialized: the nodes computing them are left in the model and run at
+/// runtime. The inputs listed by `Op::const_inputs` are folded whatever their
+/// producer and size and, if `require_const_inputs` is set, the pass fails
+/// when one of them is not known.
 #[derive(Debug)]
-pub struct PropConst;
+pub struct PropConst {
+    pub max_elements: usize,
+    pub require_const_inputs: bool,
+}
+
+impl Default for PropConst {
+    fn default() -> PropConst {
+        PropConst {
+            max_elements: DEFAULT_MAX_ELEMENTS,
+            require_const_inputs: false,
+        }
+    }
+}
 
 impl super::OptimizerPass for PropConst {
     fn pass(&self, model: &mut Model) -> TractResult<bool> {
@@ -27,11 +50,31 @@ impl super::OptimizerPass for PropConst {
                 done.insert(node);
             } else {
                 trace!("Looking at node {} inputs", node);
+                let const_inputs = model.nodes()[node].op().const_inputs();
                 for ix in 0..model.nodes()[node].inputs.len() {
                     use crate::analyser::types::Fact;
                     let source = model.nodes()[node].inputs[ix];
+                    let required = const_inputs.contains(&ix);
+                    if self.require_const_inputs && required && !model.fact(source)?.is_concrete() {
+                        bail!(
+                            "{} requires input {} to be constant (node {})",
+                            model.nodes()[node].op().name(),
+                            ix,
+                            model.nodes()[node].name
+                        )
+                    }
                     if model.nodes()[source.node].op().name() != "Const"
                         && model.fact(source)?.is_concrete()
+                        && (required
+                            || model.nodes()[source.node].op().is_foldable()
+                                && model
+                                    .fact(source)?
+                                    .value
+                                    .concretize()
+                                    .map(|v| {
+                                        v.shape().iter().product::<usize>() <= self.max_elements
+                                    })
+                                    .unwrap_or(false))
                     {
                         use crate::model::ModelDsl;
                         let konst = model.fact(source)?.concretize().unwrap();
@@ -57,3 +100,144 @@ impl super::OptimizerPass for PropConst {
         Ok(replaced > 0)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::dsl::*;
+    use crate::ops::array::{AddDims, Gather, Reshape};
+    use crate::ops::math::{Add, Neg};
+    use crate::ops::prelude::*;
+    use crate::optim::OptimizerPass;
+    use ndarray::{arr1, arr2, Array1, Array2};
+
+    #[test]
+    fn large_constants_are_not_folded() {
+        let mut model = Model::default();
+        model
+            .add_source_fact("x", TensorFact::dt_shape(DatumType::F32, vec![1]))
+            .unwrap();
+        model.add_const("small", arr1(&[-1.0f32]).into()).unwrap();
+        let small = model
+            .chain("small_dims", Box::new(AddDims::new(vec![0])))
+            .unwrap();
+        let add_small = model
+            .add_node("add_small".to_string(), Box::new(Add::default()))
+            .unwrap();
+        model
+            .add_edge(OutletId::new(0, 0), InletId::new(add_small, 0))
+            .unwrap();
+        model
+            .add_edge(OutletId::new(small, 0), InletId::new(add_small, 1))
+            .unwrap();
+
+        model
+            .add_const("large", Array1::<f32>::ones(10_000).into())
+            .unwrap();
+        let large = model
+            .chain("large_dims", Box::new(AddDims::new(vec![0])))
+            .unwrap();
+        let add_large = model
+            .add_node("add_large".to_string(), Box::new(Add::default()))
+            .unwrap();
+        model
+            .add_edge(OutletId::new(add_small, 0), InletId::new(add_large, 0))
+            .unwrap();
+        model
+            .add_edge(OutletId::new(large, 0), InletId::new(add_large, 1))
+            .unwrap();
+        model.analyse().unwrap();
+
+        PropConst {
+            max_elements: 1000,
+            ..PropConst::default()
+        }
+        .pass(&mut model)
+        .unwrap();
+        let folded = model.node(add_small).inputs[1].node;
+        assert_eq!(model.node(folded).op().name(), "Const");
+        assert_eq!(model.node(add_large).inputs[1].node, large);
+    }
+
+    fn reshape_model(shape_from_source: bool) -> (Model, usize) {
+        let mut model = Model::default();
+        model
+            .add_source_fact("x", TensorFact::dt_shape(DatumType::F32, vec![6]))
+            .unwrap();
+        let shape = if shape_from_source {
+            model.add_source("shape").unwrap()
+        } else {
+            model.add_const("shape", arr1(&[-2i64, -3]).into()).unwrap();
+            model.chain("neg", Box::new(Neg::default())).unwrap()
+        };
+        let reshape = model
+            .add_node("reshape".to_string(), Box::new(Reshape::default()))
+            .unwrap();
+        model
+            .add_edge(OutletId::new(0, 0), InletId::new(reshape, 0))
+            .unwrap();
+        model
+            .add_edge(OutletId::new(shape, 0), InletId::new(reshape, 1))
+            .unwrap();
+        model
+            .set_outputs_outlets(&[OutletId::new(reshape, 0)])
+            .unwrap();
+        (model, reshape)
+    }
+
+    #[test]
+    fn reshape_shape_is_folded() {
+        let (mut model, reshape) = reshape_model(false);
+        model.analyse().unwrap();
+        let pass = PropConst {
+            max_elements: 0,
+            require_const_inputs: true,
+        };
+        assert!(pass.pass(&mut model).unwrap());
+        let shape = model.node(reshape).inputs[1].node;
+        assert_eq!(model.node(shape).op().name(), "Const");
+    }
+
+    #[test]
+    fn reshape_requires_constant_shape() {
+        let (mut model, _) = reshape_model(true);
+        model.analyse().unwrap();
+        assert!(PropConst::default().pass(&mut model).is_ok());
+        let pass = PropConst {
+            require_const_inputs: true,
+            ..PropConst::default()
+        };
+        let err = pass.pass(&mut model).unwrap_err();
+        assert_eq!(
+            format!("{}", err),
+            "Reshape requires input 1 to be constant (node reshape)"
+        );
+    }
+
+    #[test]
+    fn gather_of_constants_is_folded() {
+        let mut model = Model::default();
+        let table = Array2::from_shape_fn((4, 2), |(i, j)| (i * 2 + j) as f32);
+        model.add_const("table", table.into()).unwrap();
+        let indices = model.add_const("indices", arr1(&[0i64, 2]).into()).unwrap();
+        let gather = model
+            .add_node("gather".to_string(), Box::new(Gather::new(0)))
+            .unwrap();
+        model
+            .add_edge(OutletId::new(0, 0), InletId::new(gather, 0))
+            .unwrap();
+        model
+            .add_edge(OutletId::new(indices, 0), InletId::new(gather, 1))
+            .unwrap();
+        let neg = model.chain("neg", Box::new(Neg::default())).unwrap();
+        model.analyse().unwrap();
+
+        assert!(PropConst::default().pass(&mut model).unwrap());
+        let folded = model.node(neg).inputs[0];
+        assert_eq!(model.node(folded.node).op().name(), "Const");
+        assert_eq!(
+            model.fact(folded).unwrap().concretize().unwrap(),
+            arr2(&[[0.0f32, 1.0], [4.0, 5.0]]).into()
+        );
+    }
+}
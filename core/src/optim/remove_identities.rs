@@ -0,0 +1,74 @@
+use crate::model::{InletId, OutletId};
+use crate::ops::identity::Identity;
+use crate::ops::prelude::*;
+use crate::Model;
+
+#[derive(Debug)]
+pub struct RemoveIdentities;
+
+impl super::OptimizerPass for RemoveIdentities {
+    fn pass(&self, model: &mut Model) -> TractResult<bool> {
+        let mut done_something = false;
+        for id in model.eval_order()? {
+            if !model.node(id).op_is::<Identity>() || model.node(id).inputs.len() != 1 {
+                continue;
+            }
+            let prec = model.node(id).inputs[0];
+            let outlet = OutletId::new(id, 0);
+            trace!("removing identity {} fed by {:?}", id, prec);
+            let fact = model
+                .fact(prec)?
+                .unify(model.fact(outlet)?)
+                .map_err(|e| format!("Removing identity {}: {}", model.node(id).name, e))?;
+            model.set_fact(prec, fact)?;
+            let successors: Vec<InletId> = model.node(id).outputs[0].successors.clone();
+            for succ in successors {
+                model.add_edge(prec, succ)?;
+            }
+            model.clear_inputs(id)?;
+            for output in model.outputs.iter_mut() {
+                if *output == outlet {
+                    *output = prec;
+                }
+            }
+            if cfg!(debug_assertions) {
+                model.check_edges()?;
+            }
+            done_something = true;
+        }
+        Ok(done_something)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::dsl::ModelDsl;
+    use ndarray::arr1;
+
+    #[test]
+    fn identities_are_removed() {
+        let mut model = Model::default();
+        model
+            .add_source_fact("a", TensorFact::dt_shape(DatumType::F32, vec![3]))
+            .unwrap();
+        model.chain("id1", Box::new(Identity::default())).unwrap();
+        model
+            .chain("neg", Box::new(crate::ops::math::Neg::default()))
+            .unwrap();
+        model.chain("id2", Box::new(Identity::default())).unwrap();
+        let input: Tensor = arr1(&[1.0f32, -2.0, 3.0]).into();
+        let plan = crate::plan::SimplePlan::new(&model).unwrap();
+        let expected = plan.run(tvec!(input.clone())).unwrap();
+
+        let optimized = model.into_optimized().unwrap();
+        assert_eq!(optimized.nodes().len(), 2);
+        assert!(optimized.nodes().iter().all(|n| !n.op_is::<Identity>()));
+        assert_eq!(
+            optimized.output_fact().unwrap(),
+            &TensorFact::dt_shape(DatumType::F32, vec![3])
+        );
+        let plan = crate::plan::SimplePlan::new(&optimized).unwrap();
+        assert_eq!(plan.run(tvec!(input)).unwrap(), expected);
+    }
+}
@@ -1,7 +1,7 @@
 use std::borrow::Borrow;
 use std::marker::PhantomData;
 
-use crate::model::{eval_order, Model, Node};
+use crate::model::{memory_aware_eval_order, Model, Node};
 use crate::ops::prelude::*;
 
 #[derive(Debug, Clone)]
@@ -13,13 +13,16 @@ pub struct SimplePlan<M: Borrow<Model>> {
 
 impl<M: Borrow<Model>> SimplePlan<M> {
     pub fn new(model: M) -> TractResult<SimplePlan<M>> {
-        let order = eval_order(model.borrow())?;
+        let order = memory_aware_eval_order(model.borrow())?;
         let mut values_needed_until_step = vec![0; model.borrow().nodes().len()];
         for step in 0..order.len() {
             for i in &model.borrow().node(order[step]).inputs {
                 values_needed_until_step[i.node] = step;
             }
         }
+        for output in model.borrow().outputs()? {
+            values_needed_until_step[output.node] = 0;
+        }
         let mut flush_lists: Vec<TVec<usize>> = vec![tvec!(); order.len()];
         for (node, &flush_at) in values_needed_until_step.iter().enumerate() {
             if flush_at != 0 {
@@ -72,13 +75,7 @@ impl<M: Borrow<Model>, P: Borrow<SimplePlan<M>> + Clone> Clone for SimpleState<M
 impl<M: Borrow<Model>, P: Borrow<SimplePlan<M>>> SimpleState<M, P> {
     pub fn new(plan: P) -> TractResult<SimpleState<M, P>> {
         let values = vec![None; plan.borrow().model.borrow().nodes().len()];
-        let states = plan
-            .borrow()
-            .model()
-            .nodes()
-            .iter()
-            .map(|n| n.op().state())
-            .collect::<TractResult<_>>()?;
+        let states = Self::op_states(plan.borrow().model())?;
         Ok(SimpleState {
             states,
             plan,
@@ -95,15 +92,31 @@ impl<M: Borrow<Model>, P: Borrow<SimplePlan<M>>> SimpleState<M, P> {
 
     /// Reset wires state.
     pub fn reset_op_states(&mut self) -> TractResult<()> {
-        self.states = self
-            .plan
-            .borrow()
-            .model()
+        self.states = Self::op_states(self.plan.borrow().model())?;
+        Ok(())
+    }
+
+    fn op_states(model: &Model) -> TractResult<Vec<Option<Box<OpState>>>> {
+        model
             .nodes()
             .iter()
-            .map(|n| n.op().state())
-            .collect::<TractResult<_>>()?;
-        Ok(())
+            .map(|n| {
+                let state = n.op().state()?;
+                debug_assert!(
+                    n.op().is_stateless() == state.is_none(),
+                    "{} ({}) claims to be {}, but {} a state",
+                    n.name,
+                    n.op().name(),
+                    if n.op().is_stateless() {
+                        "stateless"
+                    } else {
+                        "stateful"
+                    },
+                    if state.is_some() { "has" } else { "has no" },
+                );
+                Ok(state)
+            })
+            .collect()
     }
 
     pub fn run(&mut self, inputs: TVec<Tensor>) -> TractResult<TVec<SharedTensor>> {
@@ -296,3 +309,85 @@ impl<M: Borrow<Model>, P: Borrow<SimplePlan<M>>> SimpleState<M, P> {
         self.plan().model()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::dsl::ModelDsl;
+
+    #[derive(Debug, Clone, Default)]
+    struct Counter {
+        declared_stateless: bool,
+    }
+
+    impl Op for Counter {
+        fn name(&self) -> Cow<str> {
+            "Counter".into()
+        }
+
+        fn is_stateless(&self) -> bool {
+            self.declared_stateless
+        }
+    }
+
+    impl StatefullOp for Counter {
+        fn state(&self) -> TractResult<Option<Box<OpState>>> {
+            Ok(Some(Box::new(CounterState::default())))
+        }
+    }
+
+    impl InferenceRulesOp for Counter {
+        fn rules<'r, 'p: 'r, 's: 'r>(
+            &'s self,
+            _s: &mut Solver<'r>,
+            _inputs: &'p SharedTensorsProxy,
+            _outputs: &'p SharedTensorsProxy,
+        ) -> InferenceResult {
+            Ok(())
+        }
+    }
+
+    #[derive(Debug, Clone, Default)]
+    struct CounterState(i32);
+
+    impl OpState for CounterState {
+        fn eval(
+            &mut self,
+            _op: &Op,
+            _inputs: TVec<SharedTensor>,
+        ) -> TractResult<TVec<SharedTensor>> {
+            self.0 += 1;
+            Ok(tvec!(Tensor::from(self.0).into()))
+        }
+    }
+
+    fn counter_model(declared_stateless: bool) -> Model {
+        let mut model = Model::default();
+        model.add_source("a").unwrap();
+        model
+            .chain("counter", Box::new(Counter { declared_stateless }))
+            .unwrap();
+        model
+    }
+
+    #[test]
+    fn stateful_op_runs_with_its_state() {
+        let model = counter_model(false);
+        assert!(!model.node(1).op().is_stateless());
+        let plan = SimplePlan::new(&model).unwrap();
+        let mut state = SimpleState::new(&plan).unwrap();
+        for i in 1..3 {
+            let outputs = state.run(tvec!(Tensor::from(0i32))).unwrap();
+            assert_eq!(outputs[0], Tensor::from(i).into());
+        }
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "claims to be stateless, but has a state")]
+    fn stateful_op_claiming_to_be_stateless_is_caught() {
+        let model = counter_model(true);
+        let plan = SimplePlan::new(&model).unwrap();
+        let _ = SimpleState::new(&plan);
+    }
+}
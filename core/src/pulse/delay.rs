@@ -74,6 +74,10 @@ impl Op for Delay {
         "Delay".into()
     }
 
+    fn is_stateless(&self) -> bool {
+        false
+    }
+
     impl_op_same_as!();
 }
 
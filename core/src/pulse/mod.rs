@@ -54,6 +54,38 @@ impl PulsedTensorFact {
         self.shape[self.axis]
     }
 
+    /// The full, streaming, dimension of the stream axis.
+    pub fn stream_dim(&self) -> &TDim {
+        &self.dim
+    }
+
+    pub fn is_stream_axis(&self, ax: usize) -> bool {
+        ax == self.axis
+    }
+
+    /// Checks that the stream axis exists, that pulses are not empty, and
+    /// that the delay has not consumed the whole stream.
+    pub fn validate(&self) -> TractResult<()> {
+        if self.axis >= self.shape.len() {
+            bail!(
+                "Stream axis {} is out of bounds for pulsed shape {:?}",
+                self.axis,
+                self.shape
+            )
+        }
+        if self.pulse() == 0 {
+            bail!("Pulse on stream axis {} is empty", self.axis)
+        }
+        if self.dim.as_const().map(|d| d < 0).unwrap_or(false) {
+            bail!(
+                "Stream dimension {:?} is negative (delay {})",
+                self.dim,
+                self.delay
+            )
+        }
+        Ok(())
+    }
+
     pub fn to_pulse_fact(&self) -> TensorFact {
         TensorFact::dt_shape(self.dt, self.shape.clone())
     }
@@ -263,4 +295,43 @@ mod tests {
                 .unwrap()
         );
     }
+
+    #[test]
+    fn test_pulsify_all_conv_slice() {
+        use crate::analyser::Analyser;
+        use crate::ops::array::Slice;
+        use crate::ops::nn::*;
+        use ndarray::*;
+
+        let mut model = Model::default();
+        let ker = model
+            .add_const("kernel", arr3(&[[[0.5f32, 1.0, -0.1]]]).into())
+            .unwrap();
+        let _ = model
+            .add_source_fact("a", TensorFact::shape(shapefact!(1, 1, S))) // NCT
+            .unwrap();
+        let conv = model.chain("conv", Box::new(Conv::default())).unwrap();
+        model
+            .add_edge(OutletId::new(ker, 0), InletId::new(conv, 1))
+            .unwrap();
+        model
+            .chain("slice", Box::new(Slice::new(vec![(0, 0), (0, 0), (1, 0)])))
+            .unwrap();
+        model.analyse().unwrap();
+        let mut model = model.into_optimized().unwrap();
+
+        let pulsed = Analyser::new(&mut model)
+            .unwrap()
+            .pulsify_all(2, 4)
+            .unwrap();
+        assert_eq!(pulsed.len(), 4); // source - delay - conv - slice
+        let last = &pulsed.last().unwrap().outputs[0];
+        assert_eq!(last.shape, tvec!(1, 1, 4));
+        assert_eq!(last.delay, 3);
+
+        assert!(Analyser::new(&mut model)
+            .unwrap()
+            .pulsify_all(1, 4)
+            .is_err());
+    }
 }
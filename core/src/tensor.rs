@@ -126,6 +126,17 @@ impl Tensor {
         })
     }
 
+    /// Builds a tensor of type `dt` by evaluating `f` at each index of
+    /// `shape`, casting the values from f64.
+    pub fn from_shape_and_fn<F: Fn(&[usize]) -> f64>(
+        dt: DatumType,
+        shape: &[usize],
+        f: F,
+    ) -> TractResult<Tensor> {
+        let values = ArrayD::from_shape_fn(shape, |ix| f(ix.slice()));
+        Ok(Tensor::from(values).cast_to_dt(dt)?.into_owned())
+    }
+
     pub fn into_aligned(self, alignment: usize) -> TractResult<Tensor> {
         Ok(Tensor {
             null: self.null,
@@ -173,6 +184,12 @@ impl Tensor {
         self.dt
     }
 
+    /// Size of the tensor elements, in bytes. Heap data owned by String
+    /// elements is not accounted for.
+    pub fn byte_size(&self) -> usize {
+        self.shape.iter().product::<usize>() * self.dt.size_of()
+    }
+
     pub fn dump_t<D: Datum>(&self, force_full: bool) -> TractResult<String> {
         use itertools::Itertools;
         let s = if self.shape.len() == 0 {
@@ -197,17 +214,75 @@ impl Tensor {
         Ok(s)
     }
 
+    fn dump_values_t<D: Datum>(&self, max: usize) -> TractResult<String> {
+        use itertools::Itertools;
+        let data = self.to_array_view::<D>()?;
+        let mut values = data.iter().take(max).map(|v| v.format_elem()).join(", ");
+        if data.len() > max {
+            values.push_str(", ...");
+        }
+        Ok(format!("[{}]", values))
+    }
+
+    fn try_dump_values(&self, max: usize) -> TractResult<String> {
+        dispatch_datum!(Self::dump_values_t(self.dt)(self, max))
+    }
+
+    /// Renders up to `max` elements of the tensor, in logical order.
+    pub fn dump_values(&self, max: usize) -> String {
+        self.try_dump_values(max)
+            .unwrap_or_else(|e| format!("Error : {:?}", e))
+    }
+
     pub fn dump(&self, force_full: bool) -> TractResult<String> {
         dispatch_datum!(Self::dump_t(self.dt)(self, force_full))
     }
 
+    fn broadcast_to_t<D: Datum>(&self, target: &[usize]) -> TractResult<Tensor> {
+        let view = self.to_array_view::<D>()?;
+        let broadcast = view.broadcast(target).ok_or_else(|| {
+            format!(
+                "Can not broadcast tensor of shape {:?} to {:?}",
+                self.shape, target
+            )
+        })?;
+        Ok(broadcast.to_owned().into())
+    }
+
+    /// Broadcasts the tensor to the `target` shape, following the numpy
+    /// rules: missing leading axes are added, and each axis must either
+    /// match the target one or be 1.
+    pub fn broadcast_to(&self, target: &[usize]) -> TractResult<Tensor> {
+        dispatch_datum!(Self::broadcast_to_t(self.dt)(self, target))
+    }
+
+    /// Compares two tensors, allowing float elements to differ by up to
+    /// `tolerance`. Other datum types are compared exactly.
+    pub fn approx_eq(&self, other: &Self, tolerance: f32) -> bool {
+        if self.datum_type() != other.datum_type() || self.shape() != other.shape() {
+            return false;
+        }
+        if !self.datum_type().is_float() {
+            return self == other;
+        }
+        match (self.to_f32(), other.to_f32()) {
+            (Ok(a), Ok(b)) => a
+                .as_slice::<f32>()
+                .unwrap()
+                .iter()
+                .zip(b.as_slice::<f32>().unwrap())
+                .all(|(a, b)| a == b || (a - b).abs() <= tolerance),
+            _ => false,
+        }
+    }
+
     pub fn close_enough(&self, other: &Self, approx: bool) -> bool {
         if self.is_null() != other.is_null() {
             return false;
         }
-        let ma = self.cast_to::<f32>().unwrap();
+        let ma = self.to_f32().unwrap();
         let ma = ma.to_array_view::<f32>().unwrap();
-        let mb = other.cast_to::<f32>().unwrap();
+        let mb = other.to_f32().unwrap();
         let mb = mb.to_array_view::<f32>().unwrap();
         let avg = ma
             .iter()
@@ -243,10 +318,22 @@ impl Tensor {
                 .all(|t| t.2)
     }
 
-    pub fn into_array<D: Datum>(self) -> TractResult<ArrayD<D>> {
+    fn check_for_access<D: Datum>(&self) -> TractResult<()> {
         if self.is_null() {
             bail!("Null tensor")
         }
+        if D::datum_type() != self.datum_type() {
+            bail!(
+                "Incompatible datum type. Required {:?}, got {:?}",
+                D::datum_type(),
+                self.datum_type()
+            );
+        }
+        Ok(())
+    }
+
+    pub fn into_array<D: Datum>(self) -> TractResult<ArrayD<D>> {
+        self.check_for_access::<D>()?;
         let casted = unsafe { vec_to_datum::<D>(self.data) };
         unsafe { Ok(ArrayD::from_shape_vec_unchecked(&*self.shape, casted)) }
     }
@@ -272,10 +359,7 @@ impl Tensor {
     }
 
     pub fn to_array_view<'a, D: Datum>(&'a self) -> TractResult<ArrayViewD<'a, D>> {
-        assert_eq!(D::datum_type(), self.datum_type());
-        if self.is_null() {
-            bail!("Null tensor")
-        }
+        self.check_for_access::<D>()?;
         if self.data.len() != 0 {
             unsafe {
                 return Ok(ArrayViewD::from_shape_ptr(
@@ -309,10 +393,7 @@ impl Tensor {
     }
 
     pub fn to_array_view_mut<'a, D: Datum>(&'a mut self) -> TractResult<ArrayViewMutD<'a, D>> {
-        assert_eq!(D::datum_type(), self.datum_type());
-        if self.is_null() {
-            bail!("Null tensor")
-        }
+        self.check_for_access::<D>()?;
         let shape = self.shape.clone();
         if self.data.len() != 0 {
             unsafe {
@@ -357,6 +438,11 @@ impl Tensor {
         self.cast_to_dt(D::datum_type())
     }
 
+    /// Upcasts a half, single or double precision tensor to single precision.
+    pub fn to_f32(&self) -> TractResult<Cow<Tensor>> {
+        self.cast_to::<f32>()
+    }
+
     pub fn cast_to_dt(&self, dt: DatumType) -> TractResult<Cow<Tensor>> {
         use crate::DatumType::*;
         if self.dt == dt {
@@ -391,6 +477,26 @@ impl Tensor {
             (I16, F32) => self.cast::<i16, f32>()?,
             (I32, F32) => self.cast::<i32, f32>()?,
             (I64, F32) => self.cast::<i64, f32>()?,
+            (U8, F32) => self.cast::<u8, f32>()?,
+            (U16, F32) => self.cast::<u16, f32>()?,
+
+            (U8, I32) => self.cast::<u8, i32>()?,
+            (U16, I32) => self.cast::<u16, i32>()?,
+            (U8, I64) => self.cast::<u8, i64>()?,
+            (U16, I64) => self.cast::<u16, i64>()?,
+
+            (F32, I8) => self.cast::<f32, i8>()?,
+            (F32, I16) => self.cast::<f32, i16>()?,
+            (F32, I32) => self.cast::<f32, i32>()?,
+            (F32, I64) => self.cast::<f32, i64>()?,
+            (F32, U8) => self.cast::<f32, u8>()?,
+            (F32, U16) => self.cast::<f32, u16>()?,
+            (F64, I8) => self.cast::<f64, i8>()?,
+            (F64, I16) => self.cast::<f64, i16>()?,
+            (F64, I32) => self.cast::<f64, i32>()?,
+            (F64, I64) => self.cast::<f64, i64>()?,
+            (F64, U8) => self.cast::<f64, u8>()?,
+            (F64, U16) => self.cast::<f64, u16>()?,
             _ => bail!("Unsupported cast from {:?} to {:?}", self.dt, dt),
         };
         Ok(Cow::Owned(target))
@@ -432,35 +538,58 @@ impl Serialize for Tensor {
     where
         S: Serializer,
     {
+        use serde::ser::Error;
         macro_rules! serialize_inner {
-            ($type:ident, $m:ident) => {{
+            ($type:ty) => {{
                 let data = (
-                    stringify!($type),
+                    format!("{:?}", self.datum_type()),
                     self.shape(),
-                    $m.iter().cloned().collect::<Vec<_>>(),
+                    self.as_slice::<$type>()
+                        .map_err(|e| S::Error::custom(format!("{:?}", e)))?,
                 );
                 data.serialize(serializer)
             }};
         };
 
-        use Tensor::*;
-        match self {
-            Bool(m) => serialize_inner!(bool, m),
-            U8(m) => serialize_inner!(u8, m),
-            U16(m) => serialize_inner!(u16, m),
-            I8(m) => serialize_inner!(i8, m),
-            I16(m) => serialize_inner!(i16, m),
-            I32(m) => serialize_inner!(i32, m),
-            I64(m) => serialize_inner!(i64, m),
-            F16(m) => serialize_inner!(f16, m),
-            F32(m) => serialize_inner!(f32, m),
-            F64(m) => serialize_inner!(f64, m),
-            TDim(m) => serialize_inner!(TDim, m),
-            String(m) => serialize_inner!(str, m),
+        match self.datum_type() {
+            DatumType::Bool => serialize_inner!(bool),
+            DatumType::U8 => serialize_inner!(u8),
+            DatumType::U16 => serialize_inner!(u16),
+            DatumType::I8 => serialize_inner!(i8),
+            DatumType::I16 => serialize_inner!(i16),
+            DatumType::I32 => serialize_inner!(i32),
+            DatumType::I64 => serialize_inner!(i64),
+            DatumType::F16 => {
+                let tensor = self
+                    .cast_to::<f32>()
+                    .map_err(|e| S::Error::custom(format!("{:?}", e)))?;
+                let data = (
+                    format!("{:?}", self.datum_type()),
+                    self.shape(),
+                    tensor
+                        .as_slice::<f32>()
+                        .map_err(|e| S::Error::custom(format!("{:?}", e)))?,
+                );
+                data.serialize(serializer)
+            }
+            DatumType::F32 => serialize_inner!(f32),
+            DatumType::F64 => serialize_inner!(f64),
+            DatumType::TDim => serialize_inner!(TDim),
+            DatumType::String => Err(S::Error::custom("String tensors can not be serialized")),
         }
     }
 }
 
+#[cfg(feature = "serialize")]
+impl Serialize for SharedTensor {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.as_tensor().serialize(serializer)
+    }
+}
+
 fn vec_to_u8<T: Datum>(mut data: Vec<T>) -> Vec<u8> {
     let v = unsafe {
         Vec::from_raw_parts(
@@ -530,3 +659,39 @@ where
         ArrayBase::from_shape_vec_unchecked(dim, v)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn byte_size() {
+        let t = Tensor::from(arr2(&[[1f32, 2., 3.], [4., 5., 6.]]));
+        assert_eq!(t.byte_size(), 24);
+        assert_eq!(Tensor::from(arr1(&[1i8, 2])).byte_size(), 2);
+    }
+
+    #[test]
+    fn from_shape_and_fn() {
+        let t = Tensor::from_shape_and_fn(DatumType::I32, &[2, 3], |ix| (ix[0] * 3 + ix[1]) as f64)
+            .unwrap();
+        assert_eq!(t, Tensor::from(arr2(&[[0i32, 1, 2], [3, 4, 5]])));
+        let t = Tensor::from_shape_and_fn(DatumType::F32, &[2], |ix| ix[0] as f64 / 2.0).unwrap();
+        assert_eq!(t, Tensor::from(arr1(&[0.0f32, 0.5])));
+    }
+
+    #[test]
+    fn broadcast_to() {
+        let t = Tensor::from(arr2(&[[1i32, 2, 3]]));
+        assert_eq!(
+            t.broadcast_to(&[4, 3]).unwrap(),
+            Tensor::from(arr2(&[[1i32, 2, 3], [1, 2, 3], [1, 2, 3], [1, 2, 3]]))
+        );
+    }
+
+    #[test]
+    fn broadcast_to_incompatible() {
+        let t = Tensor::from(arr2(&[[1.0f32, 2.0, 3.0], [4.0, 5.0, 6.0]]));
+        assert!(t.broadcast_to(&[4, 3]).is_err());
+    }
+}
@@ -31,15 +31,77 @@ fn mat_mul_prepacked(c: &mut Criterion, m: usize, k: usize, n: usize) {
     });
 }
 
+fn mat_mul_repacking_b(c: &mut Criterion, m: usize, k: usize, n: usize) {
+    c.bench_function(
+        &format!("mat_mul_repacking_b_{}x{}x{}", m, k, n),
+        move |be| {
+            let mm = (tract_linalg::ops().smm)(m, k, n);
+            let a = vec![0.0; m * k];
+            let b = vec![0.0; n * k];
+            let mut pb = unsafe {
+                tract_linalg::align::uninitialized(mm.packed_b_len(), mm.packed_b_alignment())
+            };
+            let mut c = vec![0.0; m * n];
+            be.iter(move || {
+                mm.pack_b(pb.as_mut_ptr(), b.as_ptr(), n as _, 1);
+                mm.mat_mul_with_prepacked_b(
+                    a.as_ptr(),
+                    k as _,
+                    1,
+                    pb.as_ptr(),
+                    c.as_mut_ptr(),
+                    n as _,
+                    1,
+                )
+            })
+        },
+    );
+}
+
+fn mat_mul_with_prepacked_b(c: &mut Criterion, m: usize, k: usize, n: usize) {
+    c.bench_function(
+        &format!("mat_mul_with_prepacked_b_{}x{}x{}", m, k, n),
+        move |be| {
+            let mm = (tract_linalg::ops().smm)(m, k, n);
+            let a = vec![0.0; m * k];
+            let b = vec![0.0; n * k];
+            let mut pb = unsafe {
+                tract_linalg::align::uninitialized(mm.packed_b_len(), mm.packed_b_alignment())
+            };
+            mm.pack_b(pb.as_mut_ptr(), b.as_ptr(), n as _, 1);
+            let mut c = vec![0.0; m * n];
+            be.iter(move || {
+                mm.mat_mul_with_prepacked_b(
+                    a.as_ptr(),
+                    k as _,
+                    1,
+                    pb.as_ptr(),
+                    c.as_mut_ptr(),
+                    n as _,
+                    1,
+                )
+            })
+        },
+    );
+}
+
 fn simple(c: &mut Criterion, m: usize, k: usize, n: usize) {
     pack_a(c, m, k, n);
     pack_b(c, m, k, n);
     mat_mul_prepacked(c, m, k, n);
+    mat_mul_repacking_b(c, m, k, n);
+    mat_mul_with_prepacked_b(c, m, k, n);
 }
 
 fn s16x60x8(c: &mut Criterion) {
     simple(c, 16, 60, 8)
 }
 
-criterion_group!(benches, s16x60x8);
+fn s1024x1024x1024(c: &mut Criterion) {
+    pack_a(c, 1024, 1024, 1024);
+    pack_b(c, 1024, 1024, 1024);
+    mat_mul_repacking_b(c, 1024, 1024, 1024);
+}
+
+criterion_group!(benches, s16x60x8, s1024x1024x1024);
 criterion_main!(benches);
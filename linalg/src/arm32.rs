@@ -3,7 +3,7 @@ mod armvfpv2;
 mod armv7neon;
 
 use crate::Ops;
-use crate::frame::PackedMatMul;
+use crate::frame::{PackedMatMul, PackedQMatMul};
 
 fn has_neon_cpuinfo() -> std::io::Result<bool>  {
     let cpu_info = fs::read_to_string("/proc/cpuinfo")?;
@@ -18,11 +18,30 @@ fn has_neon() -> bool {
     has_neon_cpuinfo().unwrap_or(false)
 }
 
+fn neon_kernel() -> &'static str {
+    match env::var("TRACT_CPU_ARM32_KERNEL").as_ref().map(|s| &**s) {
+        Ok("12x4") => "12x4",
+        _ => "8x4",
+    }
+}
+
 pub fn plug(ops: &mut Ops) {
+    // armv7 neon has no double precision lanes: dmm stays on the generic kernel
     if has_neon() {
-        ops.smm = Box::new(|m, k, n| {
-            log::info!("armv7neon activated for smm");
-            Box::new(PackedMatMul::<armv7neon::SMatMul8x4, f32>::new(m, k, n))
+        if neon_kernel() == "12x4" {
+            ops.smm = Box::new(|m, k, n| {
+                log::info!("armv7neon (12x4) activated for smm");
+                Box::new(PackedMatMul::<armv7neon::SMatMul12x4, f32>::new(m, k, n))
+            });
+        } else {
+            ops.smm = Box::new(|m, k, n| {
+                log::info!("armv7neon (8x4) activated for smm");
+                Box::new(PackedMatMul::<armv7neon::SMatMul8x4, f32>::new(m, k, n))
+            });
+        }
+        ops.qmm = Box::new(|m, k, n| {
+            log::info!("armv7neon activated for qmm");
+            Box::new(PackedQMatMul::<armv7neon::QMatMul8x4>::new(m, k, n))
         });
     } else {
         ops.smm = Box::new(|m, k, n| {
@@ -10,6 +10,22 @@ extern "C" {
         rsc: size_t,
         csc: size_t,
     );
+    fn armv7neon_mm_s12x4(
+        k: size_t,
+        a: *const f32,
+        b: *const f32,
+        c: *mut f32,
+        rsc: size_t,
+        csc: size_t,
+    );
+    fn armv7neon_mm_u8_8x4(
+        k: size_t,
+        a: *const u8,
+        b: *const u8,
+        c: *mut i32,
+        rsc: size_t,
+        csc: size_t,
+    );
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -40,12 +56,69 @@ impl frame::matmul::PackedMatMulKer<f32> for SMatMul8x4 {
     }
 }
 
+#[derive(Copy, Clone, Debug)]
+pub struct SMatMul12x4;
+
+impl frame::matmul::PackedMatMulKer<f32> for SMatMul12x4 {
+    #[inline(always)]
+    fn name() -> &'static str {
+        "armv7neon"
+    }
+    #[inline(always)]
+    fn mr() -> usize {
+        12
+    }
+    #[inline(always)]
+    fn nr() -> usize {
+        4
+    }
+    fn alignment_bytes_a() -> usize {
+        4
+    }
+    fn alignment_bytes_b() -> usize {
+        4
+    }
+    #[inline(never)]
+    fn kernel(k: usize, a: *const f32, b: *const f32, c: *mut f32, rsc: usize, csc: usize) {
+        unsafe { armv7neon_mm_s12x4(k, a, b, c, rsc, csc) }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct QMatMul8x4;
+
+impl frame::qmatmul::PackedQMatMulKer for QMatMul8x4 {
+    #[inline(always)]
+    fn name() -> &'static str {
+        "armv7neon"
+    }
+    #[inline(always)]
+    fn mr() -> usize {
+        8
+    }
+    #[inline(always)]
+    fn nr() -> usize {
+        4
+    }
+    fn alignment_bytes_a() -> usize {
+        4
+    }
+    fn alignment_bytes_b() -> usize {
+        4
+    }
+    #[inline(never)]
+    fn kernel(k: usize, a: *const u8, b: *const u8, c: *mut i32, rsc: usize, csc: usize) {
+        unsafe { armv7neon_mm_u8_8x4(k, a, b, c, rsc, csc) }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
     use crate::arm32::has_neon;
     use crate::frame::matmul::test::*;
-    use crate::frame::PackedMatMul;
+    use crate::frame::qmatmul::test::*;
+    use crate::frame::{PackedMatMul, PackedQMatMul};
     use proptest::*;
 
     proptest! {
@@ -65,5 +138,44 @@ mod test {
             let mm = PackedMatMul::<SMatMul8x4, f32>::new(m, k, n);
             test_mat_mul_prep_f32(mm, m, k, n, a, b)?
         }
+
+        #[test]
+        fn ker_mat_mul_12x4((k, ref a, ref b) in strat_ker_mat_mul::<SMatMul12x4>()) {
+            if !has_neon() {
+                return Ok(())
+            }
+            test_ker_mat_mul::<SMatMul12x4>(k, a, b)?
+        }
+
+        #[test]
+        fn mat_mul_prepacked_12x4((m, k, n, ref a, ref b) in strat_mat_mul()) {
+            if !has_neon() {
+                return Ok(())
+            }
+            let mm = PackedMatMul::<SMatMul12x4, f32>::new(m, k, n);
+            test_mat_mul_prep_f32(mm, m, k, n, a, b)?
+        }
+
+        #[test]
+        fn qmat_mul_prepacked((m, k, n, ref a, ref b, za, zb) in strat_qmat_mul()) {
+            if !has_neon() {
+                return Ok(())
+            }
+            let mm = PackedQMatMul::<QMatMul8x4>::new(m, k, n);
+            test_qmat_mul_prep(mm, m, k, n, a, b, za, zb)?
+        }
+    }
+
+    #[test]
+    fn mat_mul_12x4_odd_sizes() {
+        if !has_neon() {
+            return;
+        }
+        for &(m, k, n) in &[(1, 1, 1), (13, 5, 3), (25, 7, 9), (35, 1, 5)] {
+            let a: Vec<f32> = (0..m * k).map(|i| (i % 7) as f32 - 3.0).collect();
+            let b: Vec<f32> = (0..k * n).map(|i| 5.0 - (i % 11) as f32).collect();
+            let mm = PackedMatMul::<SMatMul12x4, f32>::new(m, k, n);
+            test_mat_mul_prep_f32(mm, m, k, n, &a, &b).unwrap();
+        }
     }
 }
@@ -0,0 +1,89 @@
+use std::{env, fs};
+mod arm64simd;
+
+use crate::frame::PackedMatMul;
+use crate::Ops;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Kernel {
+    Generic,
+    SMatMul8x8,
+    SMatMul12x4,
+}
+
+fn parse_kernel(name: &str) -> Option<Kernel> {
+    match name {
+        "generic" => Some(Kernel::Generic),
+        "8x8" => Some(Kernel::SMatMul8x8),
+        "12x4" => Some(Kernel::SMatMul12x4),
+        _ => None,
+    }
+}
+
+fn has_asimd_cpuinfo() -> std::io::Result<bool> {
+    let cpu_info = fs::read_to_string("/proc/cpuinfo")?;
+    let asimd = cpu_info.split("\n").any(|line| line.starts_with("Features") && line.contains("asimd"));
+    Ok(asimd)
+}
+
+fn has_asimd() -> bool {
+    if let Ok(v) = env::var("TRACT_CPU_ARM64_ASIMD") {
+        return v == "true"
+    }
+    // asimd is mandatory on aarch64, cpuinfo may just not be there (macOS, iOS)
+    has_asimd_cpuinfo().unwrap_or(true)
+}
+
+fn kernel() -> Kernel {
+    if let Ok(v) = env::var("TRACT_CPU_ARM64_KERNEL") {
+        if let Some(k) = parse_kernel(&v) {
+            return k
+        }
+        log::warn!("Ignoring unknown TRACT_CPU_ARM64_KERNEL value: {}", v);
+    }
+    if has_asimd() {
+        Kernel::SMatMul8x8
+    } else {
+        Kernel::Generic
+    }
+}
+
+pub fn plug(ops: &mut Ops) {
+    match kernel() {
+        Kernel::Generic => (),
+        Kernel::SMatMul8x8 => {
+            ops.smm = Box::new(|m, k, n| {
+                log::info!("arm64simd 8x8 activated for smm");
+                Box::new(PackedMatMul::<arm64simd::SMatMul8x8, f32>::new(m, k, n))
+            });
+        }
+        Kernel::SMatMul12x4 => {
+            ops.smm = Box::new(|m, k, n| {
+                log::info!("arm64simd 12x4 activated for smm");
+                Box::new(PackedMatMul::<arm64simd::SMatMul12x4, f32>::new(m, k, n))
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn may_have_asimd() {
+        if let Ok(asimd) = env::var("TRACT_CPU_EXPECT_ARM64_ASIMD") {
+            assert_eq!(asimd == "true", has_asimd());
+        } else {
+            println!("Has asimd ? {:?}", has_asimd());
+        }
+    }
+
+    #[test]
+    fn kernel_override() {
+        assert_eq!(parse_kernel("generic"), Some(Kernel::Generic));
+        assert_eq!(parse_kernel("8x8"), Some(Kernel::SMatMul8x8));
+        assert_eq!(parse_kernel("12x4"), Some(Kernel::SMatMul12x4));
+        assert_eq!(parse_kernel("neon"), None);
+    }
+}
@@ -0,0 +1,88 @@
+use crate::frame;
+
+// Plain rust kernels: aarch64 always has asimd, so the fixed-size loops below
+// get vectorized by the compiler.
+macro_rules! simd_kernel {
+    ($name:ident, $mr:expr, $nr:expr) => {
+        #[derive(Copy, Clone, Debug)]
+        pub struct $name;
+
+        impl frame::matmul::PackedMatMulKer<f32> for $name {
+            #[inline(always)]
+            fn name() -> &'static str {
+                "arm64simd"
+            }
+            #[inline(always)]
+            fn mr() -> usize {
+                $mr
+            }
+            #[inline(always)]
+            fn nr() -> usize {
+                $nr
+            }
+            #[inline(always)]
+            fn alignment_bytes_a() -> usize {
+                16
+            }
+            #[inline(always)]
+            fn alignment_bytes_b() -> usize {
+                16
+            }
+            #[inline(never)]
+            fn kernel(k: usize, a: *const f32, b: *const f32, c: *mut f32, rsc: usize, csc: usize) {
+                unsafe {
+                    let mut ab = [[0.0f32; $nr]; $mr];
+                    for i in 0..k {
+                        let a = std::slice::from_raw_parts(a.offset(($mr * i) as isize), $mr);
+                        let b = std::slice::from_raw_parts(b.offset(($nr * i) as isize), $nr);
+                        for y in 0..$mr {
+                            for x in 0..$nr {
+                                ab[y][x] += a[y] * b[x];
+                            }
+                        }
+                    }
+                    for y in 0..$mr {
+                        for x in 0..$nr {
+                            *c.offset((y * rsc + x * csc) as isize) = ab[y][x];
+                        }
+                    }
+                }
+            }
+        }
+    };
+}
+
+simd_kernel!(SMatMul8x8, 8, 8);
+simd_kernel!(SMatMul12x4, 12, 4);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::frame::matmul::test::*;
+    use crate::frame::PackedMatMul;
+    use proptest::*;
+
+    proptest! {
+        #[test]
+        fn ker_mat_mul_8x8((k, ref a, ref b) in strat_ker_mat_mul::<SMatMul8x8>()) {
+            test_ker_mat_mul::<SMatMul8x8>(k, a, b)?
+        }
+
+        #[test]
+        fn mat_mul_prepacked_8x8((m, k, n, ref a, ref b) in strat_mat_mul()) {
+            let mm = PackedMatMul::<SMatMul8x8, f32>::new(m, k, n);
+            test_mat_mul_prep_f32(mm, m, k, n, a, b)?
+        }
+
+        #[test]
+        fn ker_mat_mul_12x4((k, ref a, ref b) in strat_ker_mat_mul::<SMatMul12x4>()) {
+            test_ker_mat_mul::<SMatMul12x4>(k, a, b)?
+        }
+
+        #[test]
+        fn mat_mul_prepacked_12x4((m, k, n, ref a, ref b) in strat_mat_mul()) {
+            let mm = PackedMatMul::<SMatMul12x4, f32>::new(m, k, n);
+            test_mat_mul_prep_f32(mm, m, k, n, a, b)?
+        }
+    }
+}
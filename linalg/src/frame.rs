@@ -1,4 +1,7 @@
 pub mod matmul;
+pub mod qmatmul;
 
 pub use self::matmul::MatMul;
 pub use self::matmul::PackedMatMul;
+pub use self::qmatmul::PackedQMatMul;
+pub use self::qmatmul::QMatMul;
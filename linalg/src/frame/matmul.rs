@@ -14,6 +14,26 @@ pub trait MatMul<T: Copy + Add + Mul + Zero + Debug>: Send + Sync + Debug + obje
     fn write_b_packed_by_rows<'p>(&self, pb: &'p mut [T]) -> PackedWriter<'p, T>;
 
     fn mat_mul_prepacked(&self, pa: *const T, pb: *const T, c: *mut T, rsc: isize, csc: isize);
+
+    /// Multiplies `a` by a `b` that was packed beforehand with `pack_b`.
+    ///
+    /// Only `a` is packed, so a constant `b` can be packed once and reused
+    /// across calls.
+    fn mat_mul_with_prepacked_b(
+        &self,
+        a: *const T,
+        rsa: isize,
+        csa: isize,
+        pb: *const T,
+        c: *mut T,
+        rsc: isize,
+        csc: isize,
+    ) {
+        let mut pa: Vec<T> =
+            unsafe { crate::align::uninitialized(self.packed_a_len(), self.packed_a_alignment()) };
+        self.pack_a(pa.as_mut_ptr(), a, rsa, csa);
+        self.mat_mul_prepacked(pa.as_ptr(), pb, c, rsc, csc);
+    }
 }
 
 clone_trait_object!(<T> MatMul<T> where T: Copy + Add + Mul + Zero);
@@ -33,6 +53,11 @@ pub trait PackedMatMulKer<T: Copy + Add + Mul + Zero>: Copy + Clone + Debug + Se
     fn alignment_bytes_b() -> usize;
 }
 
+/// Packed buffer length (in elements) from which `PackedMatMul` packs its
+/// panels in parallel. Below it, thread overhead outweighs the copy.
+#[cfg(feature = "parallel")]
+pub const PARALLEL_PACKING_THRESHOLD: usize = 64 * 1024;
+
 #[derive(Copy, Clone)]
 pub struct PackedMatMul<K, T>
 where
@@ -78,6 +103,59 @@ where
         }
     }
 
+    #[allow(unused_variables)]
+    fn parallel_packing(&self, len: usize) -> bool {
+        #[cfg(feature = "parallel")]
+        return len >= PARALLEL_PACKING_THRESHOLD;
+        #[cfg(not(feature = "parallel"))]
+        return false;
+    }
+
+    /// Calls `f` on each panel index, spreading the calls over the rayon
+    /// thread pool if `parallel` is set. Raw pointers are not `Send`, so
+    /// callers pass their buffers in as addresses.
+    #[allow(unused_variables)]
+    fn for_each_panel<F: Fn(usize) + Send + Sync>(panels: usize, parallel: bool, f: F) {
+        #[cfg(feature = "parallel")]
+        {
+            if parallel {
+                use rayon::prelude::*;
+                return (0..panels).into_par_iter().for_each(f);
+            }
+        }
+        (0..panels).for_each(f)
+    }
+
+    fn pack_a_with(&self, pa: *mut T, a: *const T, rsa: isize, csa: isize, parallel: bool) {
+        let mr = K::mr();
+        assert!(pa as usize % K::alignment_bytes_a() == 0);
+        let (pa_addr, a_addr) = (pa as usize, a as usize);
+        Self::for_each_panel((self.m + mr - 1) / mr, parallel, |p| unsafe {
+            self.pack_panel_a(
+                (pa_addr as *mut T).offset((p * mr * self.k) as isize),
+                (a_addr as *const T).offset((p * mr) as isize * rsa),
+                rsa,
+                csa,
+                mr.min(self.m - p * mr),
+            )
+        });
+    }
+
+    fn pack_b_with(&self, pb: *mut T, b: *const T, rsb: isize, csb: isize, parallel: bool) {
+        let nr = K::nr();
+        assert!(pb as usize % K::alignment_bytes_b() == 0);
+        let (pb_addr, b_addr) = (pb as usize, b as usize);
+        Self::for_each_panel((self.n + nr - 1) / nr, parallel, |p| unsafe {
+            self.pack_panel_b(
+                (pb_addr as *mut T).offset((p * nr * self.k) as isize),
+                (b_addr as *const T).offset((p * nr) as isize * csb),
+                rsb,
+                csb,
+                nr.min(self.n - p * nr),
+            )
+        });
+    }
+
     fn pack_panel_a(&self, pa: *mut T, a: *const T, rsa: isize, csa: isize, rows: usize) {
         let mr = K::mr();
         for i in 0..self.k {
@@ -120,29 +198,8 @@ where
     }
 
     fn pack_a(&self, pa: *mut T, a: *const T, rsa: isize, csa: isize) {
-        let mr = K::mr();
-        assert!(pa as usize % K::alignment_bytes_a() == 0);
-        unsafe {
-            for p in 0..(self.m / mr) {
-                self.pack_panel_a(
-                    pa.offset((p * mr * self.k) as isize),
-                    a.offset((p * mr) as isize * rsa),
-                    rsa,
-                    csa,
-                    mr,
-                )
-            }
-            if self.m % mr != 0 {
-                self.pack_panel_a(
-                    pa.offset((self.m / mr * mr * self.k) as isize),
-                    a.offset((self.m / mr * mr) as isize * rsa),
-                    rsa,
-                    csa,
-                    self.m % mr,
-                )
-            }
-            assert_eq!(*pa, *a);
-        }
+        self.pack_a_with(pa, a, rsa, csa, self.parallel_packing(self.packed_a_len()));
+        unsafe { assert_eq!(*pa, *a) };
     }
 
     fn packed_b_len(&self) -> usize {
@@ -150,28 +207,7 @@ where
     }
 
     fn pack_b(&self, pb: *mut T, b: *const T, rsb: isize, csb: isize) {
-        let nr = K::nr();
-        assert!(pb as usize % K::alignment_bytes_b() == 0);
-        unsafe {
-            for p in 0..(self.n / nr) {
-                self.pack_panel_b(
-                    pb.offset((p * nr * self.k) as isize),
-                    b.offset((p * nr) as isize * csb),
-                    rsb,
-                    csb,
-                    K::nr(),
-                )
-            }
-            if self.n % nr != 0 {
-                self.pack_panel_b(
-                    pb.offset((self.n / nr * nr * self.k) as isize),
-                    b.offset((self.n / nr * nr) as isize * csb),
-                    rsb,
-                    csb,
-                    self.n % nr,
-                )
-            }
-        }
+        self.pack_b_with(pb, b, rsb, csb, self.parallel_packing(self.packed_b_len()))
     }
 
     fn write_b_packed_by_rows<'p>(&self, pb: &'p mut [T]) -> PackedWriter<'p, T> {
@@ -378,6 +414,20 @@ pub mod test {
             .boxed()
     }
 
+    pub fn strat_mat_mul_f64() -> BoxedStrategy<(usize, usize, usize, Vec<f64>, Vec<f64>)> {
+        strat_mat_mul()
+            .prop_map(|(m, k, n, a, b)| {
+                (
+                    m,
+                    k,
+                    n,
+                    a.into_iter().map(|x| x as f64).collect(),
+                    b.into_iter().map(|x| x as f64).collect(),
+                )
+            })
+            .boxed()
+    }
+
     pub fn test_mat_mul_prep_f32<MM: MatMul<f32>>(
         mm: MM,
         m: usize,
@@ -386,16 +436,42 @@ pub mod test {
         a: &[f32],
         b: &[f32],
     ) -> Result<(), proptest::test_runner::TestCaseError> {
+        test_mat_mul_prep(mm, m, k, n, a, b)
+    }
+
+    pub fn test_mat_mul_prep_f64<MM: MatMul<f64>>(
+        mm: MM,
+        m: usize,
+        k: usize,
+        n: usize,
+        a: &[f64],
+        b: &[f64],
+    ) -> Result<(), proptest::test_runner::TestCaseError> {
+        test_mat_mul_prep(mm, m, k, n, a, b)
+    }
+
+    fn test_mat_mul_prep<T, MM>(
+        mm: MM,
+        m: usize,
+        k: usize,
+        n: usize,
+        a: &[T],
+        b: &[T],
+    ) -> Result<(), proptest::test_runner::TestCaseError>
+    where
+        T: Copy + Add<Output = T> + Mul<Output = T> + Zero + Debug + PartialEq + From<f32>,
+        MM: MatMul<T>,
+    {
         unsafe {
-            let mut packed_a: Vec<f32> =
+            let mut packed_a: Vec<T> =
                 align::uninitialized(mm.packed_a_len(), mm.packed_a_alignment());
             mm.pack_a(packed_a.as_mut_ptr(), a.as_ptr(), k as isize, 1);
 
-            let mut packed_b: Vec<f32> =
+            let mut packed_b: Vec<T> =
                 align::uninitialized(mm.packed_b_len(), mm.packed_b_alignment());
             mm.pack_b(packed_b.as_mut_ptr(), b.as_ptr(), n as isize, 1);
 
-            let mut found = vec![9999.0f32; m * n];
+            let mut found = vec![T::from(9999.0f32); m * n];
 
             mm.mat_mul_prepacked(
                 packed_a.as_ptr(),
@@ -404,6 +480,32 @@ pub mod test {
                 n as isize,
                 1,
             );
+            let mut expect = vec![T::zero(); m * n];
+            for x in 0..n {
+                for y in 0..m {
+                    for i in 0..k {
+                        expect[x + y * n] = expect[x + y * n] + a[i + k * y] * b[x + i * n]
+                    }
+                }
+            }
+            prop_assert_eq!(found, expect);
+        }
+        Ok(())
+    }
+
+    pub fn test_mat_mul_with_prepacked_b_f32<MM: MatMul<f32>>(
+        mm: MM,
+        m: usize,
+        k: usize,
+        n: usize,
+        a: &[f32],
+        b: &[f32],
+    ) -> Result<(), proptest::test_runner::TestCaseError> {
+        unsafe {
+            let mut packed_b: Vec<f32> =
+                align::uninitialized(mm.packed_b_len(), mm.packed_b_alignment());
+            mm.pack_b(packed_b.as_mut_ptr(), b.as_ptr(), n as isize, 1);
+
             let mut expect = vec![0.0f32; m * n];
             for x in 0..n {
                 for y in 0..m {
@@ -412,9 +514,53 @@ pub mod test {
                     }
                 }
             }
-            prop_assert_eq!(found, expect);
+            for _ in 0..2 {
+                let mut found = vec![9999.0f32; m * n];
+                mm.mat_mul_with_prepacked_b(
+                    a.as_ptr(),
+                    k as isize,
+                    1,
+                    packed_b.as_ptr(),
+                    found.as_mut_ptr(),
+                    n as isize,
+                    1,
+                );
+                prop_assert_eq!(&found, &expect);
+            }
         }
         Ok(())
     }
 
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_packing_matches_serial() {
+        fn bits(v: &[f32]) -> Vec<u32> {
+            v.iter().map(|x| x.to_bits()).collect()
+        }
+        let (m, k, n) = (67, 131, 45);
+        let mm = PackedMatMul::<crate::generic::SMatMul4x4, f32>::new(m, k, n);
+        let a: Vec<f32> = (0..m * k).map(|i| i as f32).collect();
+        let b: Vec<f32> = (0..k * n).map(|i| -(i as f32)).collect();
+        unsafe {
+            let mut serial: Vec<f32> =
+                align::uninitialized(mm.packed_a_len(), mm.packed_a_alignment());
+            let mut parallel: Vec<f32> =
+                align::uninitialized(mm.packed_a_len(), mm.packed_a_alignment());
+            serial.iter_mut().for_each(|x| *x = 0.0);
+            parallel.iter_mut().for_each(|x| *x = 0.0);
+            mm.pack_a_with(serial.as_mut_ptr(), a.as_ptr(), k as isize, 1, false);
+            mm.pack_a_with(parallel.as_mut_ptr(), a.as_ptr(), k as isize, 1, true);
+            assert_eq!(bits(&serial), bits(&parallel));
+
+            let mut serial: Vec<f32> =
+                align::uninitialized(mm.packed_b_len(), mm.packed_b_alignment());
+            let mut parallel: Vec<f32> =
+                align::uninitialized(mm.packed_b_len(), mm.packed_b_alignment());
+            serial.iter_mut().for_each(|x| *x = 0.0);
+            parallel.iter_mut().for_each(|x| *x = 0.0);
+            mm.pack_b_with(serial.as_mut_ptr(), b.as_ptr(), 1, k as isize, false);
+            mm.pack_b_with(parallel.as_mut_ptr(), b.as_ptr(), 1, k as isize, true);
+            assert_eq!(bits(&serial), bits(&parallel));
+        }
+    }
 }
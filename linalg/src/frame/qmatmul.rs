@@ -0,0 +1,317 @@
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+/// Quantized matrix multiplication: u8 operands, i32 results.
+///
+/// The operands are offset by their zero points: the result is the product
+/// of `(a - a_zero_point)` by `(b - b_zero_point)`.
+pub trait QMatMul: Send + Sync + Debug + objekt::Clone {
+    fn packed_a_len(&self) -> usize;
+    fn packed_a_alignment(&self) -> usize;
+    fn pack_a(&self, pa: *mut u8, a: *const u8, rsa: isize, csa: isize);
+    fn packed_b_len(&self) -> usize;
+    fn packed_b_alignment(&self) -> usize;
+    fn pack_b(&self, pb: *mut u8, b: *const u8, rsb: isize, csb: isize);
+
+    fn mat_mul_prepacked(
+        &self,
+        pa: *const u8,
+        pb: *const u8,
+        c: *mut i32,
+        rsc: isize,
+        csc: isize,
+        a_zero_point: u8,
+        b_zero_point: u8,
+    );
+}
+
+clone_trait_object!(QMatMul);
+
+/// A kernel accumulating the raw products of a packed panel of A and a
+/// packed panel of B. Zero points are handled by `PackedQMatMul`.
+///
+/// Accumulation wraps around on overflow.
+pub trait PackedQMatMulKer: Copy + Clone + Debug + Send + Sync {
+    fn name() -> &'static str;
+    fn kernel(k: usize, a: *const u8, b: *const u8, c: *mut i32, rsc: usize, csc: usize);
+    fn mr() -> usize;
+    fn nr() -> usize;
+    fn alignment_bytes_a() -> usize;
+    fn alignment_bytes_b() -> usize;
+}
+
+#[derive(Copy, Clone)]
+pub struct PackedQMatMul<K>
+where
+    K: PackedQMatMulKer,
+{
+    m: usize,
+    k: usize,
+    n: usize,
+    _kernel: PhantomData<K>,
+}
+
+impl<K> std::fmt::Debug for PackedQMatMul<K>
+where
+    K: PackedQMatMulKer,
+{
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            fmt,
+            "QMM m:{} k:{} n:{} {}({}x{})",
+            self.m,
+            self.k,
+            self.n,
+            K::name(),
+            K::mr(),
+            K::nr()
+        )
+    }
+}
+
+impl<K> PackedQMatMul<K>
+where
+    K: PackedQMatMulKer,
+{
+    pub fn new(m: usize, k: usize, n: usize) -> PackedQMatMul<K> {
+        PackedQMatMul {
+            m,
+            k,
+            n,
+            _kernel: PhantomData,
+        }
+    }
+
+    fn pack_panel_a(&self, pa: *mut u8, a: *const u8, rsa: isize, csa: isize, rows: usize) {
+        let mr = K::mr();
+        for i in 0..self.k {
+            for j in 0..mr {
+                unsafe {
+                    *pa.offset((i * mr + j) as isize) = if j < rows {
+                        *a.offset(i as isize * csa + j as isize * rsa)
+                    } else {
+                        0
+                    }
+                }
+            }
+        }
+    }
+
+    fn pack_panel_b(&self, pb: *mut u8, b: *const u8, rsb: isize, csb: isize, cols: usize) {
+        let nr = K::nr();
+        for i in 0..self.k {
+            for j in 0..nr {
+                unsafe {
+                    *pb.offset((i * nr + j) as isize) = if j < cols {
+                        *b.offset(j as isize * csb + i as isize * rsb)
+                    } else {
+                        0
+                    }
+                }
+            }
+        }
+    }
+
+    /// Sums each packed row of A (resp. column of B) over k, in a buffer
+    /// padded to a multiple of `width`.
+    fn packed_sums(&self, p: *const u8, mn: usize, width: usize) -> Vec<i32> {
+        let panels = (mn + width - 1) / width;
+        let mut sums = vec![0i32; panels * width];
+        for panel in 0..panels {
+            for i in 0..self.k {
+                for j in 0..width {
+                    let v = unsafe { *p.offset(((panel * self.k + i) * width + j) as isize) };
+                    sums[panel * width + j] = sums[panel * width + j].wrapping_add(v as i32);
+                }
+            }
+        }
+        sums
+    }
+}
+
+impl<K> QMatMul for PackedQMatMul<K>
+where
+    K: PackedQMatMulKer,
+{
+    fn packed_a_alignment(&self) -> usize {
+        K::alignment_bytes_a()
+    }
+    fn packed_b_alignment(&self) -> usize {
+        K::alignment_bytes_b()
+    }
+    fn packed_a_len(&self) -> usize {
+        let mr = K::mr();
+        (self.m + mr - 1) / mr * mr * self.k
+    }
+
+    fn pack_a(&self, pa: *mut u8, a: *const u8, rsa: isize, csa: isize) {
+        let mr = K::mr();
+        assert!(pa as usize % K::alignment_bytes_a() == 0);
+        unsafe {
+            for p in 0..(self.m / mr) {
+                self.pack_panel_a(
+                    pa.offset((p * mr * self.k) as isize),
+                    a.offset((p * mr) as isize * rsa),
+                    rsa,
+                    csa,
+                    mr,
+                )
+            }
+            if self.m % mr != 0 {
+                self.pack_panel_a(
+                    pa.offset((self.m / mr * mr * self.k) as isize),
+                    a.offset((self.m / mr * mr) as isize * rsa),
+                    rsa,
+                    csa,
+                    self.m % mr,
+                )
+            }
+        }
+    }
+
+    fn packed_b_len(&self) -> usize {
+        (self.n + K::nr() - 1) / K::nr() * K::nr() * self.k
+    }
+
+    fn pack_b(&self, pb: *mut u8, b: *const u8, rsb: isize, csb: isize) {
+        let nr = K::nr();
+        assert!(pb as usize % K::alignment_bytes_b() == 0);
+        unsafe {
+            for p in 0..(self.n / nr) {
+                self.pack_panel_b(
+                    pb.offset((p * nr * self.k) as isize),
+                    b.offset((p * nr) as isize * csb),
+                    rsb,
+                    csb,
+                    nr,
+                )
+            }
+            if self.n % nr != 0 {
+                self.pack_panel_b(
+                    pb.offset((self.n / nr * nr * self.k) as isize),
+                    b.offset((self.n / nr * nr) as isize * csb),
+                    rsb,
+                    csb,
+                    self.n % nr,
+                )
+            }
+        }
+    }
+
+    fn mat_mul_prepacked(
+        &self,
+        pa: *const u8,
+        pb: *const u8,
+        c: *mut i32,
+        rsc: isize,
+        csc: isize,
+        a_zero_point: u8,
+        b_zero_point: u8,
+    ) {
+        assert!(pa as usize % K::alignment_bytes_a() == 0);
+        assert!(pb as usize % K::alignment_bytes_b() == 0);
+        let mr = K::mr();
+        let nr = K::nr();
+        let (m, k, n) = (self.m, self.k, self.n);
+        // sum((a - za) * (b - zb)) = sum(a * b) - zb * sum(a) - za * sum(b) + k * za * zb
+        let (za, zb) = (a_zero_point as i32, b_zero_point as i32);
+        let a_sums = self.packed_sums(pa, m, mr);
+        let b_sums = self.packed_sums(pb, n, nr);
+        let zz = (k as i32).wrapping_mul(za).wrapping_mul(zb);
+        let mut tmpc = vec![0i32; mr * nr];
+        for ia in 0..(m + mr - 1) / mr {
+            for ib in 0..(n + nr - 1) / nr {
+                K::kernel(
+                    k,
+                    unsafe { pa.offset((ia * k * mr) as isize) },
+                    unsafe { pb.offset((ib * k * nr) as isize) },
+                    tmpc.as_mut_ptr(),
+                    nr,
+                    1,
+                );
+                for y in 0..mr.min(m - ia * mr) {
+                    for x in 0..nr.min(n - ib * nr) {
+                        let row = ia * mr + y;
+                        let col = ib * nr + x;
+                        let v = tmpc[y * nr + x]
+                            .wrapping_sub(zb.wrapping_mul(a_sums[row]))
+                            .wrapping_sub(za.wrapping_mul(b_sums[col]))
+                            .wrapping_add(zz);
+                        unsafe {
+                            *c.offset(row as isize * rsc + col as isize * csc) = v;
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::align;
+    use proptest::prelude::*;
+    use proptest::*;
+
+    pub fn strat_qmat_mul(
+    ) -> BoxedStrategy<(usize, usize, usize, Vec<u8>, Vec<u8>, u8, u8)> {
+        (1usize..35, 1usize..35, 1usize..35)
+            .prop_flat_map(move |(m, k, n)| {
+                (
+                    Just(m),
+                    Just(k),
+                    Just(n),
+                    proptest::collection::vec(any::<u8>(), m * k),
+                    proptest::collection::vec(any::<u8>(), n * k),
+                    1u8..=255,
+                    1u8..=255,
+                )
+            })
+            .boxed()
+    }
+
+    pub fn test_qmat_mul_prep<MM: QMatMul>(
+        mm: MM,
+        m: usize,
+        k: usize,
+        n: usize,
+        a: &[u8],
+        b: &[u8],
+        za: u8,
+        zb: u8,
+    ) -> Result<(), proptest::test_runner::TestCaseError> {
+        unsafe {
+            let mut packed_a: Vec<u8> =
+                align::uninitialized(mm.packed_a_len(), mm.packed_a_alignment());
+            mm.pack_a(packed_a.as_mut_ptr(), a.as_ptr(), k as isize, 1);
+
+            let mut packed_b: Vec<u8> =
+                align::uninitialized(mm.packed_b_len(), mm.packed_b_alignment());
+            mm.pack_b(packed_b.as_mut_ptr(), b.as_ptr(), n as isize, 1);
+
+            let mut found = vec![9999i32; m * n];
+
+            mm.mat_mul_prepacked(
+                packed_a.as_ptr(),
+                packed_b.as_ptr(),
+                found.as_mut_ptr(),
+                n as isize,
+                1,
+                za,
+                zb,
+            );
+            let mut expect = vec![0i32; m * n];
+            for x in 0..n {
+                for y in 0..m {
+                    for i in 0..k {
+                        expect[x + y * n] +=
+                            (a[i + k * y] as i32 - za as i32) * (b[x + i * n] as i32 - zb as i32)
+                    }
+                }
+            }
+            prop_assert_eq!(found, expect);
+        }
+        Ok(())
+    }
+}
@@ -1,4 +1,5 @@
 pub mod matmul;
 
 pub use self::matmul::DMatMul4x2;
+pub use self::matmul::QMatMul4x4;
 pub use self::matmul::SMatMul4x4;
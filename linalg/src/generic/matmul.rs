@@ -99,7 +99,7 @@ impl frame::matmul::PackedMatMulKer<f64> for DMatMul4x2 {
             let mut ab = [[0.0f64; 2]; 4];
             for i in 0..k {
                 let a = std::slice::from_raw_parts(a.offset(4 * i as isize), 4);
-                let b = std::slice::from_raw_parts(b.offset(4 * i as isize), 4);
+                let b = std::slice::from_raw_parts(b.offset(2 * i as isize), 2);
                 ab[0][0] += a[0] * b[0];
                 ab[0][1] += a[0] * b[1];
                 ab[1][0] += a[1] * b[0];
@@ -122,11 +122,59 @@ impl frame::matmul::PackedMatMulKer<f64> for DMatMul4x2 {
     }
 }
 
+#[derive(Copy, Clone, Debug)]
+pub struct QMatMul4x4;
+
+impl frame::qmatmul::PackedQMatMulKer for QMatMul4x4 {
+    #[inline(always)]
+    fn name() -> &'static str {
+        "generic"
+    }
+    #[inline(always)]
+    fn mr() -> usize {
+        4
+    }
+    #[inline(always)]
+    fn nr() -> usize {
+        4
+    }
+    #[inline(always)]
+    fn alignment_bytes_a() -> usize {
+        1
+    }
+    #[inline(always)]
+    fn alignment_bytes_b() -> usize {
+        1
+    }
+    #[inline(never)]
+    fn kernel(k: usize, a: *const u8, b: *const u8, c: *mut i32, rsc: usize, csc: usize) {
+        unsafe {
+            let mut ab = [[0i32; 4]; 4];
+            for i in 0..k {
+                let a = std::slice::from_raw_parts(a.offset(4 * i as isize), 4);
+                let b = std::slice::from_raw_parts(b.offset(4 * i as isize), 4);
+                for y in 0..4 {
+                    for x in 0..4 {
+                        ab[y][x] = ab[y][x].wrapping_add(a[y] as i32 * b[x] as i32);
+                    }
+                }
+            }
+            let c = std::slice::from_raw_parts_mut(c, 1 + 3 * csc + 3 * rsc);
+            for y in 0..4 {
+                for x in 0..4 {
+                    c[x * csc + y * rsc] = ab[y][x];
+                }
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
     use crate::frame::matmul::test::*;
-    use crate::frame::PackedMatMul;
+    use crate::frame::qmatmul::test::*;
+    use crate::frame::{PackedMatMul, PackedQMatMul};
     use proptest::*;
 
     proptest! {
@@ -135,5 +183,23 @@ mod test {
             let mm = PackedMatMul::<SMatMul4x4, f32>::new(m, k, n);
             test_mat_mul_prep_f32(mm, m, k, n, a, b)?
         }
+
+        #[test]
+        fn mat_mul_with_prepacked_b((m, k, n, ref a, ref b) in strat_mat_mul()) {
+            let mm = PackedMatMul::<SMatMul4x4, f32>::new(m, k, n);
+            test_mat_mul_with_prepacked_b_f32(mm, m, k, n, a, b)?
+        }
+
+        #[test]
+        fn dmat_mul_prepacked((m, k, n, ref a, ref b) in strat_mat_mul_f64()) {
+            let mm = PackedMatMul::<DMatMul4x2, f64>::new(m, k, n);
+            test_mat_mul_prep_f64(mm, m, k, n, a, b)?
+        }
+
+        #[test]
+        fn qmat_mul_prepacked((m, k, n, ref a, ref b, za, zb) in strat_qmat_mul()) {
+            let mm = PackedQMatMul::<QMatMul4x4>::new(m, k, n);
+            test_qmat_mul_prep(mm, m, k, n, a, b, za, zb)?
+        }
     }
 }
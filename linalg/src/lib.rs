@@ -6,29 +6,39 @@ extern crate objekt;
 extern crate num_traits;
 #[cfg(test)]
 extern crate proptest;
+#[cfg(feature = "parallel")]
+extern crate rayon;
 
 pub mod align;
 pub mod f16;
 pub mod frame;
 mod generic;
 
+#[cfg(target_arch = "x86_64")]
+pub mod x86_64;
 #[cfg(target_arch = "x86_64")]
 pub mod x86_64_fma;
 
 #[cfg(any(target_arch = "arm", target_arch = "armv7"))]
 pub mod arm32;
 
-pub use self::frame::{MatMul, PackedMatMul};
+#[cfg(target_arch = "aarch64")]
+pub mod arm64;
+
+pub use self::frame::{MatMul, PackedMatMul, PackedQMatMul, QMatMul};
 
 pub struct Ops {
     pub smm: Box<Fn(usize, usize, usize) -> Box<MatMul<f32>> + Send + Sync>,
     pub dmm: Box<Fn(usize, usize, usize) -> Box<MatMul<f64>> + Send + Sync>,
+    /// Quantized u8 matmul kernel. No op of tract-core dispatches to it yet.
+    pub qmm: Box<Fn(usize, usize, usize) -> Box<QMatMul> + Send + Sync>,
 }
 
 pub fn generic() -> Ops {
     Ops {
         smm: Box::new(|m, k, n| Box::new(PackedMatMul::<generic::SMatMul4x4, f32>::new(m, k, n))),
         dmm: Box::new(|m, k, n| Box::new(PackedMatMul::<generic::DMatMul4x2, f64>::new(m, k, n))),
+        qmm: Box::new(|m, k, n| Box::new(PackedQMatMul::<generic::QMatMul4x4>::new(m, k, n))),
     }
 }
 
@@ -36,18 +46,11 @@ pub fn generic() -> Ops {
 pub fn best() -> Ops {
     let mut ops = generic();
     #[cfg(target_arch = "x86_64")]
-    {
-        if is_x86_feature_detected!("fma") {
-            log::info!("x86_64/fma activated for smm");
-            ops.smm = Box::new(|m, k, n| {
-                Box::new(PackedMatMul::<x86_64_fma::matmul::KerFma16x6, f32>::new(
-                    m, k, n,
-                ))
-            });
-        }
-    }
+    x86_64::plug(&mut ops);
     #[cfg(any(target_arch = "arm", target_arch = "armv7"))]
     arm32::plug(&mut ops);
+    #[cfg(target_arch = "aarch64")]
+    arm64::plug(&mut ops);
     return ops;
 }
 
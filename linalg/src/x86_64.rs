@@ -0,0 +1,73 @@
+use std::env;
+mod avx2;
+
+use crate::frame::PackedMatMul;
+use crate::x86_64_fma;
+use crate::Ops;
+
+/// Whether a feature is disabled by setting `TRACT_CPU_X86_64_<FEATURE>` to
+/// `false`. Features the cpu lacks can not be forced on.
+fn cpu_disabled(feature: &str) -> bool {
+    env::var(format!("TRACT_CPU_X86_64_{}", feature))
+        .map(|v| v == "false")
+        .unwrap_or(false)
+}
+
+pub fn has_fma() -> bool {
+    is_x86_feature_detected!("fma") && !cpu_disabled("FMA")
+}
+
+pub fn has_avx2() -> bool {
+    is_x86_feature_detected!("avx2") && !cpu_disabled("AVX2")
+}
+
+/// Plugs the fastest kernels the cpu supports.
+///
+/// The FMA kernels are preferred. The AVX2 one is only a fallback for the
+/// rare cpus with AVX2 but without FMA: on usual AVX2 hardware it is only
+/// picked when FMA is disabled with `TRACT_CPU_X86_64_FMA=false`.
+pub fn plug(ops: &mut Ops) {
+    if has_fma() {
+        log::info!("x86_64/fma activated for smm and dmm");
+        ops.smm = Box::new(|m, k, n| {
+            Box::new(PackedMatMul::<x86_64_fma::matmul::KerFma16x6, f32>::new(
+                m, k, n,
+            ))
+        });
+        ops.dmm = Box::new(|m, k, n| {
+            Box::new(PackedMatMul::<x86_64_fma::matmul::DKerFma8x4, f64>::new(
+                m, k, n,
+            ))
+        });
+    } else if has_avx2() {
+        // fallback only: virtually all AVX2 cpus also have FMA
+        log::info!("x86_64/avx2 activated for smm");
+        ops.smm = Box::new(|m, k, n| Box::new(PackedMatMul::<avx2::SMatMul8x8, f32>::new(m, k, n)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frame::matmul::test::*;
+    use proptest::*;
+
+    proptest! {
+        #[test]
+        fn dmat_mul_fma((m, k, n, ref a, ref b) in strat_mat_mul_f64()) {
+            if has_fma() {
+                let mm = PackedMatMul::<x86_64_fma::matmul::DKerFma8x4, f64>::new(m, k, n);
+                test_mat_mul_prep_f64(mm, m, k, n, a, b)?
+            }
+        }
+    }
+
+    #[test]
+    fn may_have_avx2() {
+        if let Ok(avx2) = env::var("TRACT_CPU_EXPECT_X86_64_AVX2") {
+            assert_eq!(avx2 == "true", has_avx2());
+        } else {
+            println!("Has avx2 ? {:?}", has_avx2());
+        }
+    }
+}
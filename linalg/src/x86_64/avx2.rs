@@ -0,0 +1,105 @@
+use crate::frame;
+
+#[repr(align(32))]
+struct EightAlignedF32([f32; 8]);
+
+#[derive(Copy, Clone, Debug)]
+pub struct SMatMul8x8;
+
+#[target_feature(enable = "avx2")]
+unsafe fn avx2(k: usize, a: *const f32, b: *const f32, c: *mut f32, rsc: usize, csc: usize) {
+    use std::arch::x86_64::*;
+    assert!(k == 0 || a as usize % 32 == 0);
+    assert!(b as usize % 4 == 0);
+    assert!(c as usize % 4 == 0);
+    let mut ab = [_mm256_setzero_ps(); 8];
+    for i in 0..k {
+        let ar = _mm256_load_ps(a.offset((i * 8) as isize));
+        for j in 0usize..8 {
+            let br = _mm256_set1_ps(*b.offset((i * 8 + j) as isize));
+            ab[j] = _mm256_add_ps(ab[j], _mm256_mul_ps(ar, br));
+        }
+    }
+    for x in 0..8 {
+        let mut col = EightAlignedF32([0f32; 8]);
+        _mm256_store_ps(col.0.as_mut_ptr(), ab[x]);
+        for y in 0..8 {
+            *c.offset((y * rsc + x * csc) as isize) = col.0[y];
+        }
+    }
+}
+
+impl frame::matmul::PackedMatMulKer<f32> for SMatMul8x8 {
+    #[inline(always)]
+    fn name() -> &'static str {
+        "avx2"
+    }
+    #[inline(always)]
+    fn mr() -> usize {
+        8
+    }
+    #[inline(always)]
+    fn nr() -> usize {
+        8
+    }
+    fn alignment_bytes_a() -> usize {
+        32
+    }
+    fn alignment_bytes_b() -> usize {
+        4
+    }
+    #[inline(always)]
+    fn kernel(k: usize, a: *const f32, b: *const f32, c: *mut f32, rsc: usize, csc: usize) {
+        unsafe { avx2(k, a, b, c, rsc, csc) }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::frame::matmul::test::*;
+    use crate::frame::{MatMul, PackedMatMul};
+    use crate::generic;
+    use crate::x86_64::has_avx2;
+    use proptest::*;
+
+    proptest! {
+        #[test]
+        fn ker_mat_mul((k, ref a, ref b) in strat_ker_mat_mul::<SMatMul8x8>()) {
+            if !has_avx2() {
+                return Ok(())
+            }
+            test_ker_mat_mul::<SMatMul8x8>(k, a, b)?
+        }
+
+        #[test]
+        fn mat_mul_prepacked((m, k, n, ref a, ref b) in strat_mat_mul()) {
+            if !has_avx2() {
+                return Ok(())
+            }
+            let mm = PackedMatMul::<SMatMul8x8, f32>::new(m, k, n);
+            test_mat_mul_prep_f32(mm, m, k, n, a, b)?
+        }
+
+        #[test]
+        fn mat_mul_same_as_generic((m, k, n, ref a, ref b) in strat_mat_mul()) {
+            if !has_avx2() {
+                return Ok(())
+            }
+            let run = |mm: &MatMul<f32>| unsafe {
+                let mut pa: Vec<f32> =
+                    crate::align::uninitialized(mm.packed_a_len(), mm.packed_a_alignment());
+                mm.pack_a(pa.as_mut_ptr(), a.as_ptr(), k as isize, 1);
+                let mut pb: Vec<f32> =
+                    crate::align::uninitialized(mm.packed_b_len(), mm.packed_b_alignment());
+                mm.pack_b(pb.as_mut_ptr(), b.as_ptr(), n as isize, 1);
+                let mut c = vec![0.0f32; m * n];
+                mm.mat_mul_prepacked(pa.as_ptr(), pb.as_ptr(), c.as_mut_ptr(), n as isize, 1);
+                c
+            };
+            let found = run(&PackedMatMul::<SMatMul8x8, f32>::new(m, k, n));
+            let expect = run(&PackedMatMul::<generic::SMatMul4x4, f32>::new(m, k, n));
+            prop_assert_eq!(found, expect);
+        }
+    }
+}
@@ -58,6 +58,64 @@ impl frame::matmul::PackedMatMulKer<f32> for KerFma16x6 {
     }
 }
 
+#[repr(align(32))]
+struct EightAlignedF64([f64; 8]);
+
+#[derive(Copy, Clone, Debug)]
+pub struct DKerFma8x4;
+
+#[target_feature(enable = "fma")]
+unsafe fn fma_f64(k: usize, a: *const f64, b: *const f64, c: *mut f64, rsc: usize, csc: usize) {
+    use std::arch::x86_64::*;
+    assert!(a as usize % 32 == 0);
+    assert!(b as usize % 8 == 0);
+    assert!(c as usize % 8 == 0);
+    let mut ab1 = [_mm256_setzero_pd(); 4];
+    let mut ab2 = [_mm256_setzero_pd(); 4];
+    for i in 0..k {
+        let ar1 = _mm256_load_pd(a.offset((i * 8) as isize));
+        let ar2 = _mm256_load_pd(a.offset((i * 8 + 4) as isize));
+        for j in 0usize..4 {
+            let br = _mm256_set1_pd(*b.offset((i * 4 + j) as isize));
+            ab1[j] = _mm256_fmadd_pd(ar1, br, ab1[j]);
+            ab2[j] = _mm256_fmadd_pd(ar2, br, ab2[j]);
+        }
+    }
+    for x in 0..4 {
+        let mut col = EightAlignedF64([0f64; 8]);
+        _mm256_store_pd(col.0.as_mut_ptr(), ab1[x]);
+        _mm256_store_pd(col.0.as_mut_ptr().offset(4), ab2[x]);
+        for y in 0..8 {
+            *c.offset((y * rsc + x * csc) as isize) = col.0[y];
+        }
+    }
+}
+
+impl frame::matmul::PackedMatMulKer<f64> for DKerFma8x4 {
+    #[inline(always)]
+    fn name() -> &'static str {
+        "fma"
+    }
+    #[inline(always)]
+    fn mr() -> usize {
+        8
+    }
+    #[inline(always)]
+    fn nr() -> usize {
+        4
+    }
+    fn alignment_bytes_a() -> usize {
+        32
+    }
+    fn alignment_bytes_b() -> usize {
+        8
+    }
+    #[inline(always)]
+    fn kernel(k: usize, a: *const f64, b: *const f64, c: *mut f64, rsc: usize, csc: usize) {
+        unsafe { fma_f64(k, a, b, c, rsc, csc) }
+    }
+}
+
 #[cfg(test)]
 #[cfg(all(
     any(target_arch = "x86", target_arch = "x86_64"),
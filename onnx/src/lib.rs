@@ -23,3 +23,4 @@ pub mod tensor;
 
 pub use self::model::for_path;
 pub use self::model::for_reader;
+pub use self::model::validate_ops;
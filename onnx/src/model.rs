@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::{fs, path};
 
 use tract_core::model::{InletId, Model, OutletId};
@@ -6,16 +6,41 @@ use tract_core::*;
 
 use crate::pb;
 
+/// Options for turning a ONNX model into a tract model.
+#[derive(Debug, Clone, Default)]
+pub struct LoadOptions {
+    /// Loads op types tract has no builder for as `UnimplementedOp`
+    /// placeholders instead of failing. Such a model can not be run, but it
+    /// can still be inspected and analysed around the unknown nodes.
+    pub allow_unknown_ops: bool,
+}
+
 /// Load a ONNX protobul model from a file.
 pub fn for_path<P: AsRef<path::Path>>(p: P) -> TractResult<Model> {
     for_reader(fs::File::open(p)?)
 }
 
+/// Load a ONNX protobul model from a file, with custom options.
+pub fn for_path_with_options<P: AsRef<path::Path>>(
+    p: P,
+    options: &LoadOptions,
+) -> TractResult<Model> {
+    for_reader_with_options(fs::File::open(p)?, options)
+}
+
 /// Load a ONNX model from a reader.
 pub fn for_reader<R: ::std::io::Read>(r: R) -> TractResult<Model> {
     model_proto_for_reader(r)?.tractify()
 }
 
+/// Load a ONNX model from a reader, with custom options.
+pub fn for_reader_with_options<R: ::std::io::Read>(
+    r: R,
+    options: &LoadOptions,
+) -> TractResult<Model> {
+    model_for_proto(&model_proto_for_reader(r)?, options)
+}
+
 /// Load a ONNX protobuf graph def from a path
 pub fn model_proto_for_path<P: AsRef<path::Path>>(p: P) -> TractResult<pb::ModelProto> {
     model_proto_for_reader(fs::File::open(p)?)
@@ -26,56 +51,148 @@ pub fn model_proto_for_reader<R: ::std::io::Read>(mut r: R) -> TractResult<pb::M
     Ok(::protobuf::parse_from_reader(&mut r).map_err(|e| format!("{:?}", e))?)
 }
 
+/// Checks that tract knows how to build every op of a ONNX model.
+///
+/// Returns the sorted list of all the op types missing a builder for the
+/// model opset, instead of stopping at the first one.
+pub fn validate_ops(proto: &pb::ModelProto) -> Result<(), Vec<String>> {
+    let op_builder = super::ops::OpBuilder::new();
+    let opset = opset(proto);
+    let missing: BTreeSet<&str> = proto
+        .get_graph()
+        .get_node()
+        .iter()
+        .map(|node| node.get_op_type())
+        .filter(|op_type| !op_builder.is_registered(op_type, opset))
+        .collect();
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(missing.into_iter().map(|s| s.to_string()).collect())
+    }
+}
+
+fn opset(proto: &pb::ModelProto) -> i64 {
+    // the default domain is spelled either "" or "ai.onnx"; without any
+    // import, assume the latest opset
+    proto
+        .get_opset_import()
+        .iter()
+        .find(|import| import.get_domain() == "" || import.get_domain() == "ai.onnx")
+        .map(|import| import.get_version())
+        .unwrap_or(i64::max_value())
+}
+
 impl Tractify<pb::ModelProto> for Model {
     fn tractify(proto: &pb::ModelProto) -> TractResult<Model> {
-        let mut model = Model::default();
-        let op_builder = super::ops::OpBuilder::new();
-        let graph = proto.get_graph();
-        let mut initializers: HashMap<&str, Tensor> = graph
-            .get_initializer()
-            .iter()
-            .map(|init| Ok((init.get_name(), init.tractify()?)))
-            .collect::<TractResult<_>>()?;
-        let mut outlets_by_name = HashMap::<String, OutletId>::new();
-        for input in graph.get_input().iter() {
-            if let Some(init) = initializers.remove(input.get_name()) {
-                let id = model.add_node(
-                    input.get_name().to_owned(),
-                    Box::new(::tract_core::ops::konst::Const::new(init.into())),
-                )?;
-                outlets_by_name.insert(input.get_name().to_owned(), OutletId::new(id, 0));
-            } else {
-                let fact = input.get_field_type().get_tensor_type().tractify()?;
-                let id = model.add_node(
-                    input.get_name().to_owned(),
-                    Box::new(::tract_core::ops::source::Source::new(fact)),
-                )?;
-                outlets_by_name.insert(input.get_name().to_owned(), OutletId::new(id, 0));
-            }
+        model_for_proto(proto, &LoadOptions::default())
+    }
+}
+
+/// Turns a ONNX protobuf model into a tract model.
+pub fn model_for_proto(proto: &pb::ModelProto, options: &LoadOptions) -> TractResult<Model> {
+    let mut model = Model::default();
+    let op_builder = super::ops::OpBuilder::new();
+    let opset = opset(proto);
+    let graph = proto.get_graph();
+    let mut initializers: HashMap<&str, Tensor> = graph
+        .get_initializer()
+        .iter()
+        .map(|init| Ok((init.get_name(), init.tractify()?)))
+        .collect::<TractResult<_>>()?;
+    let mut outlets_by_name = HashMap::<String, OutletId>::new();
+    for input in graph.get_input().iter() {
+        if let Some(init) = initializers.remove(input.get_name()) {
+            let id = model.add_node(
+                input.get_name().to_owned(),
+                Box::new(::tract_core::ops::konst::Const::new(init.into())),
+            )?;
+            outlets_by_name.insert(input.get_name().to_owned(), OutletId::new(id, 0));
+        } else {
+            let fact = input.get_field_type().get_tensor_type().tractify()?;
+            let id = model.add_node(
+                input.get_name().to_owned(),
+                Box::new(::tract_core::ops::source::Source::new(fact)),
+            )?;
+            outlets_by_name.insert(input.get_name().to_owned(), OutletId::new(id, 0));
+        }
+    }
+    for pbnode in graph.get_node().iter() {
+        let name = if pbnode.get_name() != "" {
+            pbnode.get_name().to_string()
+        } else if pbnode.get_output().len() > 0 && pbnode.get_output()[0] != "" {
+            pbnode.get_output()[0].to_owned()
+        } else {
+            format!("{}-{}", model.nodes().len(), pbnode.get_op_type())
+        };
+        let id = model.add_node(
+            name,
+            op_builder.build(pbnode, opset, options.allow_unknown_ops)?,
+        )?;
+        for (ix, output) in pbnode.get_output().iter().enumerate() {
+            outlets_by_name.insert(output.to_owned(), OutletId::new(id, ix));
+        }
+        // absent optional inputs have an empty name and are not wired
+        let present = pbnode.get_input().iter().filter(|i| !i.is_empty());
+        for (ix, input) in present.enumerate() {
+            model.add_edge(outlets_by_name[&*input], InletId::new(id, ix))?;
         }
-        for pbnode in graph.get_node().iter() {
-            let name = if pbnode.get_name() != "" {
-                pbnode.get_name().to_string()
-            } else if pbnode.get_output().len() > 0 && pbnode.get_output()[0] != "" {
-                pbnode.get_output()[0].to_owned()
-            } else {
-                format!("{}-{}", model.nodes().len(), pbnode.get_op_type())
-            };
-            let id = model.add_node(name, op_builder.build(pbnode)?)?;
-            for (ix, output) in pbnode.get_output().iter().enumerate() {
-                outlets_by_name.insert(output.to_owned(), OutletId::new(id, ix));
-            }
-            for (ix, input) in pbnode.get_input().iter().enumerate() {
-                model.add_edge(outlets_by_name[&*input], InletId::new(id, ix))?;
-            }
+    }
+    let mut outputs = vec![];
+    for output in graph.get_output().iter() {
+        let fact = output.get_field_type().get_tensor_type().tractify()?;
+        outputs.push(outlets_by_name[output.get_name()]);
+        model.set_fact(outlets_by_name[output.get_name()], fact)?;
+    }
+    model.set_outputs_outlets(&outputs)?;
+    Ok(model)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_ops_lists_all_missing_ops() {
+        let mut proto = pb::ModelProto::new();
+        for op_type in &["Relu", "Foo", "Bar", "Foo", "Softmax"] {
+            let mut node = pb::NodeProto::new();
+            node.set_op_type(op_type.to_string());
+            proto.mut_graph().mut_node().push(node);
         }
-        let mut outputs = vec![];
-        for output in graph.get_output().iter() {
-            let fact = output.get_field_type().get_tensor_type().tractify()?;
-            outputs.push(outlets_by_name[output.get_name()]);
-            model.set_fact(outlets_by_name[output.get_name()], fact)?;
+        assert_eq!(
+            validate_ops(&proto),
+            Err(vec!["Bar".to_string(), "Foo".to_string()])
+        );
+        proto
+            .mut_graph()
+            .mut_node()
+            .retain(|n| n.get_op_type() == "Relu");
+        assert_eq!(validate_ops(&proto), Ok(()));
+    }
+
+    #[test]
+    fn unknown_ops_load_as_placeholders_when_allowed() {
+        let mut proto = pb::ModelProto::new();
+        let mut input = pb::ValueInfoProto::new();
+        input.set_name("x".to_string());
+        proto.mut_graph().mut_input().push(input);
+        for (op_type, from, to) in &[("Relu", "x", "y"), ("Foo", "y", "z")] {
+            let mut node = pb::NodeProto::new();
+            node.set_op_type(op_type.to_string());
+            node.set_input(vec![from.to_string()].into());
+            node.set_output(vec![to.to_string()].into());
+            proto.mut_graph().mut_node().push(node);
         }
-        model.set_outputs_outlets(&outputs)?;
-        Ok(model)
+        assert!(model_for_proto(&proto, &LoadOptions::default()).is_err());
+        let options = LoadOptions {
+            allow_unknown_ops: true,
+        };
+        let model = model_for_proto(&proto, &options).unwrap();
+        let output = tract_core::analyser::detect_output(&model)
+            .unwrap()
+            .unwrap();
+        assert_eq!(model.node(output).name, "z");
+        assert_eq!(model.node(output).op.name(), "Unimplemented(Foo)");
     }
 }
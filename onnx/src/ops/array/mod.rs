@@ -11,31 +11,42 @@ use num_traits::AsPrimitive;
 pub fn register_all_ops(reg: &mut OpRegister) {
     reg.insert("Concat", concat);
     reg.insert("ConstantLike", constant_like);
+    reg.insert("ConstantOfShape", constant_of_shape);
+    reg.insert("DepthToSpace", depth_to_space);
     reg.insert("Expand", |_| {
         Ok(Box::new(tractops::array::MultiBroadcastTo::default()))
     });
     reg.insert("EyeLike", eye_like);
     reg.insert("Flatten", flatten);
+    reg.insert("Gather", gather);
     reg.insert("Pad", pad);
     reg.insert("Reshape", |_| {
         Ok(Box::new(tractops::array::Reshape::default()))
     });
+    reg.insert("Resize", resize);
     reg.insert("Shape", |_| {
         Ok(Box::new(tractops::array::Shape::new(DatumType::I64)))
     });
     reg.insert("Size", |_| {
         Ok(Box::new(tractops::array::Size::new(DatumType::I64)))
     });
+    reg.insert("Tile", tile);
     reg.insert("Transpose", transpose);
-    reg.insert("Slice", slice);
+    reg.insert_versioned("Slice", 1..10, slice1);
+    reg.insert_versioned("Slice", 10.., |_| Ok(Box::new(slice::Slice10::new())));
+    reg.insert("SpaceToDepth", |node| {
+        let blocksize = node.get_attr_int("blocksize")? as usize;
+        Ok(Box::new(tractops::array::SpaceToDepth::new(blocksize)))
+    });
     reg.insert("Split", split);
     reg.insert("Squeeze", squeeze);
     reg.insert("Unsqueeze", unsqueeze);
+    reg.insert("Upsample", upsample);
 }
 
 pub fn concat(node: &NodeProto) -> TractResult<Box<Op>> {
     let axis = node.get_attr_int("axis")?;
-    Ok(Box::new(tractops::array::Concat::new(axis as usize)))
+    Ok(Box::new(tractops::array::Concat::new(axis)))
 }
 
 pub fn make_const<T>(shape: &[usize], v: f32) -> TractResult<SharedTensor>
@@ -70,6 +81,25 @@ pub fn constant_like(node: &NodeProto) -> TractResult<Box<Op>> {
     }
 }
 
+pub fn constant_of_shape(node: &NodeProto) -> TractResult<Box<Op>> {
+    let value = match node.get_attr_opt_tensor("value")? {
+        Some(value) => value,
+        None => Tensor::from(::ndarray::arr1(&[0f32])),
+    };
+    Ok(Box::new(tractops::array::ConstantOfShape::new(value)))
+}
+
+pub fn depth_to_space(node: &NodeProto) -> TractResult<Box<Op>> {
+    use tractops::array::{DepthToSpace, DepthToSpaceMode};
+    let blocksize = node.get_attr_int("blocksize")? as usize;
+    let mode = match node.get_attr_opt_str("mode")? {
+        None | Some("DCR") => DepthToSpaceMode::DCR,
+        Some("CRD") => DepthToSpaceMode::CRD,
+        Some(other) => bail!("Unsupported mode for DepthToSpace: {}", other),
+    };
+    Ok(Box::new(DepthToSpace::new(blocksize, mode)))
+}
+
 pub fn eye_like(node: &NodeProto) -> TractResult<Box<Op>> {
     use protobuf::ProtobufEnum;
     let dt = match node.get_attr_opt_int("dtype")? {
@@ -88,7 +118,12 @@ pub fn eye_like(node: &NodeProto) -> TractResult<Box<Op>> {
 
 pub fn flatten(node: &NodeProto) -> TractResult<Box<Op>> {
     let axis = node.get_attr_opt_int("axis")?.unwrap_or(1);
-    Ok(Box::new(tractops::array::Flatten::new(axis as usize)))
+    Ok(Box::new(tractops::array::Flatten::new(axis)))
+}
+
+pub fn gather(node: &NodeProto) -> TractResult<Box<Op>> {
+    let axis = node.get_attr_opt_int("axis")?.unwrap_or(0);
+    Ok(Box::new(tractops::array::Gather::new(axis)))
 }
 
 pub fn pad(node: &NodeProto) -> TractResult<Box<Op>> {
@@ -97,7 +132,8 @@ pub fn pad(node: &NodeProto) -> TractResult<Box<Op>> {
     let mode = match mode {
         Some("reflect") => tractops::array::PadMode::Reflect,
         Some("edge") => tractops::array::PadMode::Edge,
-        _ => tractops::array::PadMode::Constant(value.unwrap_or(0.0)),
+        None | Some("constant") => tractops::array::PadMode::Constant(value.unwrap_or(0.0)),
+        Some(other) => bail!("Unsupported mode for Pad: {}", other),
     };
     let pads = node.get_attr_ints("pads")?;
     let rank = pads.len() / 2;
@@ -107,7 +143,70 @@ pub fn pad(node: &NodeProto) -> TractResult<Box<Op>> {
     Ok(Box::new(tractops::array::Pad::new(pads, mode)))
 }
 
-pub fn slice(node: &NodeProto) -> TractResult<Box<Op>> {
+pub fn resize(node: &NodeProto) -> TractResult<Box<Op>> {
+    use tractops::array::{CoordTransform, InterpolationMode, NearestMode};
+    let inputs = node.get_input();
+    // opset 10 has (X, scales), opset 11 has (X, roi, scales, sizes) with
+    // either scales or sizes left empty
+    let (scales_input, sizes_input) = if inputs.len() == 2 {
+        (Some(1), None)
+    } else {
+        (node.get_input_slot(2), node.get_input_slot(3))
+    };
+    let nearest = match node.get_attr_opt_str("nearest_mode")? {
+        None if inputs.len() == 2 => NearestMode::Floor,
+        None | Some("round_prefer_floor") => NearestMode::RoundPreferFloor,
+        Some("round_prefer_ceil") => NearestMode::RoundPreferCeil,
+        Some("floor") => NearestMode::Floor,
+        Some("ceil") => NearestMode::Ceil,
+        Some(other) => bail!("Unsupported nearest_mode for Resize: {}", other),
+    };
+    let mode = match node.get_attr_opt_str("mode")? {
+        None | Some("nearest") => InterpolationMode::Nearest(nearest),
+        Some("linear") => InterpolationMode::Linear,
+        Some(other) => bail!("Unsupported mode for Resize: {}", other),
+    };
+    let coord_transform = match node.get_attr_opt_str("coordinate_transformation_mode")? {
+        None if inputs.len() == 2 => CoordTransform::Asymmetric,
+        None | Some("half_pixel") => CoordTransform::HalfPixel,
+        Some("pytorch_half_pixel") => CoordTransform::PytorchHalfPixel,
+        Some("align_corners") => CoordTransform::AlignCorners,
+        Some("asymmetric") => CoordTransform::Asymmetric,
+        Some(other) => bail!(
+            "Unsupported coordinate_transformation_mode for Resize: {}",
+            other
+        ),
+    };
+    Ok(Box::new(tractops::array::Resize::new(
+        mode,
+        coord_transform,
+        scales_input,
+        sizes_input,
+    )))
+}
+
+pub fn upsample(node: &NodeProto) -> TractResult<Box<Op>> {
+    use tractops::array::{CoordTransform, InterpolationMode, NearestMode, Resize};
+    let mode = match node.get_attr_opt_str("mode")? {
+        None | Some("nearest") => InterpolationMode::Nearest(NearestMode::Floor),
+        Some("linear") => InterpolationMode::Linear,
+        Some(other) => bail!("Unsupported mode for Upsample: {}", other),
+    };
+    // opset 7 has the scales as an attribute, opset 9 as a second input
+    match node.get_attr_opt_floats("scales")? {
+        Some(scales) => Ok(Box::new(
+            Resize::new(mode, CoordTransform::Asymmetric, None, None).with_scales(scales.to_vec()),
+        )),
+        None => Ok(Box::new(Resize::new(
+            mode,
+            CoordTransform::Asymmetric,
+            Some(1),
+            None,
+        ))),
+    }
+}
+
+pub fn slice1(node: &NodeProto) -> TractResult<Box<Op>> {
     let axes = node.get_attr_opt_ints("axes")?;
     let begin = node.get_attr_ints("starts")?;
     let end = node.get_attr_ints("ends")?;
@@ -121,20 +220,34 @@ pub fn slice(node: &NodeProto) -> TractResult<Box<Op>> {
 pub fn split(node: &NodeProto) -> TractResult<Box<Op>> {
     let axis = node.get_attr_opt_int("axis")?.unwrap_or(0);
     let split = node.get_attr_opt_ints("split")?;
+    if let Some(split) = split {
+        if split.len() != node.get_output().len() {
+            bail!(
+                "Split has {} outputs, but its split attribute has {} values",
+                node.get_output().len(),
+                split.len()
+            )
+        }
+        if let Some(&negative) = split.iter().find(|&&d| d < 0) {
+            bail!("Split can not produce a part of length {}", negative)
+        }
+    }
     Ok(Box::new(tractops::array::Split::new(
-        axis as usize,
+        axis,
         node.get_output().len(),
         split.map(|a| a.into_iter().map(|&d| d as _).collect()),
     )))
 }
 
 pub fn squeeze(node: &NodeProto) -> TractResult<Box<Op>> {
-    let axes = node
-        .get_attr_opt_ints("axes")?
-        .map(|l| l.iter().map(|&a| a as usize).collect());
+    let axes = node.get_attr_opt_ints("axes")?.map(|l| l.to_vec());
     Ok(Box::new(tractops::array::Squeeze::new(axes)))
 }
 
+pub fn tile(_node: &NodeProto) -> TractResult<Box<Op>> {
+    Ok(Box::new(tractops::array::Tile::new()))
+}
+
 pub fn transpose(node: &NodeProto) -> TractResult<Box<Op>> {
     let perm = node
         .get_attr_opt_ints("perm")?
@@ -143,10 +256,160 @@ pub fn transpose(node: &NodeProto) -> TractResult<Box<Op>> {
 }
 
 pub fn unsqueeze(node: &NodeProto) -> TractResult<Box<Op>> {
-    let axes = node
-        .get_attr_ints("axes")?
-        .iter()
-        .map(|&a| a as usize)
-        .collect();
+    let axes = node.get_attr_ints("axes")?.to_vec();
     Ok(Box::new(tractops::array::AddDims::new(axes)))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pb::{AttributeProto, AttributeProto_AttributeType};
+
+    fn node(op_type: &str, outputs: usize, attrs: Vec<AttributeProto>) -> NodeProto {
+        let mut node = NodeProto::new();
+        node.set_op_type(op_type.to_string());
+        node.set_output((0..outputs).map(|i| format!("o{}", i)).collect());
+        node.set_attribute(attrs.into());
+        node
+    }
+
+    fn ints(name: &str, v: &[i64]) -> AttributeProto {
+        let mut attr = AttributeProto::new();
+        attr.set_name(name.to_string());
+        attr.set_field_type(AttributeProto_AttributeType::INTS);
+        attr.set_ints(v.to_vec());
+        attr
+    }
+
+    fn int(name: &str, v: i64) -> AttributeProto {
+        let mut attr = AttributeProto::new();
+        attr.set_name(name.to_string());
+        attr.set_field_type(AttributeProto_AttributeType::INT);
+        attr.set_i(v);
+        attr
+    }
+
+    fn floats(name: &str, v: &[f32]) -> AttributeProto {
+        let mut attr = AttributeProto::new();
+        attr.set_name(name.to_string());
+        attr.set_field_type(AttributeProto_AttributeType::FLOATS);
+        attr.set_floats(v.to_vec());
+        attr
+    }
+
+    fn tensor(name: &str, v: pb::TensorProto) -> AttributeProto {
+        let mut attr = AttributeProto::new();
+        attr.set_name(name.to_string());
+        attr.set_field_type(AttributeProto_AttributeType::TENSOR);
+        attr.set_t(v);
+        attr
+    }
+
+    fn string(name: &str, v: &str) -> AttributeProto {
+        let mut attr = AttributeProto::new();
+        attr.set_name(name.to_string());
+        attr.set_field_type(AttributeProto_AttributeType::STRING);
+        attr.set_s(v.as_bytes().to_vec());
+        attr
+    }
+
+    #[test]
+    fn pad_rejects_unknown_mode() {
+        let pads = ints("pads", &[1, 1]);
+        assert!(pad(&node("Pad", 1, vec![pads.clone(), string("mode", "edge")])).is_ok());
+        assert!(pad(&node(
+            "Pad",
+            1,
+            vec![pads.clone(), string("mode", "constant")]
+        ))
+        .is_ok());
+        let err = pad(&node("Pad", 1, vec![pads, string("mode", "reflekt")])).unwrap_err();
+        assert_eq!(format!("{}", err), "Unsupported mode for Pad: reflekt");
+    }
+
+    #[test]
+    fn split_rejects_mismatched_split_attribute() {
+        assert!(split(&node("Split", 2, vec![ints("split", &[2, 3])])).is_ok());
+        let err = split(&node("Split", 3, vec![ints("split", &[2, 3])])).unwrap_err();
+        assert_eq!(
+            format!("{}", err),
+            "Split has 3 outputs, but its split attribute has 2 values"
+        );
+    }
+
+    #[test]
+    fn constant_of_shape_fills_value() {
+        let mut value = pb::TensorProto::new();
+        value.set_data_type(pb::TensorProto_DataType::FLOAT);
+        value.set_dims(vec![1]);
+        value.set_float_data(vec![7.0]);
+        let op =
+            constant_of_shape(&node("ConstantOfShape", 1, vec![tensor("value", value)])).unwrap();
+        let shape = Tensor::from(::ndarray::arr1(&[2i64, 3]));
+        let output = op
+            .as_stateless()
+            .unwrap()
+            .eval(tvec!(shape.into()))
+            .unwrap();
+        assert_eq!(
+            output[0],
+            Tensor::from(::ndarray::Array2::from_elem((2, 3), 7.0f32)).into()
+        );
+    }
+
+    #[test]
+    fn upsample_nearest_2x_from_attribute() {
+        let attrs = vec![
+            string("mode", "nearest"),
+            floats("scales", &[1.0, 1.0, 2.0, 2.0]),
+        ];
+        let op = upsample(&node("Upsample", 1, attrs)).unwrap();
+        let input = TensorFact::dt_shape(DatumType::F32, vec![1, 3, 5, 4]);
+        let output = TensorFact::default();
+        let (_, outputs) = op.infer_facts(tvec!(&input), tvec!(&output)).unwrap();
+        assert_eq!(
+            outputs[0],
+            TensorFact::dt_shape(DatumType::F32, vec![1, 3, 10, 8])
+        );
+        let input: SharedTensor =
+            ::ndarray::ArrayD::from_shape_vec(vec![1, 1, 2, 2], vec![1.0f32, 2.0, 3.0, 4.0])
+                .unwrap()
+                .into();
+        let result = op.as_stateless().unwrap().eval(tvec!(input)).unwrap();
+        assert_eq!(result[0].shape(), &[1, 1, 4, 4]);
+    }
+
+    #[test]
+    fn upsample_nearest_2x_from_input() {
+        let op = upsample(&node("Upsample", 1, vec![string("mode", "nearest")])).unwrap();
+        let input = TensorFact::dt_shape(DatumType::F32, vec![1, 3, 5, 4]);
+        let scales: TensorFact =
+            SharedTensor::from(::ndarray::arr1(&[1.0f32, 1.0, 2.0, 2.0])).into();
+        let output = TensorFact::default();
+        let (_, outputs) = op
+            .infer_facts(tvec!(&input, &scales), tvec!(&output))
+            .unwrap();
+        assert_eq!(
+            outputs[0],
+            TensorFact::dt_shape(DatumType::F32, vec![1, 3, 10, 8])
+        );
+    }
+
+    #[test]
+    fn depth_to_space_reads_mode() {
+        let op = depth_to_space(&node(
+            "DepthToSpace",
+            1,
+            vec![int("blocksize", 2), string("mode", "CRD")],
+        ))
+        .unwrap();
+        assert_eq!(op.name(), "DepthToSpace");
+        let err = depth_to_space(&node(
+            "DepthToSpace",
+            1,
+            vec![int("blocksize", 2), string("mode", "RCD")],
+        ))
+        .unwrap_err();
+        assert_eq!(format!("{}", err), "Unsupported mode for DepthToSpace: RCD");
+    }
+}
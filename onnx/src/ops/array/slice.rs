@@ -1,18 +1,39 @@
 use ndarray::prelude::*;
+use tract_core::ops::array::AxisSlice;
 use tract_core::ops::prelude::*;
 
 #[derive(Debug, Clone, new, Default)]
 pub struct Slice {
-    axes: Option<Vec<usize>>,
+    axes: Option<Vec<i64>>,
     starts: Vec<isize>,
     ends: Vec<isize>,
 }
 
 impl Slice {
+    /// Resolves the axis sliced by each pair of bounds, for an input of
+    /// rank `rank`.
+    fn resolved_axes(&self, rank: usize) -> TractResult<Vec<usize>> {
+        if self.starts.len() != self.ends.len() {
+            bail!("Slice starts and ends must have the same length")
+        }
+        match self.axes {
+            Some(ref axes) => {
+                if axes.len() != self.starts.len() {
+                    bail!("Slice axes, starts and ends must have the same length")
+                }
+                axes.iter().map(|&a| normalize_axis(a, rank)).collect()
+            }
+            None => (0..self.starts.len())
+                .map(|a| normalize_axis(a as i64, rank))
+                .collect(),
+        }
+    }
+
     fn eval_t<T: Datum>(&self, input: SharedTensor) -> TractResult<SharedTensor> {
         let mut input = input.to_array_view::<T>()?;
+        let axes = self.resolved_axes(input.ndim())?;
         for (ix, (&b, &e)) in self.starts.iter().zip(self.ends.iter()).enumerate() {
-            let axis = self.axes.as_ref().map(|axes| axes[ix]).unwrap_or(ix);
+            let axis = axes[ix];
             let b = if b > input.shape()[axis] as isize {
                 input.shape()[axis] as isize
             } else {
@@ -36,6 +57,27 @@ impl Op for Slice {
     fn name(&self) -> Cow<str> {
         "onnx.Slice".into()
     }
+
+    fn reduce(
+        &self,
+        inputs: TVec<&TensorFact>,
+        _outputs: TVec<&TensorFact>,
+        phase: ReductionPhase,
+    ) -> TractResult<Option<ReducedOpRewire>> {
+        if phase == ReductionPhase::Normalize {
+            if let Some(shape) = inputs[0].shape.as_concrete_finite()? {
+                let mut prune = vec![AxisSlice::default(); shape.len()];
+                let axes = self.resolved_axes(shape.len())?;
+                for (ix, (&b, &e)) in self.starts.iter().zip(self.ends.iter()).enumerate() {
+                    let axis = axes[ix];
+                    prune[axis] = axis_slice(shape[axis] as i64, b as i64, e as i64, 1).unwrap();
+                }
+                let op = tract_core::ops::array::Slice::new(prune);
+                return Ok(Some(ReducedOpRewire::unary(op)));
+            }
+        }
+        Ok(None)
+    }
 }
 
 impl StatelessOp for Slice {
@@ -64,17 +106,13 @@ impl InferenceRulesOp for Slice {
         s.equals(&inputs[0].rank, &outputs[0].rank)?;
         s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
         s.given(&inputs[0].shape, move |s, shape| {
+            let axes = self.resolved_axes(shape.len())?;
             (0..shape.len()).try_for_each(move |axis| {
                 let d = shape[axis];
-                let spec = if let Some(axes) = self.axes.as_ref() {
-                    if let Some(ix) = axes.iter().position(|&a| a == axis) {
-                        Some((self.starts[ix], self.ends[ix]))
-                    } else {
-                        None
-                    }
-                } else {
-                    Some((self.starts[axis].into(), self.ends[axis].into()))
-                };
+                let spec = axes
+                    .iter()
+                    .position(|&a| a == axis)
+                    .map(|ix| (self.starts[ix], self.ends[ix]));
                 if let Some((mut b, mut e)) = spec {
                     if let Ok(d) = d.to_integer() {
                         if b as i32 > d {
@@ -103,3 +141,382 @@ impl InferenceRulesOp for Slice {
         Ok(())
     }
 }
+
+/// Slice as of opset 10: starts, ends, and optionally axes and steps are
+/// given as inputs, following Python slicing conventions.
+#[derive(Debug, Clone, new, Default)]
+pub struct Slice10;
+
+/// Normalizes a Python-style slice over a dimension of size `dim`: negative
+/// bounds count from the end, out of range bounds are clamped.
+///
+/// Returns the resolved start and end, and the length of the slice.
+fn resolve(dim: i64, start: i64, end: i64, step: i64) -> (i64, i64, i64) {
+    let start = if start < 0 { start + dim } else { start };
+    let end = if end < 0 { end + dim } else { end };
+    if step > 0 {
+        let start = start.max(0).min(dim);
+        let end = end.max(0).min(dim);
+        (start, end, ((end - start + step - 1) / step).max(0))
+    } else {
+        let start = start.max(0).min(dim - 1);
+        let end = end.max(-1).min(dim - 1);
+        (start, end, ((start - end - step - 1) / -step).max(0))
+    }
+}
+
+/// Translates a Python-style slice over an axis of size `dim` to the spec
+/// of the core Slice, which can not express negative steps.
+fn axis_slice(dim: i64, start: i64, end: i64, step: i64) -> Option<AxisSlice> {
+    if step < 0 {
+        return None;
+    }
+    let (start, end, len) = resolve(dim, start, end, step);
+    let end = if len == 0 { start } else { end };
+    Some(AxisSlice::new(
+        start as usize,
+        (dim - end) as usize,
+        step as usize,
+    ))
+}
+
+impl Slice10 {
+    /// Reads the (axis, start, end, step) of each sliced axis from the
+    /// starts, ends, axes and steps inputs.
+    fn specs(params: &[SharedTensor], rank: usize) -> TractResult<Vec<(usize, i64, i64, i64)>> {
+        let params = params
+            .iter()
+            .map(|p| Ok(p.cast_to::<i64>()?.as_slice::<i64>()?.to_vec()))
+            .collect::<TractResult<Vec<Vec<i64>>>>()?;
+        let (starts, ends) = (&params[0], &params[1]);
+        if starts.len() != ends.len() {
+            bail!("Slice starts and ends must have the same length")
+        }
+        let axes: Vec<usize> = match params.get(2) {
+            Some(axes) => axes
+                .iter()
+                .map(|&a| normalize_axis(a, rank))
+                .collect::<TractResult<_>>()?,
+            None => (0..starts.len())
+                .map(|a| normalize_axis(a as i64, rank))
+                .collect::<TractResult<_>>()?,
+        };
+        if axes.len() != starts.len() {
+            bail!("Slice axes, starts and ends must have the same length")
+        }
+        let steps = params
+            .get(3)
+            .cloned()
+            .unwrap_or_else(|| vec![1; starts.len()]);
+        if steps.len() != starts.len() {
+            bail!("Slice steps, starts and ends must have the same length")
+        }
+        if steps.iter().any(|&s| s == 0) {
+            bail!("Slice steps can not be zero")
+        }
+        Ok((0..starts.len())
+            .map(|ix| (axes[ix], starts[ix], ends[ix], steps[ix]))
+            .collect())
+    }
+
+    fn eval_t<T: Datum>(
+        &self,
+        input: SharedTensor,
+        specs: &[(usize, i64, i64, i64)],
+    ) -> TractResult<SharedTensor> {
+        let mut input = input.to_array_view::<T>()?;
+        for &(axis, start, end, step) in specs {
+            let (start, end, len) = resolve(input.shape()[axis] as i64, start, end, step);
+            let slice = if len == 0 {
+                ::ndarray::Slice::new(0, Some(0), 1)
+            } else if step > 0 {
+                ::ndarray::Slice::new(start as isize, Some(end as isize), step as isize)
+            } else {
+                // ndarray walks a negative step from the end of the range
+                ::ndarray::Slice::new(end as isize + 1, Some(start as isize + 1), step as isize)
+            };
+            input.slice_axis_inplace(Axis(axis), slice);
+        }
+        Ok(Tensor::from(input.to_owned()).into())
+    }
+}
+
+impl Op for Slice10 {
+    fn name(&self) -> Cow<str> {
+        "onnx.Slice10".into()
+    }
+
+    fn const_inputs(&self) -> TVec<usize> {
+        tvec!(1, 2, 3, 4)
+    }
+
+    fn reduce(
+        &self,
+        inputs: TVec<&TensorFact>,
+        _outputs: TVec<&TensorFact>,
+        phase: ReductionPhase,
+    ) -> TractResult<Option<ReducedOpRewire>> {
+        if phase == ReductionPhase::Normalize {
+            let params: Option<Vec<SharedTensor>> =
+                inputs[1..].iter().map(|i| i.value.concretize()).collect();
+            if let (Some(shape), Some(params)) = (inputs[0].shape.as_concrete_finite()?, params) {
+                let mut prune = vec![AxisSlice::default(); shape.len()];
+                for (axis, start, end, step) in Self::specs(&params, shape.len())? {
+                    match axis_slice(shape[axis] as i64, start, end, step) {
+                        Some(spec) => prune[axis] = spec,
+                        None => return Ok(None),
+                    }
+                }
+                let op = tract_core::ops::array::Slice::new(prune);
+                return Ok(Some(ReducedOpRewire::unary(op)));
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl StatelessOp for Slice10 {
+    /// Evaluates the operation given the input tensors.
+    fn eval(&self, inputs: TVec<SharedTensor>) -> TractResult<TVec<SharedTensor>> {
+        let specs = Self::specs(&inputs[1..], inputs[0].shape().len())?;
+        let input = inputs[0].clone();
+        Ok(tvec!(dispatch_datum!(Self::eval_t(input.datum_type())(
+            self, input, &specs
+        ))?))
+    }
+}
+
+impl InferenceRulesOp for Slice10 {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p SharedTensorsProxy,
+        outputs: &'p SharedTensorsProxy,
+    ) -> InferenceResult {
+        s.equals(&outputs.len, 1)?;
+        s.equals(&inputs[0].rank, &outputs[0].rank)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.given_2(&inputs.len, &inputs[0].rank, move |s, n, rank| {
+            if n < 3 || n > 5 {
+                bail!("Slice expects 3 to 5 inputs, got {}", n)
+            }
+            s.given_all(
+                (1..n as usize).map(|i| &inputs[i].value),
+                move |s, params| {
+                    let specs = Self::specs(&params, rank as usize)?;
+                    for axis in 0..rank as usize {
+                        match specs.iter().find(|spec| spec.0 == axis) {
+                            None => s.equals(&inputs[0].shape[axis], &outputs[0].shape[axis])?,
+                            Some(&(_, start, end, step)) => {
+                                s.given(&inputs[0].shape[axis], move |s, dim| {
+                                    if let Ok(dim) = dim.to_integer() {
+                                        let (_, _, len) = resolve(dim as i64, start, end, step);
+                                        s.equals(&outputs[0].shape[axis], len.to_dim())?;
+                                    } else if step > 0 {
+                                        // assume bounds are in range for symbolic dimensions,
+                                        // but a positive end may be clamped to the dimension
+                                        let start = if start < 0 {
+                                            dim + start
+                                        } else {
+                                            start.to_dim()
+                                        };
+                                        let end = if end < 0 {
+                                            Some(dim + end)
+                                        } else if end >= i32::max_value() as i64 {
+                                            Some(dim)
+                                        } else {
+                                            None
+                                        };
+                                        if let Some(end) = end {
+                                            s.equals(
+                                                &outputs[0].shape[axis],
+                                                (end - start).div_ceil(step.to_dim()),
+                                            )?;
+                                        }
+                                    }
+                                    Ok(())
+                                })?
+                            }
+                        }
+                    }
+                    Ok(())
+                },
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slice(input: ArrayD<i32>, params: &[&[i64]]) -> ArrayD<i32> {
+        let mut inputs: TVec<SharedTensor> = tvec!(input.into());
+        inputs.extend(params.iter().map(|p| arr1(p).into()));
+        Slice10
+            .eval(inputs)
+            .unwrap()
+            .remove(0)
+            .to_array_view::<i32>()
+            .unwrap()
+            .to_owned()
+    }
+
+    #[test]
+    fn slice10_negative_end() {
+        let input = Array::from_iter(0..10)
+            .into_shape((2, 5))
+            .unwrap()
+            .into_dyn();
+        let output = slice(input, &[&[1], &[-1], &[1]]);
+        assert_eq!(output, arr2(&[[1, 2, 3], [6, 7, 8]]).into_dyn());
+    }
+
+    #[test]
+    fn slice10_step_2() {
+        let input = Array::from_iter(0..10).into_dyn();
+        let output = slice(input, &[&[1], &[1000], &[0], &[2]]);
+        assert_eq!(output, arr1(&[1, 3, 5, 7, 9]).into_dyn());
+        let input = Array::from_iter(0..10).into_dyn();
+        let output = slice(input, &[&[-1], &[-1000], &[0], &[-2]]);
+        assert_eq!(output, arr1(&[9, 7, 5, 3, 1]).into_dyn());
+    }
+
+    #[test]
+    fn slice10_rejects_out_of_range_axis() {
+        let input: SharedTensor = Array::from_iter(0..10).into_shape((2, 5)).unwrap().into();
+        let params = |axis: i64| -> TVec<SharedTensor> {
+            tvec!(
+                input.clone(),
+                arr1(&[1i64]).into(),
+                arr1(&[3i64]).into(),
+                arr1(&[axis]).into()
+            )
+        };
+        assert!(Slice10.eval(params(-3)).is_err());
+        assert!(Slice10.eval(params(2)).is_err());
+        let output = Slice10.eval(params(-1)).unwrap();
+        assert_eq!(output[0], arr2(&[[1, 2], [6, 7]]).into());
+    }
+
+    #[test]
+    fn slice1_normalizes_axes() {
+        let input: SharedTensor = Array::from_iter(0..10).into_shape((2, 5)).unwrap().into();
+        let op = Slice::new(Some(vec![-1]), vec![1], vec![3]);
+        let output = op.eval(tvec!(input.clone())).unwrap();
+        assert_eq!(output[0], arr2(&[[1, 2], [6, 7]]).into());
+        let op = Slice::new(Some(vec![-3]), vec![1], vec![3]);
+        assert!(op.eval(tvec!(input.clone())).is_err());
+        let fact = TensorFact::dt_shape(DatumType::I32, vec![2, 5]);
+        let any = TensorFact::default();
+        assert!(op.infer(tvec!(&fact), tvec!(&any)).is_err());
+    }
+
+    #[test]
+    fn slice10_bool() {
+        let input: SharedTensor = arr1(&[true, false, true, false]).into();
+        let output = Slice10
+            .eval(tvec!(input, arr1(&[1i64]).into(), arr1(&[3i64]).into()))
+            .unwrap();
+        assert_eq!(output[0], arr1(&[false, true]).into());
+    }
+
+    #[test]
+    fn slice10_infers_output_shape() {
+        let input = TensorFact::dt_shape(DatumType::F32, vec![TDim::s(), 10.to_dim()]);
+        let starts = TensorFact::from(arr1(&[1i64, 1]));
+        let ends = TensorFact::from(arr1(&[-1i64, 1000]));
+        let axes = TensorFact::from(arr1(&[0i64, 1]));
+        let steps = TensorFact::from(arr1(&[1i64, 2]));
+        let output = TensorFact::default();
+        let (_, outputs) = Slice10
+            .infer(tvec!(&input, &starts, &ends, &axes, &steps), tvec!(&output))
+            .unwrap();
+        assert_eq!(
+            outputs[0],
+            TensorFact::dt_shape(DatumType::F32, vec![TDim::s() - 2, 5.to_dim()])
+        );
+    }
+
+    #[test]
+    fn slice10_leaves_clamped_symbolic_dims_unknown() {
+        let input = TensorFact::dt_shape(DatumType::F32, vec![TDim::s(), TDim::s()]);
+        let starts = TensorFact::from(arr1(&[1i64, 1]));
+        let ends = TensorFact::from(arr1(&[1000i64, i64::max_value()]));
+        let output = TensorFact::default();
+        let (_, outputs) = Slice10
+            .infer(tvec!(&input, &starts, &ends), tvec!(&output))
+            .unwrap();
+        assert_eq!(
+            outputs[0],
+            TensorFact::dt(DatumType::F32).with_shape(ShapeFact::closed(tvec!(
+                GenericFact::Any,
+                (TDim::s() - 1).into()
+            )))
+        );
+    }
+
+    #[test]
+    fn slice10_requires_constant_bounds() {
+        use tract_core::model::dsl::ModelDsl;
+        use tract_core::model::{InletId, OutletId};
+        use tract_core::optim::{OptimizerPass, PropConst};
+        use tract_core::Model;
+
+        let mut model = Model::default();
+        let x = model
+            .add_source_fact("x", TensorFact::dt_shape(DatumType::F32, vec![10]))
+            .unwrap();
+        let starts = model.add_const("starts", arr1(&[1i64]).into()).unwrap();
+        let ends = model.add_source("ends").unwrap();
+        let slice = model
+            .add_node("slice".to_string(), Box::new(Slice10))
+            .unwrap();
+        for (ix, input) in [x, starts, ends].iter().enumerate() {
+            model
+                .add_edge(OutletId::new(*input, 0), InletId::new(slice, ix))
+                .unwrap();
+        }
+        model
+            .set_outputs_outlets(&[OutletId::new(slice, 0)])
+            .unwrap();
+        model.analyse().unwrap();
+        assert_eq!(Slice10.const_inputs(), tvec!(1, 2, 3, 4));
+        let pass = PropConst {
+            require_const_inputs: true,
+            ..PropConst::default()
+        };
+        let err = pass.pass(&mut model).unwrap_err();
+        assert_eq!(
+            format!("{}", err),
+            "onnx.Slice10 requires input 2 to be constant (node slice)"
+        );
+    }
+
+    #[test]
+    fn slice10_step_2_reduces_to_core_slice() {
+        let input = TensorFact::dt_shape(DatumType::F32, vec![3, 10]);
+        let starts = TensorFact::from(arr1(&[1i64]));
+        let ends = TensorFact::from(arr1(&[1000i64]));
+        let axes = TensorFact::from(arr1(&[1i64]));
+        let steps = TensorFact::from(arr1(&[2i64]));
+        let output = TensorFact::default();
+        let reduced = Slice10
+            .reduce(
+                tvec!(&input, &starts, &ends, &axes, &steps),
+                tvec!(&output),
+                ReductionPhase::Normalize,
+            )
+            .unwrap()
+            .unwrap();
+        let op = reduced.ops[0].clone();
+        let (_, outputs) = op.infer(tvec!(&input), tvec!(&output)).unwrap();
+        assert_eq!(outputs[0], TensorFact::dt_shape(DatumType::F32, vec![3, 5]));
+        let data = Array::from_shape_fn((3, 10), |(i, j)| (i * 10 + j) as f32);
+        let result = op.as_stateless().unwrap().eval(tvec!(data.into())).unwrap();
+        assert_eq!(
+            result[0].to_array_view::<f32>().unwrap(),
+            Array::from_shape_fn((3, 5), |(i, j)| (i * 10 + 2 * j + 1) as f32).into_dyn()
+        );
+    }
+}
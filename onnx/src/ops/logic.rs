@@ -15,4 +15,5 @@ pub fn register_all_ops(reg: &mut OpRegister) {
         Ok(Box::new(tractops::logic::Greater::default()))
     });
     reg.insert("Less", |_| Ok(Box::new(tractops::logic::Lesser::default())));
+    reg.insert("Where", |_| Ok(Box::new(tractops::logic::Where::default())));
 }
@@ -2,6 +2,7 @@ use tract_core::ops as tractops;
 
 use crate::ops::OpRegister;
 use crate::pb::NodeProto;
+use tract_core::ops::math::UnaryFunction;
 use tract_core::ops::prelude::*;
 
 pub fn register_all_ops(reg: &mut OpRegister) {
@@ -15,10 +16,10 @@ pub fn register_all_ops(reg: &mut OpRegister) {
     reg.insert("Min", |_| Ok(Box::new(tractops::math::MinN::default())));
     reg.insert("Mean", |_| Ok(Box::new(tractops::math::MeanN::default())));
 
-    reg.insert("Abs", |_| Ok(Box::new(tractops::math::Abs::default())));
     reg.insert("Ceil", |_| Ok(Box::new(tractops::math::Ceil::default())));
     reg.insert("Floor", |_| Ok(Box::new(tractops::math::Floor::default())));
-    reg.insert("Clip", clip);
+    reg.insert_versioned("Clip", 1..11, clip6);
+    reg.insert_versioned("Clip", 11.., clip11);
 
     reg.insert("Cos", |_| Ok(Box::new(tractops::math::Cos::default())));
     reg.insert("Sin", |_| Ok(Box::new(tractops::math::Sin::default())));
@@ -29,20 +30,15 @@ pub fn register_all_ops(reg: &mut OpRegister) {
 
     reg.insert("Cosh", |_| Ok(Box::new(tractops::math::Cosh::default())));
     reg.insert("Sinh", |_| Ok(Box::new(tractops::math::Sinh::default())));
-    reg.insert("Tanh", |_| Ok(Box::new(tractops::math::Tanh::default())));
     reg.insert("Acosh", |_| Ok(Box::new(tractops::math::Acosh::default())));
     reg.insert("Asinh", |_| Ok(Box::new(tractops::math::Asinh::default())));
     reg.insert("Atanh", |_| Ok(Box::new(tractops::math::Atanh::default())));
 
-    reg.insert("Exp", |_| Ok(Box::new(tractops::math::Exp::default())));
-    reg.insert("Log", |_| Ok(Box::new(tractops::math::Ln::default())));
-    reg.insert("Sqrt", |_| Ok(Box::new(tractops::math::Sqrt::default())));
     reg.insert("Rsqrt", |_| Ok(Box::new(tractops::math::Rsqrt::default())));
 
-    reg.insert("Neg", |_| Ok(Box::new(tractops::math::Neg::default())));
-    reg.insert("Reciprocal", |_| {
-        Ok(Box::new(tractops::math::Recip::default()))
-    });
+    for (name, _) in UNARY_FUNCTIONS {
+        reg.insert(name, unary);
+    }
 
     reg.insert("Pow", |_| Ok(Box::new(tractops::math::Pow::default())));
 
@@ -52,12 +48,100 @@ pub fn register_all_ops(reg: &mut OpRegister) {
     reg.insert("Gemm", gemm);
 }
 
-pub fn clip(node: &NodeProto) -> TractResult<Box<Op>> {
-    let min = node.get_attr_opt_float("min")?.unwrap_or(::std::f32::MIN);
-    let max = node.get_attr_opt_float("max")?.unwrap_or(::std::f32::MAX);
+/// ONNX element-wise ops implemented by `UnaryElementwise`.
+const UNARY_FUNCTIONS: &[(&str, UnaryFunction)] = &[
+    ("Abs", UnaryFunction::Abs),
+    ("Exp", UnaryFunction::Exp),
+    ("Log", UnaryFunction::Ln),
+    ("Neg", UnaryFunction::Neg),
+    ("Reciprocal", UnaryFunction::Recip),
+    ("Sigmoid", UnaryFunction::Sigmoid),
+    ("Sqrt", UnaryFunction::Sqrt),
+    ("Tanh", UnaryFunction::Tanh),
+];
+
+pub fn unary(node: &NodeProto) -> TractResult<Box<Op>> {
+    let function = UNARY_FUNCTIONS
+        .iter()
+        .find(|(name, _)| *name == node.get_op_type())
+        .map(|(_, f)| *f)
+        .ok_or_else(|| format!("{} is not an unary function", node.get_op_type()))?;
+    Ok(Box::new(tractops::math::UnaryElementwise::new(function)))
+}
+
+pub fn clip6(node: &NodeProto) -> TractResult<Box<Op>> {
+    let min = node
+        .get_attr_opt_float("min")?
+        .unwrap_or(::std::f32::NEG_INFINITY);
+    let max = node
+        .get_attr_opt_float("max")?
+        .unwrap_or(::std::f32::INFINITY);
     Ok(Box::new(tractops::math::Clip::new(min, max)))
 }
 
+pub fn clip11(node: &NodeProto) -> TractResult<Box<Op>> {
+    Ok(Box::new(Clip11::new(
+        node.get_input_slot(1),
+        node.get_input_slot(2),
+    )))
+}
+
+/// Clip as of opset 11, with optional min and max bounds as inputs.
+#[derive(Debug, Clone, new)]
+pub struct Clip11 {
+    min_input: Option<usize>,
+    max_input: Option<usize>,
+}
+
+impl Clip11 {
+    fn bound(inputs: &[SharedTensor], slot: Option<usize>, default: f32) -> TractResult<f32> {
+        match slot {
+            Some(slot) => inputs[slot].cast_to::<f32>()?.to_scalar::<f32>(),
+            None => Ok(default),
+        }
+    }
+}
+
+impl Op for Clip11 {
+    fn name(&self) -> Cow<str> {
+        "onnx.Clip11".into()
+    }
+}
+
+impl StatelessOp for Clip11 {
+    fn eval(&self, mut inputs: TVec<SharedTensor>) -> TractResult<TVec<SharedTensor>> {
+        let min = Self::bound(&inputs, self.min_input, ::std::f32::NEG_INFINITY)?;
+        let max = Self::bound(&inputs, self.max_input, ::std::f32::INFINITY)?;
+        inputs.truncate(1);
+        tractops::math::Clip::new(min, max).eval(inputs)
+    }
+}
+
+impl InferenceRulesOp for Clip11 {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p SharedTensorsProxy,
+        outputs: &'p SharedTensorsProxy,
+    ) -> InferenceResult {
+        let bounds: Vec<usize> = self
+            .min_input
+            .iter()
+            .chain(self.max_input.iter())
+            .cloned()
+            .collect();
+        s.equals(&inputs.len, 1 + bounds.len() as i32)?;
+        s.equals(&outputs.len, 1)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[0].shape, &outputs[0].shape)?;
+        for slot in bounds {
+            s.equals(&inputs[slot].datum_type, &inputs[0].datum_type)?;
+            s.equals(&inputs[slot].rank, 0)?;
+        }
+        Ok(())
+    }
+}
+
 pub fn gemm(node: &NodeProto) -> TractResult<Box<Op>> {
     let alpha = node.get_attr_opt_float("alpha")?.unwrap_or(1.0);
     let beta = node.get_attr_opt_float("beta")?.unwrap_or(1.0);
@@ -73,3 +157,81 @@ pub fn gemm(node: &NodeProto) -> TractResult<Box<Op>> {
         alpha, beta, trans_a, trans_b, true,
     )))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pb::{AttributeProto, AttributeProto_AttributeType};
+    use ndarray::arr1;
+
+    fn node(inputs: &[&str]) -> NodeProto {
+        let mut node = NodeProto::new();
+        node.set_op_type("Clip".to_string());
+        node.set_input(inputs.iter().map(|s| s.to_string()).collect());
+        node
+    }
+
+    fn float(name: &str, v: f32) -> AttributeProto {
+        let mut attr = AttributeProto::new();
+        attr.set_name(name.to_string());
+        attr.set_field_type(AttributeProto_AttributeType::FLOAT);
+        attr.set_f(v);
+        attr
+    }
+
+    fn clip(op: &Op, inputs: TVec<SharedTensor>) -> SharedTensor {
+        op.as_stateless().unwrap().eval(inputs).unwrap().remove(0)
+    }
+
+    #[test]
+    fn clip_attributes() {
+        let mut node = node(&["x"]);
+        node.set_attribute(vec![float("min", 0.0), float("max", 6.0)].into());
+        let op = clip6(&node).unwrap();
+        let output = clip(&*op, tvec!(arr1(&[-1.0f32, 3.0, 7.0]).into()));
+        assert_eq!(output, arr1(&[0.0f32, 3.0, 6.0]).into());
+
+        node.set_attribute(vec![float("max", 6.0)].into());
+        let op = clip6(&node).unwrap();
+        let output = clip(&*op, tvec!(arr1(&[-1.0f32, 3.0, 7.0]).into()));
+        assert_eq!(output, arr1(&[-1.0f32, 3.0, 6.0]).into());
+    }
+
+    #[test]
+    fn clip_builder_depends_on_opset() {
+        let builder = crate::ops::OpBuilder::new();
+        let mut node = node(&["x", "", "max"]);
+        node.set_attribute(vec![float("min", 0.0), float("max", 6.0)].into());
+        let op = builder.build(&node, 6, false).unwrap();
+        assert!(op.downcast_ref::<tractops::math::Clip>().is_some());
+        let output = clip(&*op, tvec!(arr1(&[-1.0f32, 3.0, 7.0]).into()));
+        assert_eq!(output, arr1(&[0.0f32, 3.0, 6.0]).into());
+
+        let op = builder.build(&node, 11, false).unwrap();
+        assert!(op.downcast_ref::<Clip11>().is_some());
+        let output = clip(
+            &*op,
+            tvec!(
+                arr1(&[-1.0f32, 3.0, 7.0]).into(),
+                Tensor::from(2.0f32).into()
+            ),
+        );
+        assert_eq!(output, arr1(&[-1.0f32, 2.0, 2.0]).into());
+    }
+
+    #[test]
+    fn clip_inputs_with_max_only() {
+        let op = clip11(&node(&["x", "", "max"])).unwrap();
+        let op = op.downcast_ref::<Clip11>().unwrap();
+        assert_eq!(op.min_input, None);
+        assert_eq!(op.max_input, Some(1));
+        let output = op
+            .eval(tvec!(
+                arr1(&[-1.0f32, 3.0, 7.0]).into(),
+                Tensor::from(2.0f32).into()
+            ))
+            .unwrap()
+            .remove(0);
+        assert_eq!(output, arr1(&[-1.0f32, 2.0, 2.0]).into());
+    }
+}
@@ -1,3 +1,5 @@
+use std::ops::{Bound, RangeBounds};
+
 use crate::pb;
 use crate::pb::NodeProto;
 use tract_core::ops::prelude::*;
@@ -7,13 +9,66 @@ mod logic;
 mod math;
 mod nn;
 
-pub type OpRegister = HashMap<&'static str, fn(&NodeProto) -> TractResult<Box<Op>>>;
+pub type OpBuilderFn = fn(&NodeProto) -> TractResult<Box<Op>>;
+
+/// Op builders by name, each one valid for a range of opset versions.
+#[derive(Default)]
+pub struct OpRegister(HashMap<&'static str, Vec<((Bound<i64>, Bound<i64>), OpBuilderFn)>>);
+
+impl OpRegister {
+    /// Registers a builder valid for all opset versions.
+    pub fn insert(&mut self, name: &'static str, builder: OpBuilderFn) {
+        self.insert_versioned(name, .., builder)
+    }
+
+    /// Registers a builder valid for the opset versions in `versions`.
+    pub fn insert_versioned<R: RangeBounds<i64>>(
+        &mut self,
+        name: &'static str,
+        versions: R,
+        builder: OpBuilderFn,
+    ) {
+        let bound = |b: Bound<&i64>| match b {
+            Bound::Included(&v) => Bound::Included(v),
+            Bound::Excluded(&v) => Bound::Excluded(v),
+            Bound::Unbounded => Bound::Unbounded,
+        };
+        let versions = (bound(versions.start_bound()), bound(versions.end_bound()));
+        self.0
+            .entry(name)
+            .or_insert_with(Vec::new)
+            .push((versions, builder));
+    }
+
+    /// Finds the builder for `name` at opset version `opset`.
+    ///
+    /// Returns Ok(None) if the op is not registered at all, and an error if
+    /// it is but for other opset versions only.
+    pub fn get(&self, name: &str, opset: i64) -> TractResult<Option<OpBuilderFn>> {
+        let builders = match self.0.get(name) {
+            Some(builders) => builders,
+            None => return Ok(None),
+        };
+        match builders
+            .iter()
+            .find(|(versions, _)| versions.contains(&opset))
+        {
+            Some((_, builder)) => Ok(Some(*builder)),
+            None => bail!(
+                "No builder for {} at opset version {} (supported: {:?})",
+                name,
+                opset,
+                builders.iter().map(|b| b.0).collect::<Vec<_>>()
+            ),
+        }
+    }
+}
 
 pub struct OpBuilder(OpRegister);
 
 impl OpBuilder {
     pub fn new() -> OpBuilder {
-        let mut reg = OpRegister::new();
+        let mut reg = OpRegister::default();
         reg.insert("Cast", cast);
         reg.insert("Constant", konst);
         reg.insert("Identity", |_| {
@@ -26,13 +81,33 @@ impl OpBuilder {
         OpBuilder(reg)
     }
 
-    pub fn build(&self, pb: &NodeProto) -> TractResult<Box<Op>> {
-        match self.0.get(pb.get_op_type()) {
+    /// Whether an op type has a builder for opset version `opset`.
+    pub fn is_registered(&self, op_type: &str, opset: i64) -> bool {
+        match self.0.get(op_type, opset) {
+            Ok(Some(_)) => true,
+            _ => false,
+        }
+    }
+
+    /// Builds the op for a node of a model of opset version `opset`.
+    ///
+    /// Unknown op types are an error, unless `allow_unknown_ops` is set: they
+    /// are then built as an `UnimplementedOp` placeholder.
+    pub fn build(
+        &self,
+        pb: &NodeProto,
+        opset: i64,
+        allow_unknown_ops: bool,
+    ) -> TractResult<Box<Op>> {
+        match self.0.get(pb.get_op_type(), opset)? {
             Some(builder) => builder(pb),
-            None => Ok(Box::new(::tract_core::ops::unimpl::UnimplementedOp::new(
-                pb.get_op_type(),
-                format!("{:?}", pb),
-            ))),
+            None if allow_unknown_ops => {
+                Ok(Box::new(::tract_core::ops::unimpl::UnimplementedOp::new(
+                    pb.get_op_type(),
+                    format!("{:?}", pb),
+                )))
+            }
+            None => bail!("Unknown op type {}", pb.get_op_type()),
         }
     }
 }
@@ -50,3 +125,35 @@ fn cast(node: &NodeProto) -> TractResult<Box<Op>> {
         .ok_or_else(|| format!("Can not convert integer {} into a TensorProto_DataType", to))?;
     Ok(Box::new(::tract_core::ops::cast::Cast::new(to.tractify()?)))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tract_core::ops::math::{Abs, Neg};
+
+    fn node(op_type: &str) -> NodeProto {
+        let mut node = NodeProto::new();
+        node.set_op_type(op_type.to_string());
+        node
+    }
+
+    #[test]
+    fn versioned_builders() {
+        let mut reg = OpRegister::default();
+        reg.insert_versioned("Foo", 1..10, |_| Ok(Box::new(Abs::default())));
+        reg.insert_versioned("Foo", 10.., |_| Ok(Box::new(Neg::default())));
+        reg.insert_versioned("Bar", 1..5, |_| Ok(Box::new(Abs::default())));
+        let builder = OpBuilder(reg);
+        assert_eq!(builder.build(&node("Foo"), 9, false).unwrap().name(), "Abs");
+        assert_eq!(
+            builder.build(&node("Foo"), 10, false).unwrap().name(),
+            "Neg"
+        );
+        assert!(builder.build(&node("Bar"), 7, true).is_err());
+        assert!(builder.build(&node("Baz"), 7, false).is_err());
+        assert_eq!(
+            builder.build(&node("Baz"), 7, true).unwrap().name(),
+            "Unimplemented(Baz)"
+        );
+    }
+}
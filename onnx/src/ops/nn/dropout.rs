@@ -0,0 +1,126 @@
+use tract_core::ops as tractops;
+use tract_core::ops::prelude::*;
+
+/// Dropout in inference mode: the data goes through untouched, and the
+/// optional mask keeps every element.
+///
+/// The optional `ratio` and `training_mode` inputs are ignored.
+#[derive(Debug, Clone, new)]
+pub struct Dropout {
+    output_mask: bool,
+    bool_mask: bool,
+}
+
+impl Op for Dropout {
+    fn name(&self) -> Cow<str> {
+        "onnx.Dropout".into()
+    }
+
+    fn noutputs(&self) -> usize {
+        1 + self.output_mask as usize
+    }
+
+    fn reduce(
+        &self,
+        _inputs: TVec<&TensorFact>,
+        _outputs: TVec<&TensorFact>,
+        _phase: ReductionPhase,
+    ) -> TractResult<Option<ReducedOpRewire>> {
+        if !self.output_mask {
+            return Ok(Some(ReducedOpRewire::unary(
+                tractops::identity::Identity::default(),
+            )));
+        }
+        Ok(None)
+    }
+}
+
+impl StatelessOp for Dropout {
+    fn eval(&self, inputs: TVec<SharedTensor>) -> TractResult<TVec<SharedTensor>> {
+        let input = inputs[0].clone();
+        if !self.output_mask {
+            return Ok(tvec!(input));
+        }
+        let mask = if self.bool_mask {
+            ::ndarray::ArrayD::from_elem(input.shape(), true).into()
+        } else {
+            Tensor::from_shape_and_fn(input.datum_type(), input.shape(), |_| 1.0)?
+        };
+        Ok(tvec!(input, mask.into()))
+    }
+}
+
+impl InferenceRulesOp for Dropout {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p SharedTensorsProxy,
+        outputs: &'p SharedTensorsProxy,
+    ) -> InferenceResult {
+        s.equals(&outputs.len, self.noutputs() as i32)?;
+        s.equals_fact(&inputs[0], &outputs[0])?;
+        if self.output_mask {
+            if self.bool_mask {
+                s.equals(&outputs[1].datum_type, DatumType::Bool)?;
+            } else {
+                s.equals(&outputs[1].datum_type, &inputs[0].datum_type)?;
+            }
+            s.equals(&outputs[1].shape, &inputs[0].shape)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::{model_for_proto, LoadOptions};
+    use crate::pb;
+    use ndarray::arr1;
+    use tract_core::*;
+
+    fn value_info(name: &str) -> pb::ValueInfoProto {
+        let mut info = pb::ValueInfoProto::new();
+        info.set_name(name.to_string());
+        info
+    }
+
+    fn chain(outputs: &[&str]) -> pb::ModelProto {
+        let mut proto = pb::ModelProto::new();
+        proto.mut_graph().mut_input().push(value_info("x"));
+        let mut dropout = pb::NodeProto::new();
+        dropout.set_op_type("Dropout".to_string());
+        dropout.set_input(vec!["x".to_string()].into());
+        dropout.set_output(outputs.iter().map(|s| s.to_string()).collect());
+        proto.mut_graph().mut_node().push(dropout);
+        let mut neg = pb::NodeProto::new();
+        neg.set_op_type("Neg".to_string());
+        neg.set_input(vec!["y".to_string()].into());
+        neg.set_output(vec!["z".to_string()].into());
+        proto.mut_graph().mut_node().push(neg);
+        proto.mut_graph().mut_output().push(value_info("z"));
+        proto
+    }
+
+    #[test]
+    fn dropout_is_identity_in_a_chain() {
+        let model = model_for_proto(&chain(&["y"]), &LoadOptions::default()).unwrap();
+        let plan = SimplePlan::new(&model).unwrap();
+        let outputs = plan
+            .run(tvec!(Tensor::from(arr1(&[1.0f32, -2.0]))))
+            .unwrap();
+        assert_eq!(*outputs[0], Tensor::from(arr1(&[-1.0f32, 2.0])));
+    }
+
+    #[test]
+    fn dropout_mask_keeps_everything() {
+        let mut proto = chain(&["y", "mask"]);
+        proto.mut_graph().mut_output().push(value_info("mask"));
+        let model = model_for_proto(&proto, &LoadOptions::default()).unwrap();
+        let plan = SimplePlan::new(&model).unwrap();
+        let outputs = plan
+            .run(tvec!(Tensor::from(arr1(&[1.0f32, -2.0]))))
+            .unwrap();
+        assert_eq!(*outputs[0], Tensor::from(arr1(&[-1.0f32, 2.0])));
+        assert_eq!(*outputs[1], Tensor::from(arr1(&[true, true])));
+    }
+}
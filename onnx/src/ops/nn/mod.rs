@@ -5,12 +5,12 @@ use tract_core::ops::prelude::*;
 use crate::ops::OpRegister;
 use crate::pb::NodeProto;
 
+mod dropout;
+
 macro_rules! reduce {
     ($id:ident) => {
         |node| {
-            let axes = node
-                .get_attr_opt_ints("axes")?
-                .map(|axes| axes.iter().map(|&i| i as usize).collect());
+            let axes = node.get_attr_opt_ints("axes")?.map(|axes| axes.to_vec());
             let keep_dims = node.get_attr_opt_int("keepdims")?.unwrap_or(1i64) == 1;
             Ok(Box::new(tractops::nn::Reduce::new(
                 axes,
@@ -27,9 +27,8 @@ pub fn register_all_ops(reg: &mut OpRegister) {
     reg.insert("AveragePool", average_pool);
     reg.insert("BatchNormalization", batch_normalization);
     reg.insert("Conv", conv);
-    reg.insert("Dropout", |_| {
-        Ok(Box::new(tractops::identity::Identity::default()))
-    });
+    reg.insert_versioned("Dropout", ..10, |node| dropout(node, false));
+    reg.insert_versioned("Dropout", 10.., |node| dropout(node, true));
     reg.insert("Elu", elu);
     reg.insert("GlobalAveragePool", |_| {
         Ok(Box::new(tractops::nn::GlobalAvgPool::default()))
@@ -43,6 +42,7 @@ pub fn register_all_ops(reg: &mut OpRegister) {
     reg.insert("LeakyRelu", leaky_relu);
     reg.insert("LogSoftmax", layer_log_soft_max);
     reg.insert("LRN", lrn);
+    reg.insert("LSTM", lstm);
     reg.insert("MaxPool", max_pool);
     reg.insert("ParametricSoftplus", parametric_softplus);
     reg.insert("PRelu", |_| Ok(Box::new(Prelu::default())));
@@ -60,10 +60,8 @@ pub fn register_all_ops(reg: &mut OpRegister) {
     reg.insert("ScaledTanh", scaled_tanh);
     reg.insert("ThresholdedRelu", thresholded_relu);
     reg.insert("Selu", selu);
-    reg.insert("Sigmoid", |_| {
-        Ok(Box::new(tractops::nn::Sigmoid::default()))
-    });
-    reg.insert("Softmax", layer_soft_max);
+    reg.insert_versioned("Softmax", 1..13, layer_soft_max);
+    reg.insert_versioned("Softmax", 13.., softmax);
     reg.insert("Softplus", |_| {
         Ok(Box::new(tractops::nn::Softplus::default()))
     });
@@ -108,16 +106,33 @@ pub fn arg_max_min(node: &NodeProto) -> TractResult<Box<Op>> {
         .map(|i| i as usize)
         .unwrap_or(0);
     let keepdims = node.get_attr_opt_int("keepdims")?.unwrap_or(1i64) == 1;
-    Ok(Box::new(tractops::nn::ArgMaxMin::new(max, axis, keepdims)))
+    let select_last_index = node.get_attr_opt_int("select_last_index")?.unwrap_or(0i64) == 1;
+    Ok(Box::new(tractops::nn::ArgMaxMin::new(
+        max,
+        axis,
+        keepdims,
+        select_last_index,
+    )))
 }
 
 pub fn batch_normalization(node: &NodeProto) -> TractResult<Box<Op>> {
     let epsilon = node.get_attr_opt_float("epsilon")?.unwrap_or(1e-5);
-    let spatial = node.get_attr_opt_int("spatial")?.unwrap_or(0);
-    assert_eq!(spatial, 0);
+    let momentum = node.get_attr_opt_float("momentum")?.unwrap_or(0.9);
+    let spatial = node.get_attr_opt_int("spatial")?.unwrap_or(1);
+    if spatial == 0 {
+        bail!("BatchNormalization with spatial=0 is not supported")
+    }
+    // training mode is an attribute from opset 14, before that it is
+    // requested by asking for the running mean and variance outputs
+    let training = node.get_attr_opt_int("training_mode")?.unwrap_or(0) != 0
+        || node.get_output().iter().skip(1).any(|o| !o.is_empty());
+    if training {
+        bail!("BatchNormalization in training mode is not supported")
+    }
     Ok(Box::new(tractops::nn::BatchNorm::new(
         DataFormat::NCHW,
         epsilon,
+        momentum,
         spatial != 0,
     )))
 }
@@ -156,6 +171,20 @@ pub fn average_pool(node: &NodeProto) -> TractResult<Box<Op>> {
     )))
 }
 
+/// Builds a Dropout, whose mask is a boolean tensor from opset 10 on, and of
+/// the input type before.
+pub fn dropout(node: &NodeProto, bool_mask: bool) -> TractResult<Box<Op>> {
+    let output_mask = node
+        .get_output()
+        .get(1)
+        .map(|o| !o.is_empty())
+        .unwrap_or(false);
+    if !output_mask && node.get_input().len() == 1 {
+        return Ok(Box::new(tractops::identity::Identity::default()));
+    }
+    Ok(Box::new(dropout::Dropout::new(output_mask, bool_mask)))
+}
+
 pub fn elu(node: &NodeProto) -> TractResult<Box<Op>> {
     let alpha = node.get_attr_opt_float("alpha")?.unwrap_or(1.0);
     Ok(Box::new(tractops::nn::Elu::new(alpha)))
@@ -196,6 +225,11 @@ pub fn layer_soft_max(node: &NodeProto) -> TractResult<Box<Op>> {
     Ok(Box::new(tractops::nn::LayerSoftmax::new(axis)))
 }
 
+pub fn softmax(node: &NodeProto) -> TractResult<Box<Op>> {
+    let axis = node.get_attr_opt_int("axis")?.unwrap_or(-1);
+    Ok(Box::new(tractops::nn::Softmax::new(axis)))
+}
+
 pub fn leaky_relu(node: &NodeProto) -> TractResult<Box<Op>> {
     let alpha = node.get_attr_opt_float("alpha")?.unwrap_or(0.01);
     Ok(Box::new(tractops::nn::LeakyRelu::new(alpha)))
@@ -209,6 +243,54 @@ pub fn lrn(node: &NodeProto) -> TractResult<Box<Op>> {
     Ok(Box::new(tractops::nn::Lrn::new(alpha, beta, bias, size)))
 }
 
+pub fn lstm(node: &NodeProto) -> TractResult<Box<Op>> {
+    use tractops::nn::{LstmActivation, LstmDirection};
+    let hidden_size = node.get_attr_int("hidden_size")? as usize;
+    let direction = match node.get_attr_opt_str("direction")? {
+        None | Some("forward") => LstmDirection::Forward,
+        Some("reverse") => LstmDirection::Reverse,
+        Some("bidirectional") => LstmDirection::Bidirectional,
+        Some(other) => bail!("Unsupported direction for LSTM: {}", other),
+    };
+    let activation = |name: &str| match name {
+        "Sigmoid" => Ok(LstmActivation::Sigmoid),
+        "Tanh" => Ok(LstmActivation::Tanh),
+        "Relu" => Ok(LstmActivation::Relu),
+        other => Err(format!("Unsupported activation for LSTM: {}", other)),
+    };
+    let activations = match node.get_attr_opt_strings("activations")? {
+        Some(names) => {
+            if names.len() != 3 * direction.num_directions() {
+                bail!(
+                    "LSTM expects {} activations, got {}",
+                    3 * direction.num_directions(),
+                    names.len()
+                )
+            }
+            [
+                activation(names[0])?,
+                activation(names[1])?,
+                activation(names[2])?,
+            ]
+        }
+        None => [
+            LstmActivation::Sigmoid,
+            LstmActivation::Tanh,
+            LstmActivation::Tanh,
+        ],
+    };
+    Ok(Box::new(tractops::nn::Lstm::new(
+        hidden_size,
+        direction,
+        activations,
+        node.get_input_slot(3),
+        node.get_input_slot(4),
+        node.get_input_slot(5),
+        node.get_input_slot(6),
+        node.get_input_slot(7),
+    )))
+}
+
 pub fn max_pool(node: &NodeProto) -> TractResult<Box<Op>> {
     let kernel_shape: TVec<usize> = node
         .get_attr_ints("kernel_shape")?
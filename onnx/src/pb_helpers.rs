@@ -2,6 +2,19 @@ use crate::pb::*;
 use tract_core::*;
 
 impl NodeProto {
+    /// Returns the slot the input declared at position `ix` is wired to, or
+    /// None if this optional input is absent.
+    ///
+    /// Absent inputs are left out of the wiring, so the slots of the
+    /// following inputs are shifted.
+    pub fn get_input_slot(&self, ix: usize) -> Option<usize> {
+        let inputs = self.get_input();
+        if inputs.get(ix).map(|name| name.is_empty()).unwrap_or(true) {
+            return None;
+        }
+        Some(inputs[..ix].iter().filter(|name| !name.is_empty()).count())
+    }
+
     fn get_attr_opt(&self, name: &str) -> TractResult<Option<&AttributeProto>> {
         Ok(self.get_attribute().iter().find(|a| a.get_name() == name))
     }
@@ -64,6 +77,18 @@ impl NodeProto {
         })?)
     }
 
+    pub fn get_attr_opt_strings(&self, name: &str) -> TractResult<Option<Vec<&str>>> {
+        match self.get_attr_opt_with_type(name, AttributeProto_AttributeType::STRINGS)? {
+            Some(attr) => Ok(Some(
+                attr.get_strings()
+                    .iter()
+                    .map(|s| ::std::str::from_utf8(s))
+                    .collect::<Result<_, _>>()?,
+            )),
+            None => Ok(None),
+        }
+    }
+
     pub fn get_attr_opt_int(&self, name: &str) -> TractResult<Option<i64>> {
         match self.get_attr_opt_with_type(name, AttributeProto_AttributeType::INT)? {
             Some(attr) => Ok(Some(attr.get_i())),
@@ -100,6 +125,13 @@ impl NodeProto {
         })?)
     }
 
+    pub fn get_attr_opt_floats(&self, name: &str) -> TractResult<Option<&[f32]>> {
+        match self.get_attr_opt_with_type(name, AttributeProto_AttributeType::FLOATS)? {
+            Some(attr) => Ok(Some(attr.get_floats())),
+            None => Ok(None),
+        }
+    }
+
     pub fn get_attr_opt_ints(&self, name: &str) -> TractResult<Option<&[i64]>> {
         match self.get_attr_opt_with_type(name, AttributeProto_AttributeType::INTS)? {
             Some(attr) => Ok(Some(attr.get_ints())),
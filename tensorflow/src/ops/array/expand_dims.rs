@@ -23,10 +23,7 @@ impl Op for ExpandDims {
             if let Some(dims) = dims.concretize() {
                 let dims = dims.cast_to::<i64>()?;
                 let op = ::tract_core::ops::array::AddDims::new(
-                    dims.to_array_view::<i64>()?
-                        .iter()
-                        .map(|&i| i as usize)
-                        .collect(),
+                    dims.to_array_view::<i64>()?.iter().cloned().collect(),
                 );
                 return Ok(Some(ReducedOpRewire::unary(op)));
             }
@@ -41,12 +38,9 @@ impl StatelessOp for ExpandDims {
         let data = data.to_array::<f32>()?;
         let dims = dims.to_array_view::<i32>()?;
         let mut shape = data.shape().to_vec();
-        for d in dims.iter() {
-            if *d >= 0 {
-                shape.insert(*d as usize, 1);
-            } else {
-                Err(format!("unimplemented ExpandDims with negative parameter"))?
-            }
+        for &d in dims.iter() {
+            let axis = normalize_axis(d as i64, shape.len() + 1)?;
+            shape.insert(axis, 1);
         }
         Ok(tvec![Tensor::from(data.into_shape(shape)?).into()])
     }
@@ -69,21 +63,17 @@ impl InferenceRulesOp for ExpandDims {
         s.equals(&dims.rank, 0)?;
         s.equals(&data.datum_type, &output.datum_type)?;
         s.equals_zero(data.rank.bex() + 1 - &output.rank)?;
-        s.given(&dims.value, move |s, index| {
-            let index = index.to_scalar::<i32>()? as usize;
-
+        s.given_2(&dims.value, &data.rank, move |s, index, rank| {
+            let rank = rank as usize;
+            let index = normalize_axis(index.to_scalar::<i32>()? as i64, rank + 1)?;
             for i in 0..index {
                 s.equals(&output.shape[i], &data.shape[i])?;
             }
-
             s.equals(output.shape[index].bex(), 1i32.to_dim().bex())?;
-
-            s.given(&data.rank, move |s, rank| {
-                for i in index..(rank as usize) {
-                    s.equals(&output.shape[i + 1], &data.shape[i])?;
-                }
-                Ok(())
-            })
+            for i in index..rank {
+                s.equals(&output.shape[i + 1], &data.shape[i])?;
+            }
+            Ok(())
         })
     }
 }
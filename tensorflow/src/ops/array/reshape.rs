@@ -67,7 +67,7 @@ impl<T: Datum> InferenceRulesOp for Reshape<T> {
             &inputs[1].value,
             move |solver, shape, dims| {
                 let dims = dims.to_array_view::<i32>().unwrap(); // checked
-                if shape.iter().all(|d| !d.is_stream()) {
+                if shape.iter().all(|d| d.as_const().is_some()) {
                     let len = shape
                         .iter()
                         .map(|d| d.as_const().unwrap() as usize)
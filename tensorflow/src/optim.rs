@@ -60,8 +60,11 @@ fn undo_conv1d_as_conv2d(model: &mut Model, node_id: usize) -> TractResult<bool>
         let succ_node = some_or_ok_false!(model.single_succ(node_id)?);
         let rm_dim_op = some_or_ok_false!(succ_node.op_as::<RmDims>());
         let conv_op = some_or_ok_false!(model.node(node_id).op_as::<ConvUnary>());
-        if add_dim_op.axes.len() == 1 && rm_dim_op.axes == add_dim_op.axes {
-            let axis = add_dim_op.axes[0];
+        if add_dim_op.axes.len() == 1
+            && add_dim_op.axes[0] >= 0
+            && rm_dim_op.axes == [add_dim_op.axes[0] as usize]
+        {
+            let axis = add_dim_op.axes[0] as usize;
             conv_op.rm_dummy_axis(axis)?
         } else {
             None
@@ -174,5 +177,4 @@ mod test {
         println!("{:#?}", optimized);
         assert_eq!(optimized.eval_order().unwrap().len(), 2);
     }
-
 }